@@ -0,0 +1,135 @@
+use crate::cis::{Error, LocalizableMessage, Session};
+use crate::common::redacted;
+use chrono::prelude::*;
+use reqwest::Method;
+use std::fmt;
+
+const JOBS: &str = "/appliance/recovery/backup/job";
+
+// Protocol of a backup location
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LocationType {
+    Ftp,
+    Http,
+    Ftps,
+    Https,
+    Scp,
+    Sftp,
+    Nfs,
+    Smb,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Backup of the appliance, parts are e.g. "common" and "seat" (statistics,
+// events and tasks). The backup password encrypts the backup.
+#[derive(Serialize, Clone)]
+pub struct BackupRequest {
+    pub parts: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_password: Option<String>,
+    pub location_type: LocationType,
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl fmt::Debug for BackupRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackupRequest")
+            .field("parts", &self.parts)
+            .field("backup_password", &redacted(&self.backup_password))
+            .field("location_type", &self.location_type)
+            .field("location", &self.location)
+            .field("location_user", &self.location_user)
+            .field("location_password", &redacted(&self.location_password))
+            .field("comment", &self.comment)
+            .finish()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackupState {
+    Failed,
+    Inprogress,
+    None,
+    Succeeded,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackupType {
+    Scheduled,
+    Manual,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Status of a backup job, progress is given in percent and size in MB
+#[derive(Deserialize, Debug, Clone)]
+pub struct BackupJobStatus {
+    pub id: String,
+    pub state: BackupState,
+    #[serde(default)]
+    pub progress: i64,
+    pub start_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub location: String,
+    pub location_type: LocationType,
+    #[serde(rename = "type")]
+    pub backup_type: BackupType,
+    #[serde(default)]
+    pub messages: Vec<LocalizableMessage>,
+    pub size: Option<i64>,
+    pub duration: Option<i64>,
+    pub cancelable: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReturnStatus {
+    Failed,
+    Warning,
+    Ok,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReturnResult {
+    pub status: ReturnStatus,
+    #[serde(default)]
+    pub messages: Vec<LocalizableMessage>,
+}
+
+// List the identifiers of all backup jobs
+pub async fn list(session: &Session) -> Result<Vec<String>, Error> {
+    let request = session.request(Method::GET, JOBS);
+    session.send(request).await
+}
+
+pub async fn get(session: &Session, job: &str) -> Result<BackupJobStatus, Error> {
+    let request = session.request(Method::GET, &format!("{}/{}", JOBS, job));
+    session.send(request).await
+}
+
+// Start a backup job
+pub async fn create(session: &Session, piece: &BackupRequest) -> Result<BackupJobStatus, Error> {
+    let request = session.request(Method::POST, JOBS);
+    session.send(session.body(request, "piece", piece)).await
+}
+
+pub async fn cancel(session: &Session, job: &str) -> Result<ReturnResult, Error> {
+    let request = session.path_action(&format!("{}/{}", JOBS, job), "cancel");
+    session.send(request).await
+}
@@ -0,0 +1,56 @@
+use crate::cis::{Error, Session};
+use reqwest::Method;
+
+// Health level of an appliance component, gray means unknown
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLevel {
+    Green,
+    Yellow,
+    Orange,
+    Red,
+    Gray,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Component of the appliance with a health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    System,
+    Database,
+    Storage,
+    Swap,
+    Memory,
+    Load,
+    Applmgmt,
+    SoftwarePackages,
+}
+
+impl Component {
+    fn path(self) -> &'static str {
+        match self {
+            Component::System => "system",
+            Component::Database => "database",
+            Component::Storage => "storage",
+            Component::Swap => "swap",
+            Component::Memory => "mem",
+            Component::Load => "load",
+            Component::Applmgmt => "applmgmt",
+            Component::SoftwarePackages => "software-packages",
+        }
+    }
+}
+
+pub async fn get(session: &Session, component: Component) -> Result<HealthLevel, Error> {
+    let request = session.request(
+        Method::GET,
+        &format!("/appliance/health/{}", component.path()),
+    );
+    session.send(request).await
+}
+
+// Overall health of the appliance
+pub async fn system(session: &Session) -> Result<HealthLevel, Error> {
+    get(session, Component::System).await
+}
@@ -0,0 +1,10 @@
+// Modules for the management of the vCenter Server Appliance
+pub mod backup;
+pub mod health;
+pub mod networking;
+pub mod ntp;
+pub mod services;
+pub mod shutdown;
+pub mod system;
+pub mod timesync;
+pub mod update;
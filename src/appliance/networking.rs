@@ -0,0 +1,232 @@
+use crate::cis::{Error, Session};
+use crate::common::{deserialize_map, redacted, MapResponse};
+use reqwest::Method;
+use std::collections::HashMap;
+use std::fmt;
+
+// How the DNS servers are configured
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsServerMode {
+    Dhcp,
+    IsStatic,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DnsServerConfig {
+    pub mode: DnsServerMode,
+    #[serde(default)]
+    pub servers: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DnsInfo {
+    #[serde(default)]
+    pub mode: String,
+    #[serde(default)]
+    pub hostname: String,
+    #[serde(default)]
+    pub servers: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceStatus {
+    Down,
+    Up,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// How the IPv4 address of an interface is configured
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Ipv4Mode {
+    Dhcp,
+    Static,
+    Unconfigured,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// IPv4 configuration of an interface, address, prefix and gateway are only
+// used in static mode
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Ipv4Config {
+    pub mode: Ipv4Mode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_gateway: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Ipv4Info {
+    #[serde(default)]
+    pub configurable: bool,
+    pub mode: Ipv4Mode,
+    pub address: Option<String>,
+    pub prefix: Option<u8>,
+    pub default_gateway: Option<String>,
+}
+
+// Network interface of the appliance, e.g. "nic0"
+#[derive(Deserialize, Debug, Clone)]
+pub struct InterfaceInfo {
+    pub name: String,
+    pub status: InterfaceStatus,
+    #[serde(default)]
+    pub mac: String,
+    pub ipv4: Option<Ipv4Info>,
+    pub ipv6: Option<serde_json::Value>,
+}
+
+// Network configuration of the appliance
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkingInfo {
+    pub dns: DnsInfo,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub interfaces: HashMap<String, InterfaceInfo>,
+    pub vcenter_base_url: Option<String>,
+}
+
+// Protocol a proxy is used for
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProxyProtocol {
+    #[display(fmt = "http")]
+    Http,
+    #[display(fmt = "https")]
+    Https,
+    #[display(fmt = "ftp")]
+    Ftp,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ProxyConfig {
+    #[serde(default)]
+    pub server: String,
+    #[serde(default)]
+    pub port: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+impl fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("server", &self.server)
+            .field("port", &self.port)
+            .field("username", &self.username)
+            .field("password", &redacted(&self.password))
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+pub async fn get(session: &Session) -> Result<NetworkingInfo, Error> {
+    let request = session.request(Method::GET, "/appliance/networking");
+    session.send(request).await
+}
+
+pub async fn list_interfaces(session: &Session) -> Result<Vec<InterfaceInfo>, Error> {
+    let request = session.request(Method::GET, "/appliance/networking/interfaces");
+    session.send(request).await
+}
+
+pub async fn get_interface(session: &Session, interface: &str) -> Result<InterfaceInfo, Error> {
+    let request = session.request(
+        Method::GET,
+        &format!("/appliance/networking/interfaces/{}", interface),
+    );
+    session.send(request).await
+}
+
+pub async fn set_ipv4(
+    session: &Session,
+    interface: &str,
+    config: &Ipv4Config,
+) -> Result<(), Error> {
+    let request = session.request(
+        Method::PUT,
+        &format!("/appliance/networking/interfaces/{}/ipv4", interface),
+    );
+    session
+        .send_empty(session.body(request, "config", config))
+        .await
+}
+
+pub async fn dns_servers(session: &Session) -> Result<DnsServerConfig, Error> {
+    let request = session.request(Method::GET, "/appliance/networking/dns/servers");
+    session.send(request).await
+}
+
+pub async fn set_dns_servers(session: &Session, config: &DnsServerConfig) -> Result<(), Error> {
+    let request = session.request(Method::PUT, "/appliance/networking/dns/servers");
+    session
+        .send_empty(session.body(request, "config", config))
+        .await
+}
+
+pub async fn hostname(session: &Session) -> Result<String, Error> {
+    let request = session.request(Method::GET, "/appliance/networking/dns/hostname");
+    session.send(request).await
+}
+
+pub async fn set_hostname(session: &Session, name: &str) -> Result<(), Error> {
+    let request = session.request(Method::PUT, "/appliance/networking/dns/hostname");
+    session
+        .send_empty(session.body(request, "name", &name))
+        .await
+}
+
+// Configured proxies keyed by protocol ("http", "https" or "ftp")
+pub async fn proxies(session: &Session) -> Result<HashMap<String, ProxyConfig>, Error> {
+    let request = session.request(Method::GET, "/appliance/networking/proxy");
+    let proxies: MapResponse<ProxyConfig> = session.send(request).await?;
+    Ok(proxies.into())
+}
+
+pub async fn set_proxy(
+    session: &Session,
+    protocol: ProxyProtocol,
+    config: &ProxyConfig,
+) -> Result<(), Error> {
+    let request = session.request(
+        Method::PUT,
+        &format!("/appliance/networking/proxy/{}", protocol),
+    );
+    session
+        .send_empty(session.body(request, "config", config))
+        .await
+}
+
+pub async fn delete_proxy(session: &Session, protocol: ProxyProtocol) -> Result<(), Error> {
+    let request = session.request(
+        Method::DELETE,
+        &format!("/appliance/networking/proxy/{}", protocol),
+    );
+    session.send_empty(request).await
+}
+
+// Hosts which are reached without proxy
+pub async fn no_proxy(session: &Session) -> Result<Vec<String>, Error> {
+    let request = session.request(Method::GET, "/appliance/networking/noproxy");
+    session.send(request).await
+}
+
+pub async fn set_no_proxy(session: &Session, servers: &[String]) -> Result<(), Error> {
+    let request = session.request(Method::PUT, "/appliance/networking/noproxy");
+    session
+        .send_empty(session.body(request, "servers", &servers))
+        .await
+}
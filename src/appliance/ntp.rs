@@ -0,0 +1,44 @@
+use crate::cis::{Error, LocalizableMessage, Session};
+use reqwest::Method;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ServerStatus {
+    ServerReachable,
+    ServerUnreachable,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Outcome of testing an NTP server
+#[derive(Deserialize, Debug, Clone)]
+pub struct TestRunStatus {
+    #[serde(default)]
+    pub server: String,
+    pub status: ServerStatus,
+    pub message: LocalizableMessage,
+}
+
+#[derive(Serialize)]
+struct ServersParams<'a> {
+    servers: &'a [String],
+}
+
+// Configured NTP servers, they are only used if the time synchronization mode is NTP
+pub async fn get(session: &Session) -> Result<Vec<String>, Error> {
+    let request = session.request(Method::GET, "/appliance/ntp");
+    session.send(request).await
+}
+
+pub async fn set(session: &Session, servers: &[String]) -> Result<(), Error> {
+    let request = session.request(Method::PUT, "/appliance/ntp");
+    let request = session.json(request, &ServersParams { servers });
+    session.send_empty(request).await
+}
+
+// Check whether the NTP servers are reachable
+pub async fn test(session: &Session, servers: &[String]) -> Result<Vec<TestRunStatus>, Error> {
+    let request = session.action(Method::POST, "/appliance/ntp", "test");
+    let request = session.json(request, &ServersParams { servers });
+    session.send(request).await
+}
@@ -0,0 +1,52 @@
+use crate::cis::{Error, Session};
+use crate::common::MapResponse;
+use reqwest::Method;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum State {
+    Starting,
+    Stopping,
+    Started,
+    Stopped,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Operating system service of the appliance
+#[derive(Deserialize, Debug, Clone)]
+pub struct ServiceInfo {
+    #[serde(default)]
+    pub description: String,
+    pub state: State,
+}
+
+// Services of the appliance keyed by their name
+pub async fn list(session: &Session) -> Result<HashMap<String, ServiceInfo>, Error> {
+    let request = session.request(Method::GET, "/appliance/services");
+    let services: MapResponse<ServiceInfo> = session.send(request).await?;
+    Ok(services.into())
+}
+
+pub async fn get(session: &Session, service: &str) -> Result<ServiceInfo, Error> {
+    let request = session.request(Method::GET, &format!("/appliance/services/{}", service));
+    session.send(request).await
+}
+
+pub async fn start(session: &Session, service: &str) -> Result<(), Error> {
+    service_action(session, service, "start").await
+}
+
+pub async fn stop(session: &Session, service: &str) -> Result<(), Error> {
+    service_action(session, service, "stop").await
+}
+
+pub async fn restart(session: &Session, service: &str) -> Result<(), Error> {
+    service_action(session, service, "restart").await
+}
+
+async fn service_action(session: &Session, service: &str, action: &str) -> Result<(), Error> {
+    let request = session.path_action(&format!("/appliance/services/{}", service), action);
+    session.send_empty(request).await
+}
@@ -0,0 +1,52 @@
+use crate::cis::{Error, Session};
+use chrono::prelude::*;
+use reqwest::Method;
+
+// Pending shutdown of the appliance
+#[derive(Deserialize, Debug, Clone)]
+pub struct ShutdownConfig {
+    pub shutdown_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub action: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+struct ShutdownParams<'a> {
+    delay: u64,
+    reason: &'a str,
+}
+
+// Pending shutdown or reboot, the action is empty if there is none
+pub async fn get(session: &Session) -> Result<ShutdownConfig, Error> {
+    let request = session.request(Method::GET, "/appliance/shutdown");
+    session.send(request).await
+}
+
+// Power off the appliance after the delay in minutes
+pub async fn poweroff(session: &Session, delay: u64, reason: &str) -> Result<(), Error> {
+    shutdown_action(session, "poweroff", delay, reason).await
+}
+
+// Reboot the appliance after the delay in minutes
+pub async fn reboot(session: &Session, delay: u64, reason: &str) -> Result<(), Error> {
+    shutdown_action(session, "reboot", delay, reason).await
+}
+
+// Cancel a pending shutdown or reboot
+pub async fn cancel(session: &Session) -> Result<(), Error> {
+    let request = session.action(Method::POST, "/appliance/shutdown", "cancel");
+    session.send_empty(request).await
+}
+
+async fn shutdown_action(
+    session: &Session,
+    action: &str,
+    delay: u64,
+    reason: &str,
+) -> Result<(), Error> {
+    let request = session.action(Method::POST, "/appliance/shutdown", action);
+    let request = session.json(request, &ShutdownParams { delay, reason });
+    session.send_empty(request).await
+}
@@ -0,0 +1,45 @@
+use crate::cis::{Error, Session};
+use reqwest::Method;
+
+// Version of the appliance, e.g. version "7.0.3.01000" of product "VMware vCenter Server"
+#[derive(Deserialize, Debug, Clone)]
+pub struct VersionInfo {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub product: String,
+    #[serde(default)]
+    pub build: String,
+    #[serde(default, rename = "type")]
+    pub appliance_type: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub releasedate: String,
+    #[serde(default)]
+    pub install_time: String,
+}
+
+pub async fn version(session: &Session) -> Result<VersionInfo, Error> {
+    let request = session.request(Method::GET, "/appliance/system/version");
+    session.send(request).await
+}
+
+// Time since the appliance was booted in seconds
+pub async fn uptime(session: &Session) -> Result<f64, Error> {
+    let request = session.request(Method::GET, "/appliance/system/uptime");
+    session.send(request).await
+}
+
+// Time zone of the appliance, e.g. "UTC" or "Europe/Berlin"
+pub async fn timezone(session: &Session) -> Result<String, Error> {
+    let request = session.request(Method::GET, "/appliance/system/time/timezone");
+    session.send(request).await
+}
+
+pub async fn set_timezone(session: &Session, name: &str) -> Result<(), Error> {
+    let request = session.request(Method::PUT, "/appliance/system/time/timezone");
+    session
+        .send_empty(session.body(request, "name", &name))
+        .await
+}
@@ -0,0 +1,26 @@
+use crate::cis::{Error, Session};
+use reqwest::Method;
+
+// How the time of the appliance is synchronized, HOST uses VMware Tools
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimeSyncMode {
+    Disabled,
+    Ntp,
+    Host,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+pub async fn get(session: &Session) -> Result<TimeSyncMode, Error> {
+    let request = session.request(Method::GET, "/appliance/timesync");
+    session.send(request).await
+}
+
+pub async fn set(session: &Session, mode: TimeSyncMode) -> Result<(), Error> {
+    let request = session.request(Method::PUT, "/appliance/timesync");
+    session
+        .send_empty(session.body(request, "mode", &mode))
+        .await
+}
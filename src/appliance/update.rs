@@ -0,0 +1,191 @@
+use crate::cis::{Error, LocalizableMessage, Session};
+use crate::common::serialize_map;
+use chrono::prelude::*;
+use reqwest::Method;
+use std::collections::HashMap;
+
+// State of the appliance update
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum State {
+    UpToDate,
+    UpdatesPending,
+    StageInProgress,
+    InstallInProgress,
+    InstallFailed,
+    RollbackInProgress,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Info {
+    pub state: State,
+    pub task: Option<serde_json::Value>,
+    #[serde(default)]
+    pub version: String,
+    pub latest_query_time: Option<DateTime<Utc>>,
+}
+
+// Where pending updates are looked up
+#[derive(Serialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SourceType {
+    #[display(fmt = "LAST_CHECK")]
+    LastCheck,
+    #[display(fmt = "LOCAL")]
+    Local,
+    #[display(fmt = "LOCAL_AND_ONLINE")]
+    LocalAndOnline,
+}
+
+// Pending update, the size is given in MB
+#[derive(Deserialize, Debug, Clone)]
+pub struct Summary {
+    #[serde(default)]
+    pub version: String,
+    pub name: LocalizableMessage,
+    pub description: LocalizableMessage,
+    #[serde(default)]
+    pub priority: String,
+    #[serde(default)]
+    pub severity: String,
+    #[serde(default)]
+    pub update_type: String,
+    pub release_date: DateTime<Utc>,
+    #[serde(default)]
+    pub reboot_required: bool,
+    #[serde(default)]
+    pub size: u64,
+}
+
+// Issues found by a precheck, errors prevent the installation
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Notifications {
+    #[serde(default)]
+    pub info: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub warnings: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub errors: Vec<serde_json::Value>,
+}
+
+// Result of a precheck, the questions have to be answered in the user data
+// of the installation
+#[derive(Deserialize, Debug, Clone)]
+pub struct PrecheckResult {
+    pub check_time: DateTime<Utc>,
+    pub estimated_time_to_install: Option<i64>,
+    pub estimated_time_to_rollback: Option<i64>,
+    #[serde(default)]
+    pub reboot_required: bool,
+    pub issues: Option<Notifications>,
+    #[serde(default)]
+    pub questions: Vec<serde_json::Value>,
+}
+
+// Update which is downloaded and ready to be installed
+#[derive(Deserialize, Debug, Clone)]
+pub struct StagedInfo {
+    #[serde(default)]
+    pub staging_complete: bool,
+    #[serde(default)]
+    pub version: String,
+    pub name: LocalizableMessage,
+    pub release_date: DateTime<Utc>,
+    #[serde(default)]
+    pub reboot_required: bool,
+    #[serde(default)]
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+struct UserDataParams<'a> {
+    #[serde(serialize_with = "serialize_map")]
+    user_data: &'a HashMap<String, String>,
+}
+
+pub async fn get(session: &Session) -> Result<Info, Error> {
+    let request = session.request(Method::GET, "/appliance/update");
+    session.send(request).await
+}
+
+// Cancel the running update operation
+pub async fn cancel(session: &Session) -> Result<(), Error> {
+    let request = session.action(Method::POST, "/appliance/update", "cancel");
+    session.send_empty(request).await
+}
+
+// List the pending updates, the url is used for online sources and defaults
+// to the configured repository
+pub async fn list_pending(
+    session: &Session,
+    source_type: SourceType,
+    url: Option<&str>,
+) -> Result<Vec<Summary>, Error> {
+    let mut request = session
+        .request(Method::GET, "/appliance/update/pending")
+        .query(&[("source_type", source_type.to_string())]);
+    if let Some(url) = url {
+        request = request.query(&[("url", url)]);
+    }
+    session.send(request).await
+}
+
+pub async fn precheck(session: &Session, version: &str) -> Result<PrecheckResult, Error> {
+    let request = session.action(Method::POST, &pending(version), "precheck");
+    session.send(request).await
+}
+
+// Validate the user data for the installation of an update
+pub async fn validate(
+    session: &Session,
+    version: &str,
+    user_data: &HashMap<String, String>,
+) -> Result<Notifications, Error> {
+    let request = session.action(Method::POST, &pending(version), "validate");
+    let request = session.json(request, &UserDataParams { user_data });
+    session.send(request).await
+}
+
+// Download an update without installing it
+pub async fn stage(session: &Session, version: &str) -> Result<(), Error> {
+    let request = session.action(Method::POST, &pending(version), "stage");
+    session.send_empty(request).await
+}
+
+// Install a staged update
+pub async fn install(
+    session: &Session,
+    version: &str,
+    user_data: &HashMap<String, String>,
+) -> Result<(), Error> {
+    let request = session.action(Method::POST, &pending(version), "install");
+    let request = session.json(request, &UserDataParams { user_data });
+    session.send_empty(request).await
+}
+
+pub async fn stage_and_install(
+    session: &Session,
+    version: &str,
+    user_data: &HashMap<String, String>,
+) -> Result<(), Error> {
+    let request = session.action(Method::POST, &pending(version), "stage-and-install");
+    let request = session.json(request, &UserDataParams { user_data });
+    session.send_empty(request).await
+}
+
+pub async fn staged(session: &Session) -> Result<StagedInfo, Error> {
+    let request = session.request(Method::GET, "/appliance/update/staged");
+    session.send(request).await
+}
+
+// Delete the staged update
+pub async fn unstage(session: &Session) -> Result<(), Error> {
+    let request = session.request(Method::DELETE, "/appliance/update/staged");
+    session.send_empty(request).await
+}
+
+fn pending(version: &str) -> String {
+    format!("/appliance/update/pending/{}", version)
+}
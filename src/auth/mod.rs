@@ -0,0 +1,37 @@
+// Login with tokens of federated identity providers
+pub mod oauth2;
+// Login with SAML tokens issued by the vCenter STS
+pub mod saml;
+
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::Request;
+use saml::SamlToken;
+
+// Credentials a session is created with, retained for auto relogin
+#[derive(Clone)]
+pub(crate) enum Credentials {
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    Saml(SamlToken),
+}
+
+impl Credentials {
+    // Add the authorization header of the credentials to a session create request
+    pub(crate) fn authorize(&self, mut request: Request) -> Request {
+        let value = match self {
+            Credentials::Basic { username, password } => basic_authorization(username, password),
+            Credentials::Saml(token) => token.authorization(request.method(), request.url()),
+        };
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }
+        request
+    }
+}
+
+fn basic_authorization(username: &str, password: &Option<String>) -> String {
+    let credentials = format!("{}:{}", username, password.as_deref().unwrap_or(""));
+    format!("Basic {}", base64::encode(credentials))
+}
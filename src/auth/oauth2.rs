@@ -0,0 +1,146 @@
+use super::saml::SamlToken;
+use crate::cis::{Error, Session};
+use crate::common::REDACTED;
+use reqwest::Method;
+use std::fmt;
+
+const TOKEN_EXCHANGE_GRANT: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+const ACCESS_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:access_token";
+const SAML2_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:saml2";
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProviderSummary {
+    #[serde(default)]
+    pub provider: String,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub config_tag: String,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+// OAuth2 endpoints of an identity provider like ADFS
+#[derive(Deserialize, Debug, Clone)]
+pub struct Oauth2Info {
+    #[serde(default)]
+    pub auth_endpoint: String,
+    #[serde(default)]
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub client_id: String,
+    #[serde(default)]
+    pub issuer: String,
+}
+
+// OpenID Connect configuration of an identity provider like Okta
+#[derive(Deserialize, Debug, Clone)]
+pub struct OidcInfo {
+    pub discovery_endpoint: Option<String>,
+    #[serde(default)]
+    pub auth_endpoint: String,
+    #[serde(default)]
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub client_id: String,
+    #[serde(default)]
+    pub issuer: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProviderInfo {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub config_tag: String,
+    #[serde(default)]
+    pub is_default: bool,
+    pub upn_claim: Option<String>,
+    #[serde(default)]
+    pub domain_names: Vec<String>,
+    pub oauth2: Option<Oauth2Info>,
+    pub oidc: Option<OidcInfo>,
+}
+
+#[derive(Serialize)]
+struct ExchangeSpec<'a> {
+    grant_type: &'a str,
+    subject_token: &'a str,
+    subject_token_type: &'a str,
+    requested_token_type: &'a str,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ExchangeResult {
+    // The issued SAML token, base64url encoded
+    #[serde(default)]
+    pub access_token: String,
+    #[serde(default)]
+    pub token_type: String,
+    #[serde(default)]
+    pub issued_token_type: String,
+    pub expires_in: Option<u64>,
+}
+
+impl fmt::Debug for ExchangeResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExchangeResult")
+            .field("access_token", &REDACTED)
+            .field("token_type", &self.token_type)
+            .field("issued_token_type", &self.issued_token_type)
+            .field("expires_in", &self.expires_in)
+            .finish()
+    }
+}
+
+// Identity providers configured for federated login
+pub async fn providers(session: &Session) -> Result<Vec<ProviderSummary>, Error> {
+    let request = session.request(Method::GET, "/vcenter/identity/providers");
+    session.send(request).await
+}
+
+pub async fn provider(session: &Session, provider: &str) -> Result<ProviderInfo, Error> {
+    let endpoint = format!("/vcenter/identity/providers/{}", provider);
+    session.send(session.request(Method::GET, &endpoint)).await
+}
+
+// Exchange an access token of the identity provider for a SAML token of the
+// vCenter STS, this doesn't require a session
+pub async fn exchange_token(session: &Session, access_token: &str) -> Result<SamlToken, Error> {
+    let spec = ExchangeSpec {
+        grant_type: TOKEN_EXCHANGE_GRANT,
+        subject_token: access_token,
+        subject_token_type: ACCESS_TOKEN_TYPE,
+        requested_token_type: SAML2_TOKEN_TYPE,
+    };
+    let request = session.request(Method::POST, "/vcenter/tokenservice/token-exchange");
+    let result: ExchangeResult = session.send(session.body(request, "spec", &spec)).await?;
+    Ok(SamlToken::Bearer(decode_saml(&result.access_token)))
+}
+
+// Log the session in with an access token of the identity provider
+pub async fn login_with_access_token(session: &Session, access_token: &str) -> Result<bool, Error> {
+    let token = exchange_token(session, access_token).await?;
+    session.login_with_saml_token(token).await
+}
+
+// Tokens are issued base64url encoded without padding, plain XML is kept
+fn decode_saml(token: &str) -> String {
+    if token.trim_start().starts_with('<') {
+        return token.to_owned();
+    }
+    base64::decode_config(token.trim_end_matches('='), base64::URL_SAFE_NO_PAD)
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .unwrap_or_else(|| token.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_saml;
+
+    #[test]
+    fn decode_issued_token() {
+        let encoded = base64::encode_config("<saml2:Assertion/>", base64::URL_SAFE_NO_PAD);
+        assert_eq!(decode_saml(&encoded), "<saml2:Assertion/>");
+        assert_eq!(decode_saml("<saml2:Assertion/>"), "<saml2:Assertion/>");
+    }
+}
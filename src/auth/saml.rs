@@ -0,0 +1,137 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::{Method, Url};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Base64 encoded SHA-256 hash of the empty body of a session create request
+const EMPTY_BODY_HASH: &str = "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=";
+
+// Signs requests with the private key belonging to the certificate a
+// holder-of-key token was issued for. Implement it with the crypto library
+// or smart card middleware at hand.
+pub trait TokenSigner: Send + Sync {
+    // RSA signature of the message using SHA-256 (RSASSA-PKCS1-v1_5)
+    fn sign(&self, message: &[u8]) -> Vec<u8>;
+}
+
+// SAML token issued by the vCenter STS, the assertion is given as XML
+#[derive(Clone)]
+pub enum SamlToken {
+    Bearer(String),
+    HolderOfKey {
+        token: String,
+        signer: Arc<dyn TokenSigner>,
+    },
+}
+
+impl SamlToken {
+    // Value of the authorization header: the gzipped and base64 encoded
+    // token, holder-of-key requests are additionally signed
+    pub(crate) fn authorization(&self, method: &Method, url: &Url) -> String {
+        let (token, signer) = match self {
+            SamlToken::Bearer(token) => (token, None),
+            SamlToken::HolderOfKey { token, signer } => (token, Some(signer)),
+        };
+        let mut params = vec![("token", base64::encode(gzip(token.as_bytes())))];
+        if let Some(signer) = signer {
+            let nonce = nonce();
+            let message = signed_message(&nonce, method, url);
+            params.push(("nonce", nonce));
+            params.push(("bodyhash", EMPTY_BODY_HASH.to_owned()));
+            params.push(("signature", base64::encode(signer.sign(message.as_bytes()))));
+            params.push(("signature_alg", "RSA-SHA256".to_owned()));
+        }
+        let params: Vec<String> = params
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, value))
+            .collect();
+        format!("SIGN {}", params.join(","))
+    }
+}
+
+// Lines of the nonce, method, path, host, port and body hash
+fn signed_message(nonce: &str, method: &Method, url: &Url) -> String {
+    let host = url.host_str().unwrap_or("").to_lowercase();
+    let port = url.port_or_known_default().unwrap_or(443);
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n",
+        nonce,
+        method,
+        url.path(),
+        host,
+        port,
+        EMPTY_BODY_HASH
+    )
+}
+
+// Milliseconds since the epoch and a random number
+fn nonce() -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or(0);
+    let random = RandomState::new().build_hasher().finish();
+    format!("{}:{}", millis, random)
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    // Writing to a Vec doesn't fail
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    struct FixedSigner;
+
+    impl TokenSigner for FixedSigner {
+        fn sign(&self, message: &[u8]) -> Vec<u8> {
+            assert!(message.ends_with(format!("\n{}\n", EMPTY_BODY_HASH).as_bytes()));
+            vec![1, 2, 3]
+        }
+    }
+
+    fn token_param(authorization: &str) -> String {
+        let start = authorization.find("token=\"").unwrap() + 7;
+        let end = start + authorization[start..].find('"').unwrap();
+        let mut token = String::new();
+        GzDecoder::new(&base64::decode(&authorization[start..end]).unwrap()[..])
+            .read_to_string(&mut token)
+            .unwrap();
+        token
+    }
+
+    #[test]
+    fn bearer_authorization() {
+        let url = Url::parse("https://vcenter.example.com/rest/com/vmware/cis/session").unwrap();
+        let token = SamlToken::Bearer("<saml2:Assertion/>".to_owned());
+        let authorization = token.authorization(&Method::POST, &url);
+        assert!(authorization.starts_with("SIGN token=\""));
+        assert!(!authorization.contains("signature"));
+        assert_eq!(token_param(&authorization), "<saml2:Assertion/>");
+    }
+
+    #[test]
+    fn holder_of_key_authorization() {
+        let url = Url::parse("https://VCenter.example.com/api/session").unwrap();
+        let token = SamlToken::HolderOfKey {
+            token: "<saml2:Assertion/>".to_owned(),
+            signer: Arc::new(FixedSigner),
+        };
+        let authorization = token.authorization(&Method::POST, &url);
+        assert!(authorization.contains(",signature=\"AQID\",signature_alg=\"RSA-SHA256\""));
+        assert_eq!(token_param(&authorization), "<saml2:Assertion/>");
+        let nonce = nonce();
+        let message = signed_message(&nonce, &Method::POST, &url);
+        assert!(message.contains("\nPOST\n/api/session\nvcenter.example.com\n443\n"));
+    }
+}
@@ -0,0 +1,69 @@
+// Synchronous wrapper of a session for programs without a Tokio runtime. It
+// runs the async calls of the crate on its own single threaded runtime and
+// must not be used from async code.
+use crate::cis::{self, Error, LoginStatus};
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::runtime::{Builder, Runtime};
+
+pub struct Session {
+    inner: cis::Session,
+    runtime: Mutex<Runtime>,
+}
+
+impl Session {
+    pub fn new(hostname: impl Into<String>, insecure_certs: bool) -> Result<Self, Error> {
+        Session::from_async(cis::Session::new(hostname, insecure_certs)?)
+    }
+
+    // Wrap a session configured with the builder or setters of the async API
+    pub fn from_async(inner: cis::Session) -> Result<Self, Error> {
+        let runtime = Builder::new().basic_scheduler().enable_all().build()?;
+        Ok(Session {
+            inner,
+            runtime: Mutex::new(runtime),
+        })
+    }
+
+    // The async session, e.g. to clone it for use in async code
+    pub fn inner(&self) -> &cis::Session {
+        &self.inner
+    }
+
+    // Run an async call of the crate to completion, e.g.
+    // session.run(|s| vcenter::vm::list(s, &filter))
+    pub fn run<'a, F, C>(&'a self, call: C) -> F::Output
+    where
+        C: FnOnce(&'a cis::Session) -> F,
+        F: Future,
+    {
+        let mut runtime = self.runtime.lock().expect("runtime lock");
+        runtime.block_on(call(&self.inner))
+    }
+
+    pub fn login(&self, username: &str, password: Option<&str>) -> Result<bool, Error> {
+        self.run(|session| session.login(username, password))
+    }
+
+    pub fn login_status(&self) -> Result<LoginStatus, Error> {
+        self.run(|session| session.login_status())
+    }
+
+    pub fn logout(&self) -> Result<(), Error> {
+        self.run(|session| session.logout())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use crate::cis::Error;
+
+    #[test]
+    fn run_without_runtime() {
+        let session = Session::new("vcenter.invalid", false).expect("Session::new");
+        let result = session.run(|session| async move { session.api_flavor() });
+        assert_eq!(result, session.inner().api_flavor());
+        assert!(matches!(session.logout(), Err(Error::Reqwest(_))));
+    }
+}
@@ -0,0 +1,181 @@
+// Bulk retrieval of object details. The REST API has no property collector,
+// so details are fetched with one request per object while a semaphore
+// bounds the number of requests in flight. Batches run an operation on many
+// objects, e.g. powering off virtual machines, and report each outcome.
+pub use crate::cancel::CancelHandle;
+use crate::cis::{Error, ErrorType, Session};
+use crate::id::VmId;
+use crate::vcenter::vm::{self, FilterSpec, Vm, VmInfo};
+use futures::future::{try_join_all, Future};
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use tokio::sync::Semaphore;
+
+const DEFAULT_CONCURRENCY: usize = 8;
+
+// Concurrent fetcher of object details, the limit is shared by all calls on it
+pub struct Bulk<'a> {
+    session: &'a Session,
+    permits: Semaphore,
+}
+
+impl<'a> Bulk<'a> {
+    pub fn new(session: &'a Session) -> Self {
+        Bulk::with_concurrency(session, DEFAULT_CONCURRENCY)
+    }
+
+    // At most `concurrency` requests are in flight, at least one
+    pub fn with_concurrency(session: &'a Session, concurrency: usize) -> Self {
+        Bulk {
+            session,
+            permits: Semaphore::new(concurrency.max(1)),
+        }
+    }
+
+    // Details of the given virtual machines, those deleted in the meantime
+    // are missing from the result
+    pub async fn vm_infos(&self, vms: &[VmId]) -> Result<HashMap<VmId, VmInfo>, Error> {
+        let infos = try_join_all(vms.iter().map(|id| self.vm_info(id))).await?;
+        Ok(vms
+            .iter()
+            .cloned()
+            .zip(infos)
+            .filter_map(|(id, info)| Some((id, info?)))
+            .collect())
+    }
+
+    // Details of all virtual machines matching the filter
+    pub async fn list_vm_infos(&self, filter: &FilterSpec) -> Result<HashMap<VmId, VmInfo>, Error> {
+        let vms: Vec<VmId> = vm::list(self.session, filter)
+            .await?
+            .into_iter()
+            .map(|summary| summary.vm)
+            .collect();
+        self.vm_infos(&vms).await
+    }
+
+    async fn vm_info(&self, id: &VmId) -> Result<Option<VmInfo>, Error> {
+        let _permit = self.permits.acquire().await;
+        match Vm::new(self.session, id).info().await {
+            Ok(info) => Ok(Some(info)),
+            Err(err) if err.error_type() == Some(&ErrorType::NotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+// Outcome of a batch in the order of the items, canceled items weren't started
+#[derive(Debug)]
+pub struct BatchReport<K, T, E> {
+    pub results: Vec<(K, Result<T, E>)>,
+    pub canceled: Vec<K>,
+}
+
+impl<K, T, E> BatchReport<K, T, E> {
+    // True if all items were run and succeeded
+    pub fn is_success(&self) -> bool {
+        self.canceled.is_empty() && self.results.iter().all(|(_, result)| result.is_ok())
+    }
+
+    pub fn succeeded(&self) -> impl Iterator<Item = (&K, &T)> {
+        self.results
+            .iter()
+            .filter_map(|(item, result)| Some((item, result.as_ref().ok()?)))
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = (&K, &E)> {
+        self.results
+            .iter()
+            .filter_map(|(item, result)| Some((item, result.as_ref().err()?)))
+    }
+}
+
+// Run the operation on every item with at most `concurrency` in flight, at
+// least one. A failed item doesn't stop the others, once the handle is
+// canceled no more items are started but those already started complete, so
+// their outcome is known.
+pub async fn batch<K, T, E, F, Fut>(
+    items: impl IntoIterator<Item = K>,
+    concurrency: usize,
+    cancel: &CancelHandle,
+    op: F,
+) -> BatchReport<K, T, E>
+where
+    K: Clone,
+    F: Fn(K) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let op = &op;
+    let mut outcomes: Vec<_> = stream::iter(items.into_iter().enumerate())
+        .map(|(index, item)| async move {
+            if cancel.is_canceled() {
+                return (index, item, None);
+            }
+            let result = op(item.clone()).await;
+            (index, item, Some(result))
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+    outcomes.sort_by_key(|(index, _, _)| *index);
+    let mut report = BatchReport {
+        results: Vec::new(),
+        canceled: Vec::new(),
+    };
+    for (_, item, result) in outcomes {
+        match result {
+            Some(result) => report.results.push((item, result)),
+            None => report.canceled.push(item),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{batch, CancelHandle};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn batch_reports_partial_failure() {
+        let cancel = CancelHandle::new();
+        let report = batch(1..=5, 2, &cancel, |n: u32| async move {
+            if n == 2 || n == 4 {
+                Err(format!("vm-{} is busy", n))
+            } else {
+                Ok(n * 10)
+            }
+        })
+        .await;
+        assert!(!report.is_success());
+        assert_eq!(
+            report
+                .succeeded()
+                .map(|(n, v)| (*n, *v))
+                .collect::<Vec<_>>(),
+            vec![(1, 10), (3, 30), (5, 50)]
+        );
+        assert_eq!(
+            report.failed().map(|(n, _)| *n).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert!(report.canceled.is_empty());
+    }
+
+    #[tokio::test]
+    async fn canceled_batch_starts_no_more_items() {
+        let cancel = CancelHandle::new();
+        let started = AtomicUsize::new(0);
+        let report = batch(0..10, 1, &cancel, |n: u32| {
+            started.fetch_add(1, Ordering::SeqCst);
+            if n == 2 {
+                cancel.cancel();
+            }
+            async move { Ok::<_, ()>(n) }
+        })
+        .await;
+        assert_eq!(started.load(Ordering::SeqCst), 3);
+        assert_eq!(report.results.len(), 3);
+        assert_eq!(report.canceled, (3..10).collect::<Vec<_>>());
+    }
+}
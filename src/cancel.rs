@@ -0,0 +1,82 @@
+// Cancellation of long running helpers like batches and waits for tasks
+use futures::future;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+// Cancels the operations it is passed to. Clones cancel the same operations,
+// so a clone can be handed to e.g. a signal handler.
+#[derive(Clone)]
+pub struct CancelHandle {
+    sender: Arc<watch::Sender<bool>>,
+    receiver: watch::Receiver<bool>,
+}
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        let (sender, receiver) = watch::channel(false);
+        CancelHandle {
+            sender: Arc::new(sender),
+            receiver,
+        }
+    }
+
+    pub fn cancel(&self) {
+        // Can't fail, the handle itself holds a receiver
+        let _ = self.sender.broadcast(true);
+    }
+
+    pub fn is_canceled(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    // Resolves once the handle is canceled
+    pub async fn canceled(&self) {
+        let mut receiver = self.receiver.clone();
+        loop {
+            match receiver.recv().await {
+                Some(true) => return,
+                Some(false) => {}
+                // The sender lives as long as the handle
+                None => future::pending().await,
+            }
+        }
+    }
+}
+
+impl Default for CancelHandle {
+    fn default() -> Self {
+        CancelHandle::new()
+    }
+}
+
+impl fmt::Debug for CancelHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelHandle")
+            .field("canceled", &self.is_canceled())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancelHandle;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn canceled_wakes_waiters() {
+        let cancel = CancelHandle::new();
+        let waiter = {
+            let cancel = cancel.clone();
+            tokio::spawn(async move { cancel.canceled().await })
+        };
+        tokio::time::delay_for(Duration::from_millis(10)).await;
+        assert!(!cancel.is_canceled());
+        cancel.cancel();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("woken")
+            .expect("join");
+        assert!(cancel.is_canceled());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::appliance::system::VersionInfo;
+use std::fmt;
+
+// Version of a vCenter, e.g. 7.0.3 for vCenter 7.0 Update 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub update: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, update: u32) -> Self {
+        Version {
+            major,
+            minor,
+            update,
+        }
+    }
+
+    // Parse the version of the appliance, e.g. "7.0.3.01000", missing
+    // components are taken as 0
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut components = version.split('.').map(str::parse::<u32>);
+        let major = components.next()?.ok()?;
+        let minor = components.next().unwrap_or(Ok(0)).ok()?;
+        let update = components.next().unwrap_or(Ok(0)).ok()?;
+        Some(Version::new(major, minor, update))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.update)
+    }
+}
+
+// Feature of the API which isn't available on all vCenter versions
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    #[display(fmt = "the /api flavor")]
+    ApiFlavor,
+    #[display(fmt = "guest operations")]
+    GuestOperations,
+    #[display(fmt = "console tickets")]
+    ConsoleTickets,
+    #[display(fmt = "namespaces")]
+    Namespaces,
+    #[display(fmt = "vSphere Lifecycle Manager images")]
+    LifecycleManager,
+    #[display(fmt = "vStats")]
+    VStats,
+}
+
+impl Feature {
+    pub fn min_version(self) -> Version {
+        match self {
+            Feature::Namespaces => Version::new(7, 0, 0),
+            Feature::ApiFlavor
+            | Feature::GuestOperations
+            | Feature::ConsoleTickets
+            | Feature::LifecycleManager => Version::new(7, 0, 2),
+            Feature::VStats => Version::new(8, 0, 0),
+        }
+    }
+
+    // Features only offered by the /api flavor
+    fn requires_api_flavor(self) -> bool {
+        match self {
+            Feature::ApiFlavor
+            | Feature::GuestOperations
+            | Feature::ConsoleTickets
+            | Feature::LifecycleManager
+            | Feature::VStats => true,
+            Feature::Namespaces => false,
+        }
+    }
+}
+
+// Version of the connected vCenter and whether the /api flavor answers,
+// detected by Session::api_capabilities
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    pub version: Version,
+    pub build: String,
+    pub product: String,
+    pub api_available: bool,
+}
+
+impl Capabilities {
+    // Versions which can't be parsed are taken as 0.0.0, so only features
+    // available on all versions are supported
+    pub fn new(info: &VersionInfo, api_available: bool) -> Self {
+        Capabilities {
+            version: Version::parse(&info.version).unwrap_or(Version::new(0, 0, 0)),
+            build: info.build.clone(),
+            product: info.product.clone(),
+            api_available,
+        }
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.version >= feature.min_version()
+            && (self.api_available || !feature.requires_api_flavor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Capabilities, Feature, Version};
+
+    #[test]
+    fn parse_version() {
+        assert_eq!(Version::parse("7.0.3.01000"), Some(Version::new(7, 0, 3)));
+        assert_eq!(Version::parse("8.0"), Some(Version::new(8, 0, 0)));
+        assert_eq!(Version::parse("vCenter"), None);
+        assert!(Version::new(7, 0, 3) > Version::new(6, 7, 3));
+    }
+
+    #[test]
+    fn supported_features() {
+        let capabilities = Capabilities {
+            version: Version::new(7, 0, 1),
+            build: "17491101".to_owned(),
+            product: "VMware vCenter Server".to_owned(),
+            api_available: false,
+        };
+        assert!(capabilities.supports(Feature::Namespaces));
+        assert!(!capabilities.supports(Feature::GuestOperations));
+        let capabilities = Capabilities {
+            version: Version::new(8, 0, 1),
+            ..capabilities
+        };
+        // The /api flavor didn't answer, e.g. blocked by a proxy
+        assert!(!capabilities.supports(Feature::ConsoleTickets));
+        assert!(!capabilities.supports(Feature::VStats));
+    }
+}
@@ -0,0 +1,30 @@
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Request, Response, StatusCode};
+use std::time::Duration;
+
+// Hooks run for every request of a session and its clones, including the
+// login. Requests are passed after authentication, so credentials can be
+// read and replaced. All hooks do nothing by default.
+pub trait Middleware: Send + Sync {
+    fn on_request(&self, _request: &mut Request) {}
+
+    fn on_response(&self, _response: &Response) {}
+
+    // Called after on_response with the metadata of the exchange, e.g. to
+    // export the latency of API calls as metrics
+    fn on_complete(&self, _meta: &ResponseMeta) {}
+}
+
+// Metadata of a response, the elapsed time is measured until the headers
+// were received
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    pub method: Method,
+    // Path of the URL including the API prefix, e.g. "/api/vcenter/vm"
+    pub endpoint: String,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub elapsed: Duration,
+    // Value of the X-Request-ID header sent with the request
+    pub request_id: Option<String>,
+}
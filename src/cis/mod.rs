@@ -0,0 +1,1207 @@
+// Builder for sessions with custom TLS settings
+pub mod builder;
+// Version of the vCenter and the features available on it
+pub mod capabilities;
+// Hooks for requests and responses
+pub mod middleware;
+// Client side limit of the request rate
+pub mod rate_limit;
+// Retry of requests failing for transient reasons
+pub mod retry;
+// Task service for long running operations
+pub mod tasks;
+// Pluggable HTTP layer and a mock for tests
+pub mod transport;
+// Record and replay of API interactions as test fixtures
+#[cfg(feature = "vcr")]
+pub mod vcr;
+
+use super::auth::saml::SamlToken;
+use super::auth::Credentials;
+use super::common::{ApiResponse, ErrorResponse, Query, WithFlavor};
+use builder::SessionBuilder;
+use capabilities::{Capabilities, Feature, Version};
+use chrono::prelude::*;
+use futures::future::{AbortHandle, Abortable};
+use middleware::{Middleware, ResponseMeta};
+use rate_limit::RateLimiter;
+use reqwest::header::{HeaderValue, USER_AGENT};
+use reqwest::{self, Method, Request, RequestBuilder, Response, StatusCode};
+use retry::RetryPolicy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+use transport::Transport;
+
+// Header correlating the requests of an operation with the vCenter logs
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// Cis module error type
+#[derive(Debug, Display, From)]
+pub enum Error {
+    #[display(fmt = "Reqwest error: {}," _0)]
+    Reqwest(reqwest::Error),
+    #[display(fmt = "Unauthorized")]
+    Unauthorized,
+    #[display(fmt = "Unexpected status code: {}", _0)]
+    UnexpectedStatusCode(u16),
+    #[display(fmt = "API error: {}", _0)]
+    Api(ApiError),
+    #[display(fmt = "Task failed: {}", _0)]
+    #[from(ignore)]
+    TaskFailed(ApiError),
+    #[display(fmt = "Timed out")]
+    Timeout,
+    #[display(fmt = "Canceled")]
+    Canceled,
+    #[display(fmt = "IO error: {}", _0)]
+    Io(std::io::Error),
+    #[display(fmt = "JSON error: {}", _0)]
+    Json(serde_json::Error),
+    #[display(fmt = "Name {} matches several objects: {:?}", name, ids)]
+    #[from(ignore)]
+    AmbiguousName { name: String, ids: Vec<String> },
+    #[display(
+        fmt = "{} requires vCenter {} or later, connected to {}",
+        feature,
+        "feature.min_version()",
+        version
+    )]
+    #[from(ignore)]
+    UnsupportedOnThisVersion { feature: Feature, version: Version },
+}
+
+impl Error {
+    // Type of the error reported by the vSphere API, if any
+    pub fn error_type(&self) -> Option<&ErrorType> {
+        match self {
+            Error::Api(err) | Error::TaskFailed(err) => Some(&err.error_type),
+            _ => None,
+        }
+    }
+
+    // Error for objects which are looked up on the client side and don't exist
+    pub(crate) fn not_found() -> Self {
+        Error::Api(ApiError {
+            status: 404,
+            error_type: ErrorType::NotFound,
+            messages: Vec::new(),
+        })
+    }
+
+    // Error for interfaces outside of the API which answer 403 Forbidden
+    pub(crate) fn forbidden() -> Self {
+        Error::Api(ApiError {
+            status: 403,
+            error_type: ErrorType::Unauthorized,
+            messages: Vec::new(),
+        })
+    }
+}
+
+// Standard error types of the vSphere API (com.vmware.vapi.std.errors)
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
+pub enum ErrorType {
+    AlreadyExists,
+    AlreadyInDesiredState,
+    Canceled,
+    ConcurrentChange,
+    Error,
+    FeatureInUse,
+    InternalServerError,
+    InvalidArgument,
+    InvalidElementConfiguration,
+    InvalidElementType,
+    InvalidRequest,
+    NotAllowedInCurrentState,
+    NotFound,
+    OperationNotFound,
+    ResourceBusy,
+    ResourceInUse,
+    ResourceInaccessible,
+    ServiceUnavailable,
+    TimedOut,
+    UnableToAllocateResource,
+    Unauthenticated,
+    Unauthorized,
+    UnexpectedInput,
+    Unsupported,
+    UnverifiedPeer,
+    #[display(fmt = "{}", _0)]
+    Other(String),
+}
+
+impl ErrorType {
+    // Parse either the fully qualified type name or the upper case error_type value
+    pub fn from_name(name: &str) -> Self {
+        let short = name.rsplit('.').next().unwrap_or(name).to_ascii_lowercase();
+        match short.as_str() {
+            "already_exists" => ErrorType::AlreadyExists,
+            "already_in_desired_state" => ErrorType::AlreadyInDesiredState,
+            "canceled" => ErrorType::Canceled,
+            "concurrent_change" => ErrorType::ConcurrentChange,
+            "error" => ErrorType::Error,
+            "feature_in_use" => ErrorType::FeatureInUse,
+            "internal_server_error" => ErrorType::InternalServerError,
+            "invalid_argument" => ErrorType::InvalidArgument,
+            "invalid_element_configuration" => ErrorType::InvalidElementConfiguration,
+            "invalid_element_type" => ErrorType::InvalidElementType,
+            "invalid_request" => ErrorType::InvalidRequest,
+            "not_allowed_in_current_state" => ErrorType::NotAllowedInCurrentState,
+            "not_found" => ErrorType::NotFound,
+            "operation_not_found" => ErrorType::OperationNotFound,
+            "resource_busy" => ErrorType::ResourceBusy,
+            "resource_in_use" => ErrorType::ResourceInUse,
+            "resource_inaccessible" => ErrorType::ResourceInaccessible,
+            "service_unavailable" => ErrorType::ServiceUnavailable,
+            "timed_out" => ErrorType::TimedOut,
+            "unable_to_allocate_resource" => ErrorType::UnableToAllocateResource,
+            "unauthenticated" => ErrorType::Unauthenticated,
+            "unauthorized" => ErrorType::Unauthorized,
+            "unexpected_input" => ErrorType::UnexpectedInput,
+            "unsupported" => ErrorType::Unsupported,
+            "unverified_peer" => ErrorType::UnverifiedPeer,
+            _ => ErrorType::Other(name.to_string()),
+        }
+    }
+}
+
+// Localizable message attached to an API error
+#[derive(Deserialize, Debug, Clone)]
+pub struct LocalizableMessage {
+    pub id: String,
+    #[serde(default)]
+    pub default_message: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+// Reference to an object of any type, e.g. {"type": "VirtualMachine", "id": "vm-42"}
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DynamicId {
+    #[serde(default, rename = "type")]
+    pub object_type: String,
+    pub id: String,
+}
+
+impl DynamicId {
+    pub fn new(object_type: impl Into<String>, id: impl Into<String>) -> Self {
+        DynamicId {
+            object_type: object_type.into(),
+            id: id.into(),
+        }
+    }
+}
+
+// Error reported by the vSphere API in the body of an unsuccessful response
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub status: u16,
+    pub error_type: ErrorType,
+    pub messages: Vec<LocalizableMessage>,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.error_type, self.status)?;
+        for message in &self.messages {
+            write!(f, ": {}", message.default_message)?;
+        }
+        Ok(())
+    }
+}
+
+impl ApiError {
+    fn from_response(status: u16, body: ErrorResponse) -> Self {
+        let (name, value) = match body {
+            ErrorResponse::Rest { error_type, value } => (error_type, value),
+            ErrorResponse::Api(value) => (String::new(), value),
+        };
+        let name = value.error_type.unwrap_or(name);
+        ApiError {
+            status,
+            error_type: ErrorType::from_name(&name),
+            messages: value.messages,
+        }
+    }
+}
+
+// Flavor of the vSphere REST API, /rest is available on all versions but
+// deprecated since vSphere 7.0 U2 in favor of /api
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ApiFlavor {
+    #[default]
+    Rest,
+    Api,
+}
+
+impl ApiFlavor {
+    pub(crate) fn prefix(self) -> &'static str {
+        match self {
+            ApiFlavor::Rest => "rest",
+            ApiFlavor::Api => "api",
+        }
+    }
+}
+
+// Represents the login status as returned from the vSphere API
+#[derive(Deserialize, Debug)]
+pub struct LoginStatus {
+    #[serde(default)]
+    pub user: String,
+    pub created_time: DateTime<Utc>,
+    pub last_accessed_time: DateTime<Utc>,
+}
+
+// Login state shared by all clones of a session
+#[derive(Default)]
+struct LoginState {
+    session_id: Option<String>,
+    // Used to log in again with auto relogin and to authenticate requests to
+    // the datastore /folder interface, which doesn't take the API session
+    credentials: Option<Credentials>,
+}
+
+// This type represents a vSphere Session and handles login. Clones share the
+// HTTP client, the login and the rate limiter and can be used from multiple
+// tasks, the remaining settings are per clone and should be set before cloning.
+#[derive(Clone)]
+pub struct Session {
+    hostname: String,
+    client: reqwest::Client,
+    transport: Arc<dyn Transport>,
+    state: Arc<Mutex<LoginState>>,
+    auto_relogin: bool,
+    flavor: ApiFlavor,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    request_timeout: Option<Duration>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    user_agent: Option<HeaderValue>,
+    request_id: Option<String>,
+    capabilities: Arc<Mutex<Option<Capabilities>>>,
+}
+
+impl Session {
+    pub fn new(hostname: impl Into<String>, insecure_certs: bool) -> Result<Self, Error> {
+        Session::builder(hostname)
+            .insecure_certs(insecure_certs)
+            .build()
+    }
+
+    pub fn builder(hostname: impl Into<String>) -> SessionBuilder {
+        SessionBuilder::new(hostname)
+    }
+
+    fn with_client(
+        hostname: String,
+        client: reqwest::Client,
+        transport: Arc<dyn Transport>,
+    ) -> Self {
+        Session {
+            hostname,
+            client,
+            transport,
+            state: Arc::new(Mutex::new(LoginState::default())),
+            auto_relogin: false,
+            flavor: ApiFlavor::default(),
+            retry_policy: RetryPolicy::none(),
+            rate_limiter: None,
+            request_timeout: None,
+            middleware: Vec::new(),
+            user_agent: None,
+            request_id: None,
+            capabilities: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn api_flavor(&self) -> ApiFlavor {
+        self.flavor
+    }
+
+    // Select the API flavor, this should happen before login as the session
+    // endpoints differ between the flavors
+    pub fn set_api_flavor(&mut self, flavor: ApiFlavor) {
+        self.flavor = flavor;
+    }
+
+    // Retry requests failing with a transient error according to the policy
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    // Limit the request rate, the limiter can be shared with other sessions
+    // to limit their combined rate
+    pub fn set_rate_limiter(&mut self, limiter: Option<Arc<RateLimiter>>) {
+        self.rate_limiter = limiter;
+    }
+
+    // Run the hooks of the middleware for every request, in the order added
+    pub fn add_middleware(&mut self, middleware: Arc<dyn Middleware>) {
+        self.middleware.push(middleware);
+    }
+
+    // Clone of the session whose requests time out after the given duration,
+    // e.g. session.with_timeout(Duration::from_secs(5)) for a single call
+    pub fn with_timeout(&self, timeout: Duration) -> Session {
+        Session {
+            request_timeout: Some(timeout),
+            ..self.clone()
+        }
+    }
+
+    // Clone of the session whose requests carry the id in the X-Request-ID
+    // header, so the calls of an operation can be found in the vCenter logs.
+    // Ids which aren't valid header values are not sent.
+    pub fn with_request_id(&self, id: impl Into<String>) -> Session {
+        Session {
+            request_id: Some(id.into()),
+            ..self.clone()
+        }
+    }
+
+    // Clone of the session with a generated UUID as request id, see
+    // with_request_id
+    pub fn with_new_request_id(&self) -> Session {
+        self.with_request_id(new_uuid())
+    }
+
+    // Clone of the session using the /api flavor, for endpoints which the
+    // /rest flavor lacks. The login of the /rest flavor is valid for both.
+    pub(crate) fn with_api_flavor(&self) -> Session {
+        Session {
+            flavor: ApiFlavor::Api,
+            ..self.clone()
+        }
+    }
+
+    // Id sent with the requests of the session, if any
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    // Use the credentials of the login to log in again and retry once when a
+    // request fails because the session expired
+    pub fn set_auto_relogin(&mut self, enabled: bool) {
+        self.auto_relogin = enabled;
+    }
+
+    #[cfg(feature = "vim25")]
+    pub(crate) fn auto_relogin(&self) -> bool {
+        self.auto_relogin
+    }
+
+    pub async fn login(&self, username: &str, password: Option<&str>) -> Result<bool, Error> {
+        let credentials = Credentials::Basic {
+            username: username.to_string(),
+            password: password.map(str::to_string),
+        };
+        self.login_with(credentials).await
+    }
+
+    // Log in with a SAML token issued by the vCenter STS instead of a password
+    pub async fn login_with_saml_token(&self, token: SamlToken) -> Result<bool, Error> {
+        self.login_with(Credentials::Saml(token)).await
+    }
+
+    async fn login_with(&self, credentials: Credentials) -> Result<bool, Error> {
+        let session_id = match self.create_session(&credentials).await? {
+            Some(session_id) => session_id,
+            None => return Ok(false),
+        };
+        let mut state = self.state();
+        state.session_id = Some(session_id);
+        state.credentials = Some(credentials);
+        Ok(true)
+    }
+
+    async fn create_session(&self, credentials: &Credentials) -> Result<Option<String>, Error> {
+        let request = self
+            .request(Method::POST, self.session_endpoint())
+            .build()?;
+        let resp: Response = self.dispatch(credentials.authorize(request)).await?;
+        let status = resp.status();
+        match status {
+            _ if status.is_success() => Ok(Some(self.read_value(resp).await?)),
+            StatusCode::UNAUTHORIZED => Ok(None),
+            _ => Err(error_from_response(resp).await),
+        }
+    }
+
+    // Log in again with the retained credentials, returns false if that is not possible
+    async fn relogin(&self) -> Result<bool, Error> {
+        if !self.auto_relogin {
+            return Ok(false);
+        }
+        let credentials = match self.state().credentials.clone() {
+            Some(credentials) => credentials,
+            None => return Ok(false),
+        };
+        match self.create_session(&credentials).await? {
+            Some(session_id) => {
+                self.set_session_id(Some(session_id));
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // Create an API session for an existing vim25 session so that both share
+    // the same login, returns false if the SOAP session was not accepted
+    #[cfg(feature = "vim25")]
+    pub(crate) async fn login_with_soap_session(&self, soap_session: &str) -> Result<bool, Error> {
+        let request = self
+            .request(Method::POST, self.session_endpoint())
+            .header("vmware-use-header-authn", "vim25")
+            .header("vmware-api-session-id", soap_session)
+            .build()?;
+        let resp: Response = self.dispatch(request).await?;
+        let status = resp.status();
+        match status {
+            _ if status.is_success() => {
+                self.set_session_id(Some(self.read_value(resp).await?));
+                Ok(true)
+            }
+            StatusCode::UNAUTHORIZED => Ok(false),
+            _ => Err(error_from_response(resp).await),
+        }
+    }
+
+    pub(crate) fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
+    pub(crate) fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    // Execute a request as is, with the tracing feature in a span recording
+    // its method, endpoint, status and duration. Credentials are not recorded.
+    #[cfg(feature = "tracing")]
+    pub(crate) async fn dispatch(&self, request: Request) -> Result<Response, reqwest::Error> {
+        use tracing::field::Empty;
+        use tracing::Instrument;
+        let request = self.before_request(request);
+        let sent = SentRequest::new(&request);
+        let session_id = if request.headers().contains_key("vmware-api-session-id") {
+            "[redacted]"
+        } else {
+            "none"
+        };
+        let span = tracing::debug_span!(
+            "vsphere_request",
+            method = %request.method(),
+            endpoint = %request.url().path(),
+            session_id,
+            status = Empty,
+            duration_ms = Empty,
+        );
+        let result = self
+            .transport
+            .execute(request)
+            .instrument(span.clone())
+            .await;
+        span.record("duration_ms", sent.start.elapsed().as_millis() as u64);
+        match &result {
+            Ok(resp) => {
+                self.after_response(resp, sent);
+                span.record("status", resp.status().as_u16());
+                tracing::debug!(parent: &span, "response received");
+            }
+            Err(err) => tracing::warn!(parent: &span, error = %err, "request failed"),
+        }
+        result
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) async fn dispatch(&self, request: Request) -> Result<Response, reqwest::Error> {
+        let request = self.before_request(request);
+        let sent = SentRequest::new(&request);
+        let resp = self.transport.execute(request).await?;
+        self.after_response(&resp, sent);
+        Ok(resp)
+    }
+
+    fn before_request(&self, mut request: Request) -> Request {
+        let headers = request.headers_mut();
+        if let Some(user_agent) = &self.user_agent {
+            headers.insert(USER_AGENT, user_agent.clone());
+        }
+        if let Some(Ok(id)) = self.request_id.as_deref().map(HeaderValue::from_str) {
+            headers.insert(REQUEST_ID_HEADER, id);
+        }
+        for middleware in &self.middleware {
+            middleware.on_request(&mut request);
+        }
+        request
+    }
+
+    fn after_response(&self, resp: &Response, sent: SentRequest) {
+        if self.middleware.is_empty() {
+            return;
+        }
+        let meta = ResponseMeta {
+            method: sent.method,
+            endpoint: sent.endpoint,
+            status: resp.status(),
+            headers: resp.headers().clone(),
+            elapsed: sent.start.elapsed(),
+            request_id: sent.request_id,
+        };
+        for middleware in &self.middleware {
+            middleware.on_response(resp);
+            middleware.on_complete(&meta);
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, LoginState> {
+        self.state.lock().expect("login state lock")
+    }
+
+    fn set_session_id(&self, session_id: Option<String>) {
+        self.state().session_id = session_id;
+    }
+
+    fn authenticate(&self, mut request: Request) -> Request {
+        let state = self.state();
+        let value = state.session_id.as_deref().unwrap_or("");
+        if let Ok(value) = HeaderValue::from_str(value) {
+            request.headers_mut().insert("vmware-api-session-id", value);
+        }
+        request
+    }
+
+    // Authenticate a request to an interface outside of the API, like the
+    // datastore /folder interface, with the session and the login credentials.
+    // Sessions created from a vim25 login have no credentials.
+    pub(crate) fn authorize(&self, request: Request) -> Request {
+        let request = self.authenticate(request);
+        match &self.state().credentials {
+            Some(credentials) => credentials.authorize(request),
+            None => request,
+        }
+    }
+
+    fn session_endpoint(&self) -> &'static str {
+        match self.flavor {
+            ApiFlavor::Rest => "/com/vmware/cis/session",
+            ApiFlavor::Api => "/session",
+        }
+    }
+
+    // Endpoint of a com.vmware service like "content/library", the /rest flavor
+    // prefixes it with the namespace and addresses objects as "id:{id}"
+    pub(crate) fn service_endpoint(&self, service: &str, id: Option<&str>) -> String {
+        match (self.flavor, id) {
+            (ApiFlavor::Rest, Some(id)) => format!("/com/vmware/{}/id:{}", service, id),
+            (ApiFlavor::Rest, None) => format!("/com/vmware/{}", service),
+            (ApiFlavor::Api, Some(id)) => format!("/{}/{}", service, id),
+            (ApiFlavor::Api, None) => format!("/{}", service),
+        }
+    }
+
+    // Create a request for the given API endpoint, it is authenticated when sent
+    pub(crate) fn request(&self, method: Method, endpoint: &str) -> RequestBuilder {
+        let request = self
+            .client
+            .request(method, api_url!(self.flavor, self.hostname, endpoint));
+        match self.request_timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    // Create a request for an action on the given API endpoint
+    pub(crate) fn action(&self, method: Method, endpoint: &str, action: &str) -> RequestBuilder {
+        self.request(method, endpoint).query(&[("action", action)])
+    }
+
+    // Create a request for an action of a com.vmware service, the /rest flavor
+    // expects the action as "~action" parameter
+    pub(crate) fn service_action(
+        &self,
+        method: Method,
+        service: &str,
+        id: Option<&str>,
+        action: &str,
+    ) -> RequestBuilder {
+        let param = match self.flavor {
+            ApiFlavor::Rest => "~action",
+            ApiFlavor::Api => "action",
+        };
+        self.request(method, &self.service_endpoint(service, id))
+            .query(&[(param, action)])
+    }
+
+    // Create a POST request for an action which the /rest flavor addresses as a
+    // sub path of the endpoint and the /api flavor as action parameter
+    pub(crate) fn path_action(&self, endpoint: &str, action: &str) -> RequestBuilder {
+        match self.flavor {
+            ApiFlavor::Rest => self.request(Method::POST, &format!("{}/{}", endpoint, action)),
+            ApiFlavor::Api => self.action(Method::POST, endpoint, action),
+        }
+    }
+
+    // Create a GET request for a list endpoint, the /rest flavor expects the
+    // filter parameters with a "filter." prefix
+    pub(crate) fn list(&self, endpoint: &str, filter: &Query) -> RequestBuilder {
+        let query: Vec<(String, &str)> = filter
+            .iter()
+            .map(|(key, value)| match self.flavor {
+                ApiFlavor::Rest => (format!("filter.{}", key), value.as_str()),
+                ApiFlavor::Api => (key.to_string(), value.as_str()),
+            })
+            .collect();
+        self.request(Method::GET, endpoint).query(&query)
+    }
+
+    // Attach a request body, the /rest flavor expects it wrapped in an object
+    // under the given key
+    pub(crate) fn body<T: Serialize>(
+        &self,
+        request: RequestBuilder,
+        key: &str,
+        body: &T,
+    ) -> RequestBuilder {
+        match self.flavor {
+            ApiFlavor::Rest => {
+                let wrapped: HashMap<&str, &T> = std::iter::once((key, body)).collect();
+                self.json(request, &wrapped)
+            }
+            ApiFlavor::Api => self.json(request, body),
+        }
+    }
+
+    // Attach a request body as is, maps in it are serialized for the session flavor
+    pub(crate) fn json<T: Serialize>(&self, request: RequestBuilder, body: &T) -> RequestBuilder {
+        request.json(&WithFlavor(self.flavor, body))
+    }
+
+    async fn read_value<T: DeserializeOwned>(&self, resp: Response) -> Result<T, Error> {
+        match self.flavor {
+            ApiFlavor::Rest => Ok(resp.json::<ApiResponse<T>>().await?.value),
+            ApiFlavor::Api => Ok(resp.json::<T>().await?),
+        }
+    }
+
+    // Send a request, retrying it according to the retry policy
+    async fn execute(&self, request: RequestBuilder) -> Result<Response, Error> {
+        self.execute_with(request, |request| self.execute_once(request))
+            .await
+    }
+
+    // Send a request with the given function, retrying it according to the
+    // retry policy. Each attempt waits for the rate limiter. This is shared
+    // with clients of other interfaces of the host, like the vim25 SOAP API.
+    pub(crate) async fn execute_with<F, Fut>(
+        &self,
+        request: RequestBuilder,
+        send: F,
+    ) -> Result<Response, Error>
+    where
+        F: Fn(Request) -> Fut,
+        Fut: Future<Output = Result<Response, Error>>,
+    {
+        let mut request = request.build()?;
+        let mut attempt = 1;
+        loop {
+            let next = if attempt < self.retry_policy.max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire().await;
+            }
+            let result = send(request).await;
+            request = match next {
+                Some(next) if self.retry_policy.should_retry(&result) => next,
+                _ => return result,
+            };
+            tokio::time::delay_for(self.retry_policy.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    // Send a request with the current session id, logging in again once if the session expired
+    async fn execute_once(&self, request: Request) -> Result<Response, Error> {
+        let retry = if self.auto_relogin {
+            request.try_clone()
+        } else {
+            None
+        };
+        let resp: Response = self.dispatch(self.authenticate(request)).await?;
+        match retry {
+            Some(retry) if resp.status() == StatusCode::UNAUTHORIZED => {
+                if !self.relogin().await? {
+                    return Ok(resp);
+                }
+                Ok(self.dispatch(self.authenticate(retry)).await?)
+            }
+            _ => Ok(resp),
+        }
+    }
+
+    // Send a request and return the deserialized value of the response
+    pub(crate) async fn send<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, Error> {
+        let resp: Response = self.execute(request).await?;
+        match resp.status() {
+            status if status.is_success() => self.read_value(resp).await,
+            _ => Err(error_from_response(resp).await),
+        }
+    }
+
+    // Send a request whose response carries no value
+    pub(crate) async fn send_empty(&self, request: RequestBuilder) -> Result<(), Error> {
+        let resp: Response = self.execute(request).await?;
+        match resp.status() {
+            status if status.is_success() => Ok(()),
+            _ => Err(error_from_response(resp).await),
+        }
+    }
+
+    // Get the value of an endpoint as JSON without a model, e.g.
+    // session.get_raw("/vcenter/vm/vm-42") for fields the models lack
+    pub async fn get_raw(&self, endpoint: &str) -> Result<serde_json::Value, Error> {
+        self.send(self.request(Method::GET, endpoint)).await
+    }
+
+    // Get the value of an endpoint as a model together with its JSON, e.g.
+    // session.get_with_raw::<VmInfo>("/vcenter/vm/vm-42") to read fields a
+    // newer vCenter added next to the ones the model knows
+    pub async fn get_with_raw<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<(T, serde_json::Value), Error> {
+        let raw = self.get_raw(endpoint).await?;
+        Ok((serde_json::from_value(raw.clone())?, raw))
+    }
+
+    // Detect the version of the vCenter and whether the /api flavor answers.
+    // The result is kept by the session and its clones. Calls needing a
+    // missing feature fail with Error::UnsupportedOnThisVersion.
+    pub async fn api_capabilities(&self) -> Result<Capabilities, Error> {
+        if let Some(capabilities) = self.capabilities.lock().expect("capabilities lock").clone() {
+            return Ok(capabilities);
+        }
+        let info = crate::appliance::system::version(self).await?;
+        let api_available = match self.flavor {
+            ApiFlavor::Api => true,
+            ApiFlavor::Rest => self.probe_api_flavor().await?,
+        };
+        let capabilities = Capabilities::new(&info, api_available);
+        *self.capabilities.lock().expect("capabilities lock") = Some(capabilities.clone());
+        Ok(capabilities)
+    }
+
+    // Fail if the capabilities lack the feature, they are detected with the
+    // first call needing a feature
+    pub(crate) async fn require(&self, feature: Feature) -> Result<(), Error> {
+        let capabilities = self.api_capabilities().await?;
+        if capabilities.supports(feature) {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedOnThisVersion {
+                feature,
+                version: capabilities.version,
+            })
+        }
+    }
+
+    // The session of the /rest flavor is also valid for the /api flavor
+    async fn probe_api_flavor(&self) -> Result<bool, Error> {
+        let request = self.client.request(
+            Method::GET,
+            api_url!(ApiFlavor::Api, self.hostname, "/appliance/system/version"),
+        );
+        let resp = self.execute(request).await?;
+        match resp.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            _ => Err(error_from_response(resp).await),
+        }
+    }
+
+    pub async fn login_status(&self) -> Result<LoginStatus, Error> {
+        let request = match self.flavor {
+            ApiFlavor::Rest => self.service_action(Method::POST, "cis/session", None, "get"),
+            ApiFlavor::Api => self.request(Method::GET, self.session_endpoint()),
+        };
+        self.send(request).await
+    }
+
+    // Query the session periodically so it doesn't expire while idle, the
+    // task runs on the Tokio runtime until the returned guard is dropped
+    pub fn spawn_keepalive(&self, interval: Duration) -> KeepaliveGuard {
+        let session = self.clone();
+        let (handle, registration) = AbortHandle::new_pair();
+        let keepalive = async move {
+            let mut ticks =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            loop {
+                ticks.tick().await;
+                // Failures are retried on the next tick, expired sessions are
+                // renewed by auto relogin if enabled
+                let _ = session.login_status().await;
+            }
+        };
+        tokio::spawn(Abortable::new(keepalive, registration));
+        KeepaliveGuard { handle }
+    }
+
+    pub async fn logout(&self) -> Result<(), Error> {
+        let request = self
+            .request(Method::DELETE, self.session_endpoint())
+            .build()?;
+        let resp: Response = self.dispatch(self.authenticate(request)).await?;
+        let status = resp.status();
+        match status {
+            _ if status.is_success() => {
+                *self.state() = LoginState::default();
+                Ok(())
+            }
+            StatusCode::UNAUTHORIZED => Ok(()),
+            _ => Err(error_from_response(resp).await),
+        }
+    }
+}
+
+// Stops the keepalive task of a session when dropped
+pub struct KeepaliveGuard {
+    handle: AbortHandle,
+}
+
+impl Drop for KeepaliveGuard {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+// Request as sent to the transport, for the metadata of its response
+struct SentRequest {
+    method: Method,
+    endpoint: String,
+    request_id: Option<String>,
+    start: Instant,
+}
+
+impl SentRequest {
+    fn new(request: &Request) -> Self {
+        SentRequest {
+            method: request.method().clone(),
+            endpoint: request.url().path().to_owned(),
+            request_id: request
+                .headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|id| id.to_str().ok())
+                .map(str::to_owned),
+            start: Instant::now(),
+        }
+    }
+}
+
+// Random UUID (version 4) without a dependency on a random number generator.
+// The hasher keys of RandomState are random per process, the time and a
+// counter make the ids unique within it.
+fn new_uuid() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let random = |part: u8| RandomState::new().hash_one((part, count, now));
+    let version = 0xf000u128 << 64;
+    let variant = 0xc000u128 << 48;
+    let bits = (u128::from(random(0)) << 64) | u128::from(random(1));
+    let bits = (bits & !(version | variant)) | (0x4000u128 << 64) | (0x8000u128 << 48);
+    let hex = format!("{:032x}", bits);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+// Map an unsuccessful response to the matching error
+async fn error_from_response(resp: Response) -> Error {
+    let status = resp.status();
+    if status == StatusCode::UNAUTHORIZED {
+        return Error::Unauthorized;
+    }
+    match resp.json::<ErrorResponse>().await {
+        Ok(body) => Error::Api(ApiError::from_response(status.as_u16(), body)),
+        Err(_) => Error::UnexpectedStatusCode(status.as_u16()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::transport::MockTransport;
+    use super::{
+        ApiError, Error, ErrorType, Feature, HeaderValue, LoginStatus, Method, Middleware, Request,
+        ResponseMeta, Session, StatusCode, Version,
+    };
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn login_login_status_logout() {
+        let mock = Arc::new(MockTransport::new());
+        let endpoint = "/rest/com/vmware/cis/session";
+        mock.once(Method::POST, endpoint, StatusCode::UNAUTHORIZED, "");
+        mock.once(
+            Method::POST,
+            endpoint,
+            StatusCode::OK,
+            r#"{"value":"4d1f0c"}"#,
+        );
+        let status = r#"{"value":{"user":"VSPHERE.LOCAL\\automation","created_time":"2020-06-01T10:00:00Z","last_accessed_time":"2020-06-01T10:05:00Z"}}"#;
+        mock.on(
+            Method::POST,
+            "/rest/com/vmware/cis/session?~action=get",
+            StatusCode::OK,
+            status,
+        );
+        mock.on(Method::DELETE, endpoint, StatusCode::OK, "");
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        let login_ok = session
+            .login("automation@vsphere.local", Some("abc"))
+            .await
+            .expect("session.login");
+        assert!(!login_ok);
+        let login_ok = session
+            .login("automation@vsphere.local", Some("secret"))
+            .await
+            .expect("login");
+        assert!(login_ok);
+        let login_status: LoginStatus = session.login_status().await.expect("session.login_status");
+        assert_eq!(login_status.user, "VSPHERE.LOCAL\\automation");
+        session.logout().await.expect("session.logout");
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[1].headers.contains_key("authorization"));
+        assert_eq!(requests[2].headers["vmware-api-session-id"], "4d1f0c");
+        assert_eq!(requests[3].headers["vmware-api-session-id"], "4d1f0c");
+    }
+
+    #[tokio::test]
+    async fn user_agent_and_request_id() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/vm",
+            StatusCode::OK,
+            r#"{"value":[]}"#,
+        );
+        let session = Session::builder("vcenter.test")
+            .user_agent("deploy-tool/1.4")
+            .transport(mock.clone())
+            .build()
+            .expect("build")
+            .with_new_request_id();
+        let id = session.request_id().expect("request id").to_owned();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert_ne!(
+            session.with_new_request_id().request_id(),
+            Some(id.as_str())
+        );
+        let request = session.request(Method::GET, "/vcenter/vm");
+        let _: Vec<serde_json::Value> = session.send(request).await.expect("send");
+        let requests = mock.requests();
+        assert_eq!(requests[0].headers["user-agent"], "deploy-tool/1.4");
+        assert_eq!(requests[0].headers["x-request-id"], id.as_str());
+    }
+
+    #[tokio::test]
+    async fn detect_capabilities() {
+        let mock = Arc::new(MockTransport::new());
+        let version = r#"{"version":"7.0.1.00000","product":"VMware vCenter Server","build":"16860138",
+            "type":"vCenter Server with an embedded Platform Services Controller","summary":"Patch",
+            "releasedate":"October 6, 2020","install_time":"2020-10-12T08:51:03.000Z"}"#;
+        mock.on(
+            Method::GET,
+            "/rest/appliance/system/version",
+            StatusCode::OK,
+            format!(r#"{{"value":{}}}"#, version),
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        assert!(matches!(
+            session.require(Feature::GuestOperations).await,
+            Err(Error::UnsupportedOnThisVersion {
+                feature: Feature::GuestOperations,
+                ..
+            })
+        ));
+        assert!(session.clone().require(Feature::Namespaces).await.is_ok());
+        let capabilities = session.api_capabilities().await.expect("capabilities");
+        assert_eq!(capabilities.version, Version::new(7, 0, 1));
+        assert!(!capabilities.api_available);
+        // Detected once, the /api probe got 404 Not Found from the mock
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].url.path(), "/api/appliance/system/version");
+    }
+
+    #[test]
+    fn session_is_shareable() {
+        fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+        assert_shareable::<Session>();
+        fn assert_send<T: Send>(_: T) {}
+        let session = Session::new("vcenter.example.com", false).expect("Session::new");
+        assert_send(session.login_status());
+        assert_send(session.login("user", Some("password")));
+    }
+
+    #[test]
+    fn request_timeout_override() {
+        let session = Session::new("vcenter.example.com", false).expect("Session::new");
+        let request = session.request(Method::GET, "/vcenter/vm").build().unwrap();
+        assert_eq!(request.timeout(), None);
+        let session = session.with_timeout(Duration::from_secs(5));
+        let request = session.request(Method::GET, "/vcenter/vm").build().unwrap();
+        assert_eq!(request.timeout(), Some(&Duration::from_secs(5)));
+    }
+
+    struct AuditHeader;
+
+    impl Middleware for AuditHeader {
+        fn on_request(&self, request: &mut Request) {
+            let value = HeaderValue::from_static("pipeline-7");
+            request.headers_mut().insert("x-audit-id", value);
+        }
+    }
+
+    #[test]
+    fn middleware_mutates_request() {
+        let mut session = Session::new("vcenter.example.com", false).expect("Session::new");
+        session.add_middleware(Arc::new(AuditHeader));
+        let request = session.request(Method::GET, "/vcenter/vm").build().unwrap();
+        let request = session.before_request(request);
+        assert_eq!(request.headers()["x-audit-id"], "pipeline-7");
+    }
+
+    #[derive(Default)]
+    struct Latency(std::sync::Mutex<Vec<ResponseMeta>>);
+
+    impl Middleware for Latency {
+        fn on_complete(&self, meta: &ResponseMeta) {
+            self.0.lock().unwrap().push(meta.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn middleware_gets_response_meta() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/vm",
+            StatusCode::OK,
+            r#"{"value":[]}"#,
+        );
+        let mut session = Session::builder("vcenter.test")
+            .transport(mock)
+            .build()
+            .expect("build");
+        let latency = Arc::new(Latency::default());
+        session.add_middleware(latency.clone());
+        let session = session.with_request_id("deploy-42");
+        let request = session.request(Method::GET, "/vcenter/vm");
+        let _: Vec<serde_json::Value> = session.send(request).await.expect("send");
+        let metas = latency.0.lock().unwrap();
+        assert_eq!(metas.len(), 1);
+        assert_eq!(metas[0].method, Method::GET);
+        assert_eq!(metas[0].endpoint, "/rest/vcenter/vm");
+        assert_eq!(metas[0].status, StatusCode::OK);
+        assert_eq!(metas[0].request_id.as_deref(), Some("deploy-42"));
+    }
+
+    #[tokio::test]
+    async fn login_failure_is_api_error() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::POST,
+            "/rest/com/vmware/cis/session",
+            StatusCode::SERVICE_UNAVAILABLE,
+            r#"{"type":"com.vmware.vapi.std.errors.service_unavailable","value":{"messages":[]}}"#,
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock)
+            .build()
+            .expect("build");
+        let err = session.login("root", Some("secret")).await.unwrap_err();
+        assert_eq!(err.error_type(), Some(&ErrorType::ServiceUnavailable));
+    }
+
+    #[tokio::test]
+    async fn get_with_raw_keeps_unknown_fields() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/appliance/system/version",
+            StatusCode::OK,
+            r#"{"value":{"version":"8.0.2.00100","fips":true}}"#,
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock)
+            .build()
+            .expect("build");
+        let (info, raw): (crate::appliance::system::VersionInfo, _) = session
+            .get_with_raw("/appliance/system/version")
+            .await
+            .expect("get");
+        assert_eq!(info.version, "8.0.2.00100");
+        assert_eq!(raw["fips"], true);
+    }
+
+    #[test]
+    fn error_type_from_name() {
+        assert_eq!(
+            ErrorType::from_name("com.vmware.vapi.std.errors.not_found"),
+            ErrorType::NotFound
+        );
+        assert_eq!(
+            ErrorType::from_name("ALREADY_IN_DESIRED_STATE"),
+            ErrorType::AlreadyInDesiredState
+        );
+        assert_eq!(
+            ErrorType::from_name("com.example.custom"),
+            ErrorType::Other("com.example.custom".to_string())
+        );
+    }
+
+    #[test]
+    fn api_error_from_both_flavors() {
+        let rest = serde_json::from_str(
+            r#"{"type":"com.vmware.vapi.std.errors.not_found","value":{"messages":[{"id":"vm.not_found","default_message":"VM not found","args":[]}]}}"#,
+        )
+        .expect("deserialize rest error");
+        let rest = ApiError::from_response(404, rest);
+        assert_eq!(rest.error_type, ErrorType::NotFound);
+        assert_eq!(rest.messages[0].default_message, "VM not found");
+
+        let api = serde_json::from_str(r#"{"error_type":"RESOURCE_IN_USE","messages":[]}"#)
+            .expect("deserialize api error");
+        let api = ApiError::from_response(400, api);
+        assert_eq!(api.error_type, ErrorType::ResourceInUse);
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Token bucket limiting the request rate of a session. Each request takes a
+// token, tokens are refilled at `rate` per second up to `burst`. Requests
+// arriving at an empty bucket reserve a future token and wait for it.
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    // Negative while requests wait for reserved tokens
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    // Allow `rate` requests per second on average and up to `burst` at once.
+    // Panics if the rate isn't a positive finite number.
+    pub fn new(rate: f64, burst: u32) -> Self {
+        assert!(
+            rate.is_finite() && rate > 0.0,
+            "rate limit must be a positive number of requests per second, got {}",
+            rate
+        );
+        let burst = f64::from(burst.max(1));
+        RateLimiter {
+            rate,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    // Wait until the next request may be sent
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if wait > Duration::from_secs(0) {
+            tokio::time::delay_for(wait).await;
+        }
+    }
+
+    // Take a token and return how long to wait until it is available
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().expect("rate limiter lock");
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst) - 1.0;
+        bucket.updated = now;
+        if bucket.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn burst_then_rate() {
+        let limiter = RateLimiter::new(2.0, 2);
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(now), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(now), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(now), Duration::from_millis(500));
+        assert_eq!(limiter.reserve(now), Duration::from_secs(1));
+        // The reserved tokens are paid back before new ones accumulate
+        let later = now + Duration::from_secs(3);
+        assert_eq!(limiter.reserve(later), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(later), Duration::from_secs(0));
+        assert_eq!(limiter.reserve(later), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn reject_invalid_rates() {
+        for rate in &[0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(std::panic::catch_unwind(|| RateLimiter::new(*rate, 1)).is_err());
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use super::Error;
+use reqwest::{Response, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+// Policy for retrying requests which failed for transient reasons. The delay
+// before retry n is initial_backoff * multiplier^(n-1), capped at max_backoff
+// and reduced by a random fraction of up to `jitter` of itself. A negative or
+// NaN multiplier is taken as 1, jitter outside of 0 to 1 is clamped.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    // Attempts including the first one, 1 disables retries
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    // Between 0 and 1
+    pub jitter: f64,
+    // Status codes which are retried, e.g. 429 and 503
+    pub retry_on: Vec<StatusCode>,
+    // Retry requests which failed because no connection could be established
+    pub retry_connection_errors: bool,
+}
+
+impl RetryPolicy {
+    // Policy which never retries, this is the default of a Session
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    pub(crate) fn should_retry(&self, result: &Result<Response, Error>) -> bool {
+        match result {
+            Ok(resp) => self.retry_on.contains(&resp.status()),
+            Err(Error::Reqwest(err)) => self.retry_connection_errors && err.is_connect(),
+            Err(_) => false,
+        }
+    }
+
+    // Delay after the given failed attempt, starting at 1
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let multiplier = if self.multiplier >= 0.0 {
+            self.multiplier
+        } else {
+            1.0
+        };
+        let factor = multiplier.powi(attempt.saturating_sub(1) as i32);
+        let backoff = self.initial_backoff.as_secs_f64() * factor;
+        // min also caps an infinite or NaN backoff
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+        let jitter = if self.jitter >= 0.0 {
+            self.jitter.min(1.0)
+        } else {
+            0.0
+        };
+        let jitter = jitter * random_fraction();
+        Duration::from_secs_f64(backoff * (1.0 - jitter))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+            retry_on: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::SERVICE_UNAVAILABLE,
+            ],
+            retry_connection_errors: true,
+        }
+    }
+}
+
+// Random number in [0, 1), the hasher keys of RandomState are random per instance
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = RetryPolicy {
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff(10), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn jitter_shortens_backoff() {
+        let policy = RetryPolicy {
+            jitter: 1.0,
+            ..RetryPolicy::default()
+        };
+        for _ in 0..100 {
+            assert!(policy.backoff(2) <= Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn invalid_factors_dont_panic() {
+        let policy = RetryPolicy {
+            multiplier: -2.0,
+            jitter: f64::NAN,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(2), Duration::from_millis(500));
+        let policy = RetryPolicy {
+            multiplier: f64::NAN,
+            jitter: 1.5,
+            ..RetryPolicy::default()
+        };
+        assert!(policy.backoff(3) <= Duration::from_millis(500));
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_secs(0),
+            multiplier: f64::INFINITY,
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(3), Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,304 @@
+use super::{ApiError, DynamicId, Error, LocalizableMessage, Session};
+use crate::cancel::CancelHandle;
+use crate::common::ErrorResponse;
+use chrono::prelude::*;
+use futures::future::{self, Either};
+use futures::stream::{self, Stream};
+use futures::task::{Context, Poll};
+use reqwest::Method;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+
+// Status of a task
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Status {
+    Pending,
+    Running,
+    Blocked,
+    Succeeded,
+    Failed,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Progress of a running task, completed counts up to total
+#[derive(Deserialize, Debug, Clone)]
+pub struct Progress {
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub completed: u64,
+    pub message: LocalizableMessage,
+}
+
+// State of a task, the result is the return value of the operation once it succeeded
+#[derive(Deserialize, Debug, Clone)]
+pub struct TaskInfo {
+    pub description: LocalizableMessage,
+    #[serde(default)]
+    pub service: String,
+    #[serde(default)]
+    pub operation: String,
+    pub parent: Option<String>,
+    pub target: Option<DynamicId>,
+    pub status: Status,
+    #[serde(default)]
+    pub cancelable: bool,
+    pub error: Option<serde_json::Value>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub user: Option<String>,
+    pub progress: Option<Progress>,
+    pub result: Option<serde_json::Value>,
+}
+
+impl TaskInfo {
+    // Error the task failed with, the status is always 0 as there is no response
+    pub fn api_error(&self) -> Option<ApiError> {
+        let error = self.error.clone()?;
+        let body: ErrorResponse = serde_json::from_value(error).ok()?;
+        Some(ApiError::from_response(0, body))
+    }
+}
+
+pub async fn get(session: &Session, task: &str) -> Result<TaskInfo, Error> {
+    let request = session.request(Method::GET, &format!("/cis/tasks/{}", task));
+    session.send(request).await
+}
+
+// Cancel a task, fails with ErrorType::Unsupported if it isn't cancelable
+pub async fn cancel(session: &Session, task: &str) -> Result<(), Error> {
+    let request = session.action(Method::POST, &format!("/cis/tasks/{}", task), "cancel");
+    session.send_empty(request).await
+}
+
+// Poll a task until it succeeded, fails with Error::TaskFailed if the task
+// failed and with Error::Timeout if it didn't finish in time. Dropping the
+// future stops the polling but not the task, see wait_for_task_with_cancel.
+pub async fn wait_for_task(
+    session: &Session,
+    task: &str,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<TaskInfo, Error> {
+    wait_for_task_with_cancel(session, task, poll_interval, timeout, &CancelHandle::new()).await
+}
+
+// Like wait_for_task, when the handle is canceled the task is canceled too
+// if it is cancelable and the wait fails with Error::Canceled
+pub async fn wait_for_task_with_cancel(
+    session: &Session,
+    task: &str,
+    poll_interval: Duration,
+    timeout: Duration,
+    cancel_handle: &CancelHandle,
+) -> Result<TaskInfo, Error> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let info = get(session, task).await?;
+        match info.status {
+            Status::Succeeded => return Ok(info),
+            Status::Failed => {
+                let error = info.api_error().unwrap_or_else(unknown_error);
+                return Err(Error::TaskFailed(error));
+            }
+            _ => {}
+        }
+        if Instant::now() + poll_interval > deadline {
+            return Err(Error::Timeout);
+        }
+        let delay = delay_for(poll_interval);
+        let canceled = cancel_handle.canceled();
+        futures::pin_mut!(canceled);
+        if let Either::Right(_) = future::select(delay, canceled).await {
+            // Best effort, the task may have finished in the meantime
+            if info.cancelable {
+                let _ = cancel(session, task).await;
+            }
+            return Err(Error::Canceled);
+        }
+    }
+}
+
+// Change of the status or progress of a task
+#[derive(Debug, Clone)]
+pub struct TaskUpdate {
+    pub status: Status,
+    // Progress in percent, if the task reports progress
+    pub percent: Option<u8>,
+    pub info: TaskInfo,
+}
+
+impl TaskUpdate {
+    fn new(info: TaskInfo) -> Self {
+        let percent = info
+            .progress
+            .as_ref()
+            .and_then(|progress| match progress.total {
+                0 => None,
+                total => Some((progress.completed.min(total) * 100 / total) as u8),
+            });
+        TaskUpdate {
+            status: info.status,
+            percent,
+            info,
+        }
+    }
+}
+
+// Stream of the updates of a task, created by progress(). It yields an update
+// whenever the status or percentage changes and ends after the task succeeded.
+// A failed task is yielded as Error::TaskFailed.
+pub struct TaskProgress<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<TaskUpdate, Error>> + Send + 'a>>,
+}
+
+impl Stream for TaskProgress<'_> {
+    type Item = Result<TaskUpdate, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+struct ProgressState {
+    task: String,
+    last: Option<(Status, Option<u8>)>,
+    done: bool,
+}
+
+// Follow a task by polling it in the given interval
+pub fn progress<'a>(
+    session: &'a Session,
+    task: impl Into<String>,
+    poll_interval: Duration,
+) -> TaskProgress<'a> {
+    let state = ProgressState {
+        task: task.into(),
+        last: None,
+        done: false,
+    };
+    let inner = stream::unfold(state, move |mut state| async move {
+        if state.done {
+            return None;
+        }
+        loop {
+            if state.last.is_some() {
+                delay_for(poll_interval).await;
+            }
+            let update = match get(session, &state.task).await {
+                Ok(info) => TaskUpdate::new(info),
+                Err(err) => {
+                    state.done = true;
+                    return Some((Err(err), state));
+                }
+            };
+            let current = Some((update.status, update.percent));
+            if state.last == current {
+                continue;
+            }
+            state.last = current;
+            match update.status {
+                Status::Succeeded => state.done = true,
+                Status::Failed => {
+                    state.done = true;
+                    let error = update.info.api_error().unwrap_or_else(unknown_error);
+                    return Some((Err(Error::TaskFailed(error)), state));
+                }
+                _ => {}
+            }
+            return Some((Ok(update), state));
+        }
+    });
+    TaskProgress {
+        inner: Box::pin(inner),
+    }
+}
+
+fn unknown_error() -> ApiError {
+    ApiError {
+        status: 0,
+        error_type: super::ErrorType::Error,
+        messages: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wait_for_task_with_cancel, Status, TaskInfo};
+    use crate::cancel::CancelHandle;
+    use crate::cis::transport::MockTransport;
+    use crate::cis::{Error, ErrorType, Session};
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn failed_task_error() {
+        let info: TaskInfo = serde_json::from_str(
+            r#"{
+                "description": {"id": "vm.clone", "default_message": "Clone", "args": []},
+                "service": "com.vmware.vcenter.vm",
+                "operation": "clone",
+                "status": "FAILED",
+                "cancelable": false,
+                "error": {"error_type": "NOT_FOUND", "messages": []}
+            }"#,
+        )
+        .expect("deserialize");
+        assert_eq!(info.status, Status::Failed);
+        assert_eq!(
+            info.api_error().map(|err| err.error_type),
+            Some(ErrorType::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn canceled_wait_cancels_task() {
+        let mock = Arc::new(MockTransport::new());
+        let running = r#"{"value":{"description":{"id":"vm.clone","default_message":"Clone","args":[]},
+            "service":"com.vmware.vcenter.vm","operation":"clone","status":"RUNNING","cancelable":true}}"#;
+        mock.on(
+            Method::GET,
+            "/rest/cis/tasks/task-17",
+            StatusCode::OK,
+            running,
+        );
+        mock.on(
+            Method::POST,
+            "/rest/cis/tasks/task-17?action=cancel",
+            StatusCode::OK,
+            "",
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        let cancel = CancelHandle::new();
+        cancel.cancel();
+        let result = wait_for_task_with_cancel(
+            &session,
+            "task-17",
+            Duration::from_secs(60),
+            Duration::from_secs(600),
+            &cancel,
+        )
+        .await;
+        assert!(matches!(result, Err(Error::Canceled)));
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, Method::POST);
+    }
+
+    #[test]
+    fn unknown_status() {
+        let status = serde_json::from_str::<Status>(r#""SUSPENDED""#);
+        if cfg!(feature = "strict") {
+            assert!(status.is_err());
+        } else {
+            assert_eq!(status.expect("deserialize"), Status::Unknown);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use futures::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Request, Response, StatusCode, Url};
+use std::sync::{Mutex, MutexGuard};
+
+// Sends the requests of a session after middleware and authentication. The
+// default transport is the reqwest client configured by the SessionBuilder,
+// tests can replace it with a MockTransport to run without a vCenter.
+pub trait Transport: Send + Sync {
+    fn execute(&self, request: Request) -> BoxFuture<'static, Result<Response, reqwest::Error>>;
+}
+
+impl Transport for reqwest::Client {
+    fn execute(&self, request: Request) -> BoxFuture<'static, Result<Response, reqwest::Error>> {
+        Box::pin(reqwest::Client::execute(self, request))
+    }
+}
+
+// Request as received by a MockTransport
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    // Empty for requests without body or with a streamed body
+    pub body: Vec<u8>,
+}
+
+struct Route {
+    method: Method,
+    path: String,
+    status: StatusCode,
+    body: Vec<u8>,
+    once: bool,
+}
+
+impl Route {
+    // Paths with a query string only match requests with the same decoded
+    // query parameters
+    fn matches(&self, request: &Request) -> bool {
+        let url = request.url();
+        let expected = match url.join(&self.path) {
+            Ok(expected) => expected,
+            Err(_) => return false,
+        };
+        let matches_query =
+            !self.path.contains('?') || expected.query_pairs().eq(url.query_pairs());
+        self.method == request.method() && expected.path() == url.path() && matches_query
+    }
+}
+
+// Transport answering with canned responses instead of a vCenter. Routes
+// match the method and the path of the URL including the API prefix, e.g.
+// "/rest/vcenter/vm". Routes added with once answer a single request and take
+// precedence, requests without route get 404 Not Found.
+#[derive(Default)]
+pub struct MockTransport {
+    routes: Mutex<Vec<Route>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // Answer every matching request with the status and body
+    pub fn on(&self, method: Method, path: &str, status: StatusCode, body: impl Into<Vec<u8>>) {
+        self.add(method, path, status, body.into(), false);
+    }
+
+    // Answer the next matching request with the status and body
+    pub fn once(&self, method: Method, path: &str, status: StatusCode, body: impl Into<Vec<u8>>) {
+        self.add(method, path, status, body.into(), true);
+    }
+
+    // Requests received so far, in the order they were sent
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().expect("requests lock").clone()
+    }
+
+    fn add(&self, method: Method, path: &str, status: StatusCode, body: Vec<u8>, once: bool) {
+        self.routes().push(Route {
+            method,
+            path: path.to_owned(),
+            status,
+            body,
+            once,
+        });
+    }
+
+    fn respond(&self, request: &Request) -> (StatusCode, Vec<u8>) {
+        let mut routes = self.routes();
+        let position = routes
+            .iter()
+            .position(|route| route.once && route.matches(request))
+            .or_else(|| routes.iter().position(|route| route.matches(request)));
+        match position {
+            Some(position) if routes[position].once => {
+                let route = routes.remove(position);
+                (route.status, route.body)
+            }
+            Some(position) => (routes[position].status, routes[position].body.clone()),
+            None => (StatusCode::NOT_FOUND, Vec::new()),
+        }
+    }
+
+    fn routes(&self) -> MutexGuard<'_, Vec<Route>> {
+        self.routes.lock().expect("routes lock")
+    }
+}
+
+impl Transport for MockTransport {
+    fn execute(&self, request: Request) -> BoxFuture<'static, Result<Response, reqwest::Error>> {
+        let (status, body) = self.respond(&request);
+        self.requests
+            .lock()
+            .expect("requests lock")
+            .push(RecordedRequest {
+                method: request.method().clone(),
+                url: request.url().clone(),
+                headers: request.headers().clone(),
+                body: request
+                    .body()
+                    .and_then(|body| body.as_bytes())
+                    .map(<[u8]>::to_vec)
+                    .unwrap_or_default(),
+            });
+        let mut resp = http::Response::new(body);
+        *resp.status_mut() = status;
+        Box::pin(futures::future::ready(Ok(Response::from(resp))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockTransport;
+    use crate::cis::Session;
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn once_takes_precedence() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/vm",
+            StatusCode::OK,
+            r#"{"value":[]}"#,
+        );
+        mock.once(
+            Method::GET,
+            "/rest/vcenter/vm",
+            StatusCode::SERVICE_UNAVAILABLE,
+            "",
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        let request = session.request(Method::GET, "/vcenter/vm").build().unwrap();
+        let resp = session.dispatch(request).await.expect("dispatch");
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let request = session.request(Method::GET, "/vcenter/vm").build().unwrap();
+        let resp = session.dispatch(request).await.expect("dispatch");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let request = session
+            .request(Method::GET, "/vcenter/host")
+            .build()
+            .unwrap();
+        let resp = session.dispatch(request).await.expect("dispatch");
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(mock.requests().len(), 3);
+    }
+}
@@ -0,0 +1,269 @@
+use super::transport::Transport;
+use super::Error;
+use futures::future::BoxFuture;
+use reqwest::{Request, Response, StatusCode, Url};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// Placeholder for redacted secrets in cassettes
+pub const REDACTED: &str = "REDACTED";
+
+// Request and response pair of a cassette, the URL is stored without scheme
+// and hostname so cassettes replay against any vCenter. Bodies are stored as
+// text, binary bodies like file downloads are not supported.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Interaction {
+    #[serde(default)]
+    pub method: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub request_body: String,
+    #[serde(default)]
+    pub status: u16,
+    #[serde(default)]
+    pub response_body: String,
+}
+
+// Recorded interactions in the order they were sent, saved as JSON fixture
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Cassette {
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let json = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&json).map_err(std::io::Error::from)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::from)?;
+        Ok(std::fs::write(path, json)?)
+    }
+}
+
+#[derive(Default)]
+struct Recording {
+    interactions: Vec<Interaction>,
+    secrets: Vec<String>,
+}
+
+// Transport recording the interactions with a real vCenter while passing
+// them through. Session IDs are collected from the requests and replaced
+// with REDACTED everywhere, including the body of the login response, when
+// the cassette is taken. Credentials are sent as headers which are not
+// recorded at all.
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl RecordingTransport {
+    // Record the requests sent through the inner transport, usually a
+    // reqwest::Client configured like the session
+    pub fn new(inner: Arc<dyn Transport>) -> Self {
+        RecordingTransport {
+            inner,
+            recording: Default::default(),
+        }
+    }
+
+    // Redact another secret, e.g. a guest password sent in a request body
+    pub fn redact(&self, secret: impl Into<String>) {
+        let secret = secret.into();
+        if !secret.is_empty() {
+            self.recording().secrets.push(secret);
+        }
+    }
+
+    // Redacted interactions recorded so far
+    pub fn cassette(&self) -> Cassette {
+        let recording = self.recording();
+        let redact = |text: &str| {
+            recording
+                .secrets
+                .iter()
+                .fold(text.to_owned(), |text, secret| {
+                    text.replace(secret, REDACTED)
+                })
+        };
+        let interactions = recording
+            .interactions
+            .iter()
+            .map(|interaction| Interaction {
+                method: interaction.method.clone(),
+                url: redact(&interaction.url),
+                request_body: redact(&interaction.request_body),
+                status: interaction.status,
+                response_body: redact(&interaction.response_body),
+            })
+            .collect();
+        Cassette { interactions }
+    }
+
+    // Save the redacted cassette as fixture for a ReplayTransport
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.cassette().save(path)
+    }
+
+    fn recording(&self) -> MutexGuard<'_, Recording> {
+        self.recording.lock().expect("recording lock")
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn execute(&self, request: Request) -> BoxFuture<'static, Result<Response, reqwest::Error>> {
+        if let Some(session_id) = request
+            .headers()
+            .get("vmware-api-session-id")
+            .and_then(|value| value.to_str().ok())
+        {
+            let mut recording = self.recording();
+            if !recording.secrets.iter().any(|secret| secret == session_id) {
+                recording.secrets.push(session_id.to_owned());
+            }
+        }
+        let method = request.method().to_string();
+        let url = relative_url(request.url());
+        let request_body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|body| String::from_utf8_lossy(body).into_owned())
+            .unwrap_or_default();
+        let recording = self.recording.clone();
+        let resp = self.inner.execute(request);
+        Box::pin(async move {
+            let resp = resp.await?;
+            let status = resp.status();
+            let headers = resp.headers().clone();
+            let body = resp.bytes().await?;
+            recording
+                .lock()
+                .expect("recording lock")
+                .interactions
+                .push(Interaction {
+                    method,
+                    url,
+                    request_body,
+                    status: status.as_u16(),
+                    response_body: String::from_utf8_lossy(&body).into_owned(),
+                });
+            let mut replayed = http::Response::new(body);
+            *replayed.status_mut() = status;
+            *replayed.headers_mut() = headers;
+            Ok(Response::from(replayed))
+        })
+    }
+}
+
+// Transport answering with the responses of a cassette. Requests match the
+// first unused interaction with the same method and URL, so repeated calls
+// get the responses in the recorded order. Requests without interaction get
+// 404 Not Found.
+pub struct ReplayTransport {
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: Cassette) -> Self {
+        ReplayTransport {
+            interactions: Mutex::new(cassette.interactions),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(ReplayTransport::new(Cassette::load(path)?))
+    }
+
+    // Number of interactions which were not replayed yet
+    pub fn remaining(&self) -> usize {
+        self.interactions.lock().expect("interactions lock").len()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn execute(&self, request: Request) -> BoxFuture<'static, Result<Response, reqwest::Error>> {
+        let method = request.method().to_string();
+        let url = relative_url(request.url());
+        let mut interactions = self.interactions.lock().expect("interactions lock");
+        let position = interactions
+            .iter()
+            .position(|interaction| interaction.method == method && interaction.url == url);
+        let (status, body) = match position {
+            Some(position) => {
+                let interaction = interactions.remove(position);
+                let status =
+                    StatusCode::from_u16(interaction.status).unwrap_or(StatusCode::NOT_FOUND);
+                (status, interaction.response_body)
+            }
+            None => (StatusCode::NOT_FOUND, String::new()),
+        };
+        let mut resp = http::Response::new(body);
+        *resp.status_mut() = status;
+        Box::pin(futures::future::ready(Ok(Response::from(resp))))
+    }
+}
+
+fn relative_url(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cassette, RecordingTransport, ReplayTransport, REDACTED};
+    use crate::cis::transport::MockTransport;
+    use crate::cis::Session;
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let mock = Arc::new(MockTransport::new());
+        let endpoint = "/rest/com/vmware/cis/session";
+        mock.on(
+            Method::POST,
+            endpoint,
+            StatusCode::OK,
+            r#"{"value":"4d1f0c"}"#,
+        );
+        mock.on(Method::DELETE, endpoint, StatusCode::OK, "");
+        let recorder = Arc::new(RecordingTransport::new(mock));
+        let session = Session::builder("vcenter.test")
+            .transport(recorder.clone())
+            .build()
+            .expect("build");
+        assert!(session
+            .login("automation", Some("secret"))
+            .await
+            .expect("login"));
+        session.logout().await.expect("logout");
+
+        let path = std::env::temp_dir().join(format!("vcr-{}.json", std::process::id()));
+        recorder.save(&path).expect("save");
+        let cassette = Cassette::load(&path).expect("load");
+        std::fs::remove_file(&path).expect("remove");
+        assert_eq!(cassette.interactions.len(), 2);
+        assert_eq!(cassette.interactions[0].url, endpoint);
+        assert_eq!(
+            cassette.interactions[0].response_body,
+            format!(r#"{{"value":"{}"}}"#, REDACTED)
+        );
+
+        let replay = Arc::new(ReplayTransport::new(cassette));
+        let session = Session::builder("other.test")
+            .transport(replay.clone())
+            .build()
+            .expect("build");
+        assert!(session
+            .login("automation", Some("secret"))
+            .await
+            .expect("login"));
+        session.logout().await.expect("logout");
+        assert_eq!(replay.remaining(), 0);
+    }
+}
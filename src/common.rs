@@ -1,7 +1,20 @@
-// Generate a URL for the vSphere API of the given hostname
+use crate::cis::{ApiFlavor, LocalizableMessage};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+// Placeholder printed by Debug instead of passwords and tokens
+pub(crate) const REDACTED: &str = "<redacted>";
+
+// Debug value of an optional secret, which only tells whether it is set
+pub(crate) fn redacted(secret: &Option<String>) -> Option<&'static str> {
+    secret.as_ref().map(|_| REDACTED)
+}
+
+// Generate a URL for the vSphere API of the given flavor and hostname
 macro_rules! api_url {
-    ($hostname:expr, $endpoint:expr) => {
-        &format!("https://{}/rest/{}", $hostname, $endpoint)
+    ($flavor:expr, $hostname:expr, $endpoint:expr) => {
+        &format!("https://{}/{}{}", $hostname, $flavor.prefix(), $endpoint)
     };
 }
 
@@ -10,3 +23,98 @@ macro_rules! api_url {
 pub struct ApiResponse<T> {
     pub value: T,
 }
+
+// Maps are returned as list of key value pairs by the /rest flavor and as
+// object by the /api flavor
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum MapResponse<V> {
+    Object(HashMap<String, V>),
+    Pairs(Vec<KeyValue<V>>),
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct KeyValue<V> {
+    pub key: String,
+    pub value: V,
+}
+
+impl<V> From<MapResponse<V>> for HashMap<String, V> {
+    fn from(map: MapResponse<V>) -> Self {
+        match map {
+            MapResponse::Object(map) => map,
+            MapResponse::Pairs(pairs) => pairs.into_iter().map(|kv| (kv.key, kv.value)).collect(),
+        }
+    }
+}
+
+// Deserialize a map of either flavor, see MapResponse
+pub fn deserialize_map<'de, D, V>(deserializer: D) -> Result<HashMap<String, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    MapResponse::deserialize(deserializer).map(HashMap::from)
+}
+
+thread_local! {
+    static FLAVOR: Cell<ApiFlavor> = const { Cell::new(ApiFlavor::Api) };
+}
+
+// Serialize a body for the given flavor, see serialize_map
+pub struct WithFlavor<'a, T>(pub ApiFlavor, pub &'a T);
+
+impl<T: Serialize> Serialize for WithFlavor<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let previous = FLAVOR.with(|flavor| flavor.replace(self.0));
+        let result = self.1.serialize(serializer);
+        FLAVOR.with(|flavor| flavor.set(previous));
+        result
+    }
+}
+
+// Serialize a map as list of key value pairs for the /rest flavor and as
+// object for the /api flavor. The flavor is taken from the enclosing
+// WithFlavor, outside of it maps are serialized as object.
+pub fn serialize_map<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    match FLAVOR.with(Cell::get) {
+        ApiFlavor::Rest => serializer.collect_seq(map.iter().map(|(key, value)| KeyValue {
+            key: key.clone(),
+            value,
+        })),
+        ApiFlavor::Api => map.serialize(serializer),
+    }
+}
+
+// Error envelope returned by the vSphere API for unsuccessful requests, the
+// /rest flavor wraps the error in type and value, the /api flavor does not
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ErrorResponse {
+    Rest {
+        #[serde(rename = "type")]
+        error_type: String,
+        #[serde(default)]
+        value: ErrorValue,
+    },
+    Api(ErrorValue),
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ErrorValue {
+    #[serde(default)]
+    pub messages: Vec<LocalizableMessage>,
+    pub error_type: Option<String>,
+}
+
+// Query parameters of a request, list values are passed as repeated keys
+pub(crate) type Query = Vec<(&'static str, String)>;
+
+// Append every value of a list to the query under the same key
+pub(crate) fn push_query<T: ToString>(query: &mut Query, key: &'static str, values: &[T]) {
+    query.extend(values.iter().map(|value| (key, value.to_string())));
+}
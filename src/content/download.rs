@@ -0,0 +1,177 @@
+// Download sessions of library items. The files of a session are prepared
+// by vCenter and then downloaded from their endpoint, e.g. the descriptor
+// and disks of an OVF package.
+use super::{ChecksumInfo, TransferEndpoint};
+use crate::cis::{ApiError, Error, ErrorType, LocalizableMessage, Session};
+use crate::id::LibraryItemId;
+use bytes::Bytes;
+use futures::stream::{Stream, TryStreamExt};
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+use tokio::time::delay_for;
+
+const SESSION_SERVICE: &str = "content/library/item/download-session";
+const FILE_SERVICE: &str = "content/library/item/downloadsession/file";
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrepareStatus {
+    Unprepared,
+    PrepareRequested,
+    Prepared,
+    Error,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// File of a download session, the endpoint is set once it is prepared
+#[derive(Deserialize, Debug, Clone)]
+pub struct FileInfo {
+    pub name: String,
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub bytes_transferred: u64,
+    pub status: PrepareStatus,
+    pub checksum_info: Option<ChecksumInfo>,
+    pub error_message: Option<LocalizableMessage>,
+    pub download_endpoint: Option<TransferEndpoint>,
+}
+
+#[derive(Serialize)]
+struct DownloadSessionModel<'a> {
+    library_item_id: &'a LibraryItemId,
+}
+
+#[derive(Serialize)]
+struct FileParams<'a> {
+    file_name: &'a str,
+}
+
+// Create a download session for the library item, returns its identifier
+pub async fn create(session: &Session, item: &LibraryItemId) -> Result<String, Error> {
+    let request = session.request(
+        Method::POST,
+        &session.service_endpoint(SESSION_SERVICE, None),
+    );
+    let model = DownloadSessionModel {
+        library_item_id: item,
+    };
+    session
+        .send(session.body(request, "create_spec", &model))
+        .await
+}
+
+// Delete the download session, the prepared files are removed
+pub async fn delete(session: &Session, download_session: &str) -> Result<(), Error> {
+    let request = session.request(
+        Method::DELETE,
+        &session.service_endpoint(SESSION_SERVICE, Some(download_session)),
+    );
+    session.send_empty(request).await
+}
+
+pub async fn files(session: &Session, download_session: &str) -> Result<Vec<FileInfo>, Error> {
+    let request = session
+        .request(Method::GET, &session.service_endpoint(FILE_SERVICE, None))
+        .query(&[("download_session_id", download_session)]);
+    session.send(request).await
+}
+
+pub async fn file(
+    session: &Session,
+    download_session: &str,
+    file_name: &str,
+) -> Result<FileInfo, Error> {
+    file_action(session, download_session, file_name, "get").await
+}
+
+// Request the file to be prepared for download, it is prepared in the
+// background
+pub async fn prepare(
+    session: &Session,
+    download_session: &str,
+    file_name: &str,
+) -> Result<FileInfo, Error> {
+    file_action(session, download_session, file_name, "prepare").await
+}
+
+// Prepare all files of the session and wait until they can be downloaded
+pub async fn prepare_all(
+    session: &Session,
+    download_session: &str,
+    poll_interval: Duration,
+) -> Result<Vec<FileInfo>, Error> {
+    let mut prepared = Vec::new();
+    for file in files(session, download_session).await? {
+        prepare(session, download_session, &file.name).await?;
+        loop {
+            let file = self::file(session, download_session, &file.name).await?;
+            match file.status {
+                PrepareStatus::Prepared => {
+                    prepared.push(file);
+                    break;
+                }
+                // Reported like a failed task, the file can't be prepared
+                PrepareStatus::Error => {
+                    return Err(Error::TaskFailed(ApiError {
+                        status: 500,
+                        error_type: ErrorType::Error,
+                        messages: file.error_message.into_iter().collect(),
+                    }))
+                }
+                _ => delay_for(poll_interval).await,
+            }
+        }
+    }
+    Ok(prepared)
+}
+
+// Download a prepared file as stream of chunks
+pub async fn download(
+    session: &Session,
+    file: &FileInfo,
+) -> Result<impl Stream<Item = Result<Bytes, Error>>, Error> {
+    let endpoint = file
+        .download_endpoint
+        .as_ref()
+        .ok_or_else(Error::not_found)?;
+    let request = session.client().get(&endpoint.uri).build()?;
+    let resp = session.dispatch(session.authorize(request)).await?;
+    match resp.status() {
+        status if status.is_success() => Ok(resp.bytes_stream().map_err(Error::from)),
+        StatusCode::NOT_FOUND => Err(Error::not_found()),
+        status => Err(Error::UnexpectedStatusCode(status.as_u16())),
+    }
+}
+
+async fn file_action(
+    session: &Session,
+    download_session: &str,
+    file_name: &str,
+    action: &str,
+) -> Result<FileInfo, Error> {
+    let request =
+        session.service_action(Method::POST, FILE_SERVICE, Some(download_session), action);
+    session
+        .send(session.json(request, &FileParams { file_name }))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileInfo, PrepareStatus};
+
+    #[test]
+    fn deserialize_file_info() {
+        let json = r#"{"name":"golden-ubuntu-2004.ovf","size":12482,"bytes_transferred":0,
+            "status":"PREPARED","checksum_info":{"algorithm":"SHA1","checksum":"a94a8fe5"},
+            "download_endpoint":{"uri":"https://vc.example.com:443/cls/data/5e7a/golden-ubuntu-2004.ovf"}}"#;
+        let file: FileInfo = serde_json::from_str(json).expect("deserialize");
+        assert_eq!(file.status, PrepareStatus::Prepared);
+        assert_eq!(
+            file.download_endpoint.unwrap().uri,
+            "https://vc.example.com:443/cls/data/5e7a/golden-ubuntu-2004.ovf"
+        );
+    }
+}
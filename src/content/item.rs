@@ -0,0 +1,140 @@
+use super::{ChecksumInfo, StorageBacking};
+use crate::cis::{ApiFlavor, Error, Session};
+use crate::id::{LibraryId, LibraryItemId};
+use chrono::prelude::*;
+use reqwest::Method;
+
+// Library item as returned by the get call, the type is e.g. "ovf", "iso" or "vm-template"
+#[derive(Deserialize, Debug, Clone)]
+pub struct ItemModel {
+    pub id: LibraryItemId,
+    pub library_id: LibraryId,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub item_type: Option<String>,
+    pub size: Option<u64>,
+    pub cached: Option<bool>,
+    pub content_version: Option<String>,
+    pub metadata_version: Option<String>,
+    pub version: Option<String>,
+    pub source_id: Option<String>,
+    pub creation_time: Option<DateTime<Utc>>,
+    pub last_modified_time: Option<DateTime<Utc>>,
+    pub last_sync_time: Option<DateTime<Utc>>,
+}
+
+// Specification of a new library item, its content is added with an update session
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub library_id: LibraryId,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<String>,
+}
+
+// File of a library item on its storage backing
+#[derive(Deserialize, Debug, Clone)]
+pub struct StorageInfo {
+    pub name: String,
+    pub storage_backing: StorageBacking,
+    #[serde(default)]
+    pub storage_uris: Vec<String>,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub cached: bool,
+    pub checksum_info: Option<ChecksumInfo>,
+    pub version: Option<String>,
+}
+
+// List the identifiers of the items of a library
+pub async fn list(session: &Session, library: &LibraryId) -> Result<Vec<LibraryItemId>, Error> {
+    let request = session
+        .request(
+            Method::GET,
+            &session.service_endpoint("content/library/item", None),
+        )
+        .query(&[("library_id", library.as_str())]);
+    session.send(request).await
+}
+
+pub async fn get(session: &Session, item: &LibraryItemId) -> Result<ItemModel, Error> {
+    let request = session.request(
+        Method::GET,
+        &session.service_endpoint("content/library/item", Some(item.as_str())),
+    );
+    session.send(request).await
+}
+
+// Create an empty library item and return its identifier
+pub async fn create(session: &Session, spec: &CreateSpec) -> Result<LibraryItemId, Error> {
+    let request = session.request(
+        Method::POST,
+        &session.service_endpoint("content/library/item", None),
+    );
+    session
+        .send(session.body(request, "create_spec", spec))
+        .await
+}
+
+pub async fn delete(session: &Session, item: &LibraryItemId) -> Result<(), Error> {
+    let request = session.request(
+        Method::DELETE,
+        &session.service_endpoint("content/library/item", Some(item.as_str())),
+    );
+    session.send_empty(request).await
+}
+
+// List the files of a library item on its storage backing
+pub async fn storage(session: &Session, item: &LibraryItemId) -> Result<Vec<StorageInfo>, Error> {
+    let request = match session.api_flavor() {
+        ApiFlavor::Rest => session
+            .request(
+                Method::GET,
+                &session.service_endpoint("content/library/item/storage", None),
+            )
+            .query(&[("library_item_id", item.as_str())]),
+        ApiFlavor::Api => session.request(
+            Method::GET,
+            &format!("/content/library/item/{}/storage", item),
+        ),
+    };
+    session.send(request).await
+}
+
+#[derive(Serialize)]
+struct SyncParams {
+    force_sync_content: bool,
+}
+
+// Synchronize an item of a subscribed library. Without force_sync_content the
+// content of an on demand library is only synchronized when it is used.
+pub async fn sync(
+    session: &Session,
+    item: &LibraryItemId,
+    force_sync_content: bool,
+) -> Result<(), Error> {
+    let request = session.service_action(
+        Method::POST,
+        "content/library/subscribed-item",
+        Some(item.as_str()),
+        "sync",
+    );
+    session
+        .send_empty(session.json(request, &SyncParams { force_sync_content }))
+        .await
+}
+
+// Remove the cached content of an item of an on demand subscribed library
+pub async fn evict(session: &Session, item: &LibraryItemId) -> Result<(), Error> {
+    let request = session.service_action(
+        Method::POST,
+        "content/library/subscribed-item",
+        Some(item.as_str()),
+        "evict",
+    );
+    session.send_empty(request).await
+}
@@ -0,0 +1,373 @@
+use super::StorageBacking;
+use crate::cis::{Error, LocalizableMessage, Session};
+use crate::common::redacted;
+use crate::id::LibraryId;
+use chrono::prelude::*;
+use reqwest::Method;
+use std::fmt;
+
+// Type of a content library
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LibraryType {
+    Local,
+    Subscribed,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Content library as returned by the get call
+#[derive(Deserialize, Debug, Clone)]
+pub struct LibraryModel {
+    pub id: LibraryId,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub library_type: LibraryType,
+    pub creation_time: Option<DateTime<Utc>>,
+    pub last_modified_time: Option<DateTime<Utc>>,
+    pub last_sync_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub storage_backings: Vec<StorageBacking>,
+    pub version: Option<String>,
+    pub server_guid: Option<String>,
+    pub publish_info: Option<PublishInfo>,
+    pub subscription_info: Option<SubscriptionInfo>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuthenticationMethod {
+    None,
+    Basic,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Publishing of a local library, subscribers use the publish URL. The
+// password is only sent and never returned.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct PublishInfo {
+    pub authentication_method: AuthenticationMethod,
+    #[serde(default)]
+    pub published: bool,
+    #[serde(skip_serializing)]
+    pub publish_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+impl fmt::Debug for PublishInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishInfo")
+            .field("authentication_method", &self.authentication_method)
+            .field("published", &self.published)
+            .field("publish_url", &self.publish_url)
+            .field("user_name", &self.user_name)
+            .field("password", &redacted(&self.password))
+            .finish()
+    }
+}
+
+// Subscription of a library to a published one. With on_demand only the
+// metadata is synchronized and the content of an item when it is used, with
+// automatic_sync_enabled vCenter synchronizes periodically.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct SubscriptionInfo {
+    #[serde(default)]
+    pub subscription_url: String,
+    pub authentication_method: AuthenticationMethod,
+    #[serde(default)]
+    pub on_demand: bool,
+    #[serde(default)]
+    pub automatic_sync_enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    // Thumbprint of the publisher certificate, required for untrusted ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssl_thumbprint: Option<String>,
+}
+
+impl fmt::Debug for SubscriptionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubscriptionInfo")
+            .field("subscription_url", &self.subscription_url)
+            .field("authentication_method", &self.authentication_method)
+            .field("on_demand", &self.on_demand)
+            .field("automatic_sync_enabled", &self.automatic_sync_enabled)
+            .field("user_name", &self.user_name)
+            .field("password", &redacted(&self.password))
+            .field("ssl_thumbprint", &self.ssl_thumbprint)
+            .finish()
+    }
+}
+
+impl SubscriptionInfo {
+    pub fn new(subscription_url: impl Into<String>) -> Self {
+        SubscriptionInfo {
+            subscription_url: subscription_url.into(),
+            authentication_method: AuthenticationMethod::None,
+            on_demand: true,
+            automatic_sync_enabled: true,
+            user_name: None,
+            password: None,
+            ssl_thumbprint: None,
+        }
+    }
+}
+
+// Outcome of probing a subscription URL
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProbeStatus {
+    Success,
+    InvalidUrl,
+    TimedOut,
+    HostNotFound,
+    ResourceNotFound,
+    InvalidCredentials,
+    CertificateError,
+    UnknownError,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProbeResult {
+    pub status: ProbeStatus,
+    pub ssl_thumbprint: Option<String>,
+    #[serde(default)]
+    pub error_messages: Vec<LocalizableMessage>,
+}
+
+// Specification of a new local library
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub storage_backings: Vec<StorageBacking>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_info: Option<PublishInfo>,
+}
+
+// Specification of a new subscribed library
+#[derive(Serialize, Debug, Clone)]
+pub struct SubscribedCreateSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub storage_backings: Vec<StorageBacking>,
+    pub subscription_info: SubscriptionInfo,
+}
+
+#[derive(Serialize)]
+struct SubscriptionUpdateSpec<'a> {
+    subscription_info: &'a SubscriptionInfo,
+}
+
+#[derive(Serialize)]
+struct PublishUpdateSpec<'a> {
+    publish_info: &'a PublishInfo,
+}
+
+// Criteria for the find call, unset fields match every library
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct FindSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub library_type: Option<LibraryType>,
+}
+
+// List the identifiers of all libraries
+pub async fn list(session: &Session) -> Result<Vec<LibraryId>, Error> {
+    let request = session.request(
+        Method::GET,
+        &session.service_endpoint("content/library", None),
+    );
+    session.send(request).await
+}
+
+pub async fn get(session: &Session, library: &LibraryId) -> Result<LibraryModel, Error> {
+    let request = session.request(
+        Method::GET,
+        &session.service_endpoint("content/library", Some(library.as_str())),
+    );
+    session.send(request).await
+}
+
+// Find the identifiers of the libraries matching the criteria
+pub async fn find(session: &Session, spec: &FindSpec) -> Result<Vec<LibraryId>, Error> {
+    let request = session.service_action(Method::POST, "content/library", None, "find");
+    session.send(session.body(request, "spec", spec)).await
+}
+
+// Create a local library and return its identifier
+pub async fn create_local(session: &Session, spec: &CreateSpec) -> Result<LibraryId, Error> {
+    let request = session.request(
+        Method::POST,
+        &session.service_endpoint("content/local-library", None),
+    );
+    session
+        .send(session.body(request, "create_spec", spec))
+        .await
+}
+
+// Delete a local library including all its items
+pub async fn delete_local(session: &Session, library: &LibraryId) -> Result<(), Error> {
+    let request = session.request(
+        Method::DELETE,
+        &session.service_endpoint("content/local-library", Some(library.as_str())),
+    );
+    session.send_empty(request).await
+}
+
+// Change the publishing of a local library
+pub async fn update_publish_info(
+    session: &Session,
+    library: &LibraryId,
+    publish_info: &PublishInfo,
+) -> Result<(), Error> {
+    let request = session.request(
+        Method::PATCH,
+        &session.service_endpoint("content/local-library", Some(library.as_str())),
+    );
+    let spec = PublishUpdateSpec { publish_info };
+    session
+        .send_empty(session.body(request, "update_spec", &spec))
+        .await
+}
+
+// Create a subscribed library and return its identifier, with an immediate
+// subscription the content is synchronized in the background
+pub async fn create_subscribed(
+    session: &Session,
+    spec: &SubscribedCreateSpec,
+) -> Result<LibraryId, Error> {
+    let request = session.request(
+        Method::POST,
+        &session.service_endpoint("content/subscribed-library", None),
+    );
+    session
+        .send(session.body(request, "create_spec", spec))
+        .await
+}
+
+// Change the subscription of a subscribed library, e.g. its URL or credentials
+pub async fn update_subscription(
+    session: &Session,
+    library: &LibraryId,
+    subscription_info: &SubscriptionInfo,
+) -> Result<(), Error> {
+    let request = session.request(
+        Method::PATCH,
+        &session.service_endpoint("content/subscribed-library", Some(library.as_str())),
+    );
+    let spec = SubscriptionUpdateSpec { subscription_info };
+    session
+        .send_empty(session.body(request, "update_spec", &spec))
+        .await
+}
+
+// Delete a subscribed library, the published library is not changed
+pub async fn delete_subscribed(session: &Session, library: &LibraryId) -> Result<(), Error> {
+    let request = session.request(
+        Method::DELETE,
+        &session.service_endpoint("content/subscribed-library", Some(library.as_str())),
+    );
+    session.send_empty(request).await
+}
+
+// Synchronize a subscribed library with the published one. This only starts
+// the synchronization, its end is visible in the last_sync_time of the library.
+pub async fn sync(session: &Session, library: &LibraryId) -> Result<(), Error> {
+    subscribed_action(session, library, "sync").await
+}
+
+// Remove the cached content of an on demand subscribed library, the metadata
+// of the items is kept
+pub async fn evict(session: &Session, library: &LibraryId) -> Result<(), Error> {
+    subscribed_action(session, library, "evict").await
+}
+
+// Check whether a subscription URL can be reached with the credentials, the
+// result contains the thumbprint of an untrusted publisher certificate
+pub async fn probe(
+    session: &Session,
+    subscription_info: &SubscriptionInfo,
+) -> Result<ProbeResult, Error> {
+    let request = session.service_action(Method::POST, "content/subscribed-library", None, "probe");
+    let spec = SubscriptionUpdateSpec { subscription_info };
+    session.send(session.json(request, &spec)).await
+}
+
+async fn subscribed_action(
+    session: &Session,
+    library: &LibraryId,
+    action: &str,
+) -> Result<(), Error> {
+    let request = session.service_action(
+        Method::POST,
+        "content/subscribed-library",
+        Some(library.as_str()),
+        action,
+    );
+    session.send_empty(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthenticationMethod, LibraryModel, SubscribedCreateSpec, SubscriptionInfo};
+
+    #[test]
+    fn serialize_subscribed_create_spec() {
+        let mut subscription_info =
+            SubscriptionInfo::new("https://publisher.example.com:443/cls/vcsp/lib/4c3f/lib.json");
+        subscription_info.authentication_method = AuthenticationMethod::Basic;
+        subscription_info.user_name = Some("vcsp".to_owned());
+        subscription_info.password = Some("secret".to_owned());
+        let spec = SubscribedCreateSpec {
+            name: "images".to_owned(),
+            description: None,
+            storage_backings: Vec::new(),
+            subscription_info,
+        };
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "name": "images",
+                "storage_backings": [],
+                "subscription_info": {
+                    "subscription_url": "https://publisher.example.com:443/cls/vcsp/lib/4c3f/lib.json",
+                    "authentication_method": "BASIC",
+                    "on_demand": true,
+                    "automatic_sync_enabled": true,
+                    "user_name": "vcsp",
+                    "password": "secret",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_subscribed_library() {
+        let json = r#"{"id":"8d1f1a5e-3b9c-4f3e-9d52-2a3c5d1e6f70","name":"images","type":"SUBSCRIBED",
+            "storage_backings":[],"subscription_info":{"subscription_url":"https://publisher.example.com/lib.json",
+            "authentication_method":"NONE","on_demand":false,"automatic_sync_enabled":true}}"#;
+        let library: LibraryModel = serde_json::from_str(json).expect("deserialize");
+        let subscription_info = library.subscription_info.unwrap();
+        assert!(!subscription_info.on_demand);
+        assert_eq!(
+            subscription_info.authentication_method,
+            AuthenticationMethod::None
+        );
+    }
+}
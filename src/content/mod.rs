@@ -0,0 +1,68 @@
+// Modules for the Content Library service
+pub mod download;
+pub mod item;
+pub mod library;
+pub mod ovf;
+pub mod upload;
+
+use crate::id::DatastoreId;
+
+// Checksum algorithm of a library item file
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChecksumAlgorithm {
+    Sha1,
+    Md5,
+    Sha256,
+    Sha512,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ChecksumInfo {
+    pub algorithm: Option<ChecksumAlgorithm>,
+    #[serde(default)]
+    pub checksum: String,
+}
+
+// URI a file of a download or update session is transferred from or to
+#[derive(Deserialize, Debug, Clone)]
+pub struct TransferEndpoint {
+    #[serde(default)]
+    pub uri: String,
+    pub ssl_certificate_thumbprint: Option<String>,
+}
+
+// Type of the storage backing of a library
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StorageBackingType {
+    Datastore,
+    Other,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Storage backing of a library, either a datastore or a storage URI
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct StorageBacking {
+    #[serde(rename = "type")]
+    pub backing_type: StorageBackingType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore_id: Option<DatastoreId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_uri: Option<String>,
+}
+
+impl StorageBacking {
+    pub fn datastore(datastore_id: impl Into<DatastoreId>) -> Self {
+        StorageBacking {
+            backing_type: StorageBackingType::Datastore,
+            datastore_id: Some(datastore_id.into()),
+            storage_uri: None,
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::cis::{ApiFlavor, DynamicId, Error, Session};
+use crate::common::serialize_map;
+use crate::id::{DatastoreId, FolderId, HostId, LibraryId, LibraryItemId, ResourcePoolId};
+use reqwest::Method;
+use std::collections::HashMap;
+
+// Where a library item is deployed to, the resource pool is required
+#[derive(Serialize, Debug, Clone)]
+pub struct DeploymentTarget {
+    pub resource_pool_id: ResourcePoolId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_id: Option<HostId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<FolderId>,
+}
+
+impl DeploymentTarget {
+    pub fn new(resource_pool_id: impl Into<ResourcePoolId>) -> Self {
+        DeploymentTarget {
+            resource_pool_id: resource_pool_id.into(),
+            host_id: None,
+            folder_id: None,
+        }
+    }
+}
+
+// Deployment of an OVF package into a resource pool. The network mappings
+// map the network names of the OVF descriptor to network identifiers, the
+// additional parameters are passed through as is (e.g. PropertyParams with
+// an "@class" member).
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct ResourcePoolDeploymentSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<String>,
+    #[serde(rename = "accept_all_EULA")]
+    pub accept_all_eula: bool,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub network_mappings: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_provisioning: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_profile_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub additional_parameters: Vec<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_datastore_id: Option<DatastoreId>,
+}
+
+// Errors, warnings and information of a deployment, the entries are of
+// various OVF specific types and therefore kept as JSON
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ResultInfo {
+    #[serde(default)]
+    pub errors: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub warnings: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub information: Vec<serde_json::Value>,
+}
+
+// Outcome of a deployment, the resource is the deployed virtual machine or vApp
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeploymentResult {
+    #[serde(default)]
+    pub succeeded: bool,
+    pub resource_id: Option<DynamicId>,
+    pub error: Option<ResultInfo>,
+}
+
+// Library item an OVF package is captured into, a new item is created unless
+// an existing item is given
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateTarget {
+    pub library_id: LibraryId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library_item_id: Option<LibraryItemId>,
+}
+
+impl CreateTarget {
+    pub fn new(library_id: impl Into<LibraryId>) -> Self {
+        CreateTarget {
+            library_id: library_id.into(),
+            library_item_id: None,
+        }
+    }
+}
+
+// Name of the captured OVF package, the flags are e.g. "EXTRA_CONFIG" to
+// keep the advanced settings of the virtual machine
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
+}
+
+// Outcome of a capture, the item holds the OVF descriptor and the disks
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateResult {
+    #[serde(default)]
+    pub succeeded: bool,
+    pub ovf_library_item_id: Option<LibraryItemId>,
+    pub error: Option<ResultInfo>,
+}
+
+#[derive(Serialize)]
+struct CreateParams<'a> {
+    source: &'a DynamicId,
+    target: &'a CreateTarget,
+    create_spec: &'a CreateSpec,
+}
+
+// Capture a virtual machine or vApp as OVF package into a content library,
+// e.g. to archive a golden image. A failed capture is reported in the result.
+pub async fn capture(
+    session: &Session,
+    source: &DynamicId,
+    target: &CreateTarget,
+    spec: &CreateSpec,
+) -> Result<CreateResult, Error> {
+    let params = CreateParams {
+        source,
+        target,
+        create_spec: spec,
+    };
+    let request = match session.api_flavor() {
+        ApiFlavor::Rest => {
+            session.service_action(Method::POST, "vcenter/ovf/library-item", None, "create")
+        }
+        ApiFlavor::Api => session.request(Method::POST, "/vcenter/ovf/library-item"),
+    };
+    session.send(session.json(request, &params)).await
+}
+
+#[derive(Serialize)]
+struct DeployParams<'a> {
+    target: &'a DeploymentTarget,
+    deployment_spec: &'a ResourcePoolDeploymentSpec,
+}
+
+// Handle to an OVF package in a content library
+pub struct LibraryItem<'a> {
+    session: &'a Session,
+    id: LibraryItemId,
+}
+
+impl<'a> LibraryItem<'a> {
+    pub fn new(session: &'a Session, id: impl Into<LibraryItemId>) -> Self {
+        LibraryItem {
+            session,
+            id: id.into(),
+        }
+    }
+
+    pub fn id(&self) -> &LibraryItemId {
+        &self.id
+    }
+
+    // Deploy the OVF package, a failed deployment is reported in the result
+    pub async fn deploy(
+        &self,
+        target: &DeploymentTarget,
+        spec: &ResourcePoolDeploymentSpec,
+    ) -> Result<DeploymentResult, Error> {
+        let params = DeployParams {
+            target,
+            deployment_spec: spec,
+        };
+        let request = self.session.service_action(
+            Method::POST,
+            "vcenter/ovf/library-item",
+            Some(self.id.as_str()),
+            "deploy",
+        );
+        let request = self.session.json(request, &params);
+        self.session.send(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CreateParams, CreateResult, CreateSpec, CreateTarget, DeployParams, DeploymentTarget,
+        ResourcePoolDeploymentSpec,
+    };
+    use crate::cis::{ApiFlavor, DynamicId};
+    use crate::common::WithFlavor;
+
+    #[test]
+    fn deploy_params_per_flavor() {
+        let target = DeploymentTarget::new("resgroup-8");
+        let mut spec = ResourcePoolDeploymentSpec {
+            name: Some("appliance".into()),
+            accept_all_eula: true,
+            ..Default::default()
+        };
+        spec.network_mappings
+            .insert("VM Network".into(), "network-11".into());
+        let params = DeployParams {
+            target: &target,
+            deployment_spec: &spec,
+        };
+        assert_eq!(
+            serde_json::to_value(WithFlavor(ApiFlavor::Rest, &params)).expect("serialize"),
+            serde_json::json!({
+                "target": {"resource_pool_id": "resgroup-8"},
+                "deployment_spec": {
+                    "name": "appliance",
+                    "accept_all_EULA": true,
+                    "network_mappings": [{"key": "VM Network", "value": "network-11"}],
+                },
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(WithFlavor(ApiFlavor::Api, &params)).expect("serialize")
+                ["deployment_spec"]["network_mappings"],
+            serde_json::json!({"VM Network": "network-11"})
+        );
+    }
+
+    #[test]
+    fn capture_params() {
+        let source = DynamicId::new("VirtualMachine", "vm-42");
+        let target = CreateTarget::new("2e1d3b1a-5c6f-4e0a-9d3b-8f7a6c5b4e3d");
+        let spec = CreateSpec {
+            name: Some("golden-ubuntu-2004".into()),
+            flags: vec!["EXTRA_CONFIG".into()],
+            ..Default::default()
+        };
+        let params = CreateParams {
+            source: &source,
+            target: &target,
+            create_spec: &spec,
+        };
+        assert_eq!(
+            serde_json::to_value(&params).expect("serialize"),
+            serde_json::json!({
+                "source": {"type": "VirtualMachine", "id": "vm-42"},
+                "target": {"library_id": "2e1d3b1a-5c6f-4e0a-9d3b-8f7a6c5b4e3d"},
+                "create_spec": {"name": "golden-ubuntu-2004", "flags": ["EXTRA_CONFIG"]},
+            })
+        );
+
+        let json = r#"{"succeeded":true,"ovf_library_item_id":"6b9a5c3e-1d2f-4a7b-8c9d-0e1f2a3b4c5d",
+            "error":{"errors":[],"warnings":[],"information":[]}}"#;
+        let result: CreateResult = serde_json::from_str(json).expect("deserialize");
+        assert!(result.succeeded);
+        assert_eq!(
+            result.ovf_library_item_id.unwrap(),
+            "6b9a5c3e-1d2f-4a7b-8c9d-0e1f2a3b4c5d"
+        );
+    }
+}
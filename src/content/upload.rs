@@ -0,0 +1,414 @@
+// Update sessions of library items. Files are added to a session, uploaded
+// to their endpoint and validated before the session is completed, e.g. an
+// ISO image or the OVA of an OVF package.
+use super::{ChecksumInfo, TransferEndpoint};
+use crate::cancel::CancelHandle;
+use crate::cis::{ApiError, Error, ErrorType, LocalizableMessage, Session};
+use crate::id::LibraryItemId;
+use bytes::Bytes;
+use futures::future::{self, Either};
+use futures::stream::{self, Stream};
+use reqwest::header::CONTENT_LENGTH;
+use reqwest::{Body, Method, StatusCode};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::time::delay_for;
+
+const SESSION_SERVICE: &str = "content/library/item/update-session";
+const FILE_SERVICE: &str = "content/library/item/updatesession/file";
+const CHUNK_SIZE: usize = 1024 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SessionState {
+    Active,
+    Done,
+    Error,
+    Canceled,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UpdateSessionModel {
+    pub id: String,
+    pub library_item_id: LibraryItemId,
+    pub state: SessionState,
+    pub client_progress: Option<i64>,
+    pub error_message: Option<LocalizableMessage>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FileStatus {
+    WaitingForTransfer,
+    Transferring,
+    Ready,
+    Validating,
+    Error,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// File pushed by the client. With a checksum vCenter validates the
+// uploaded content, the size is used to report progress.
+#[derive(Serialize, Debug, Clone)]
+pub struct FileSpec {
+    pub name: String,
+    source_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum_info: Option<ChecksumInfo>,
+}
+
+impl FileSpec {
+    pub fn new(name: impl Into<String>, size: Option<u64>) -> Self {
+        FileSpec {
+            name: name.into(),
+            source_type: "PUSH",
+            size,
+            checksum_info: None,
+        }
+    }
+}
+
+// File of an update session, the endpoint is where the content is uploaded to
+#[derive(Deserialize, Debug, Clone)]
+pub struct FileInfo {
+    pub name: String,
+    pub size: Option<u64>,
+    pub bytes_transferred: Option<u64>,
+    pub status: FileStatus,
+    pub checksum_info: Option<ChecksumInfo>,
+    pub error_message: Option<LocalizableMessage>,
+    pub upload_endpoint: Option<TransferEndpoint>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct InvalidFile {
+    pub name: String,
+    pub error_message: LocalizableMessage,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ValidationResult {
+    #[serde(default)]
+    pub has_errors: bool,
+    #[serde(default)]
+    pub missing_files: Vec<String>,
+    #[serde(default)]
+    pub invalid_files: Vec<InvalidFile>,
+}
+
+#[derive(Serialize)]
+struct UpdateSessionCreateModel<'a> {
+    library_item_id: &'a LibraryItemId,
+}
+
+// Create an update session for the library item, returns its identifier
+pub async fn create(session: &Session, item: &LibraryItemId) -> Result<String, Error> {
+    let request = session.request(
+        Method::POST,
+        &session.service_endpoint(SESSION_SERVICE, None),
+    );
+    let model = UpdateSessionCreateModel {
+        library_item_id: item,
+    };
+    session
+        .send(session.body(request, "create_spec", &model))
+        .await
+}
+
+pub async fn get(session: &Session, update_session: &str) -> Result<UpdateSessionModel, Error> {
+    let request = session.request(
+        Method::GET,
+        &session.service_endpoint(SESSION_SERVICE, Some(update_session)),
+    );
+    session.send(request).await
+}
+
+// Complete the session, the files are added to the library item in the
+// background until the session is done
+pub async fn complete(session: &Session, update_session: &str) -> Result<(), Error> {
+    session_action(session, update_session, "complete").await
+}
+
+// Cancel the session, the uploaded files are discarded
+pub async fn cancel(session: &Session, update_session: &str) -> Result<(), Error> {
+    session_action(session, update_session, "cancel").await
+}
+
+pub async fn delete(session: &Session, update_session: &str) -> Result<(), Error> {
+    let request = session.request(
+        Method::DELETE,
+        &session.service_endpoint(SESSION_SERVICE, Some(update_session)),
+    );
+    session.send_empty(request).await
+}
+
+// Add a file to the session, returns it with its upload endpoint
+pub async fn add_file(
+    session: &Session,
+    update_session: &str,
+    spec: &FileSpec,
+) -> Result<FileInfo, Error> {
+    let request = session.service_action(Method::POST, FILE_SERVICE, Some(update_session), "add");
+    session.send(session.body(request, "file_spec", spec)).await
+}
+
+// Check that all files are uploaded and match their checksums
+pub async fn validate(session: &Session, update_session: &str) -> Result<ValidationResult, Error> {
+    let request =
+        session.service_action(Method::POST, FILE_SERVICE, Some(update_session), "validate");
+    session.send(request).await
+}
+
+// Upload the content of a file from the reader, the progress callback gets
+// the number of bytes sent so far
+pub async fn upload_file<R, P>(
+    session: &Session,
+    file: &FileInfo,
+    reader: R,
+    progress: P,
+) -> Result<(), Error>
+where
+    R: AsyncRead + Send + Sync + Unpin + 'static,
+    P: Fn(u64) + Send + Sync + 'static,
+{
+    let endpoint = file.upload_endpoint.as_ref().ok_or_else(Error::not_found)?;
+    let mut request = session
+        .client()
+        .put(&endpoint.uri)
+        .body(Body::wrap_stream(chunks(reader, progress)));
+    if let Some(size) = file.size {
+        request = request.header(CONTENT_LENGTH, size);
+    }
+    let resp = session
+        .dispatch(session.authorize(request.build()?))
+        .await?;
+    match resp.status() {
+        status if status.is_success() => Ok(()),
+        StatusCode::NOT_FOUND => Err(Error::not_found()),
+        status => Err(Error::UnexpectedStatusCode(status.as_u16())),
+    }
+}
+
+// Upload a single file to the library item and wait up to the timeout until
+// it is added. The session is canceled if the upload or its validation fails.
+pub async fn upload<R, P>(
+    session: &Session,
+    item: &LibraryItemId,
+    spec: &FileSpec,
+    reader: R,
+    progress: P,
+    timeout: Duration,
+) -> Result<(), Error>
+where
+    R: AsyncRead + Send + Sync + Unpin + 'static,
+    P: Fn(u64) + Send + Sync + 'static,
+{
+    let cancel_handle = CancelHandle::new();
+    upload_with_cancel(
+        session,
+        item,
+        spec,
+        reader,
+        progress,
+        timeout,
+        &cancel_handle,
+    )
+    .await
+}
+
+// Like upload, when the handle is canceled the session is canceled too and
+// the upload fails with Error::Canceled. A timeout leaves the session as is,
+// the file may still be added.
+pub async fn upload_with_cancel<R, P>(
+    session: &Session,
+    item: &LibraryItemId,
+    spec: &FileSpec,
+    reader: R,
+    progress: P,
+    timeout: Duration,
+    cancel_handle: &CancelHandle,
+) -> Result<(), Error>
+where
+    R: AsyncRead + Send + Sync + Unpin + 'static,
+    P: Fn(u64) + Send + Sync + 'static,
+{
+    let update_session = create(session, item).await?;
+    let transfer = async {
+        let file = add_file(session, &update_session, spec).await?;
+        upload_file(session, &file, reader, progress).await?;
+        let validation = validate(session, &update_session).await?;
+        if validation.has_errors {
+            return Err(failed(validation_messages(validation)));
+        }
+        complete(session, &update_session).await
+    };
+    futures::pin_mut!(transfer);
+    let canceled = cancel_handle.canceled();
+    futures::pin_mut!(canceled);
+    let result = match future::select(transfer, canceled).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(Error::Canceled),
+    };
+    if let Err(err) = result {
+        // The original error is more useful than a failed cancel
+        let _ = cancel(session, &update_session).await;
+        return Err(err);
+    }
+    let deadline = Instant::now() + timeout;
+    loop {
+        let model = get(session, &update_session).await?;
+        match model.state {
+            SessionState::Done => return Ok(()),
+            SessionState::Active | SessionState::Unknown => {}
+            SessionState::Error | SessionState::Canceled => {
+                return Err(failed(model.error_message.into_iter().collect()))
+            }
+        }
+        if Instant::now() + POLL_INTERVAL > deadline {
+            return Err(Error::Timeout);
+        }
+        let delay = delay_for(POLL_INTERVAL);
+        let canceled = cancel_handle.canceled();
+        futures::pin_mut!(canceled);
+        if let Either::Right(_) = future::select(delay, canceled).await {
+            // Best effort, the session may be done in the meantime
+            let _ = cancel(session, &update_session).await;
+            return Err(Error::Canceled);
+        }
+    }
+}
+
+// Messages of the invalid files and one for each missing file, which the
+// validation only reports by name
+fn validation_messages(validation: ValidationResult) -> Vec<LocalizableMessage> {
+    let missing = validation
+        .missing_files
+        .into_iter()
+        .map(|name| LocalizableMessage {
+            id: "missing_file".to_owned(),
+            default_message: format!("File {} is missing", name),
+            args: vec![name],
+        });
+    validation
+        .invalid_files
+        .into_iter()
+        .map(|file| file.error_message)
+        .chain(missing)
+        .collect()
+}
+
+async fn session_action(
+    session: &Session,
+    update_session: &str,
+    action: &str,
+) -> Result<(), Error> {
+    let request =
+        session.service_action(Method::POST, SESSION_SERVICE, Some(update_session), action);
+    session.send_empty(request).await
+}
+
+// Read the content in chunks and report the bytes read so far
+fn chunks<R, P>(reader: R, progress: P) -> impl Stream<Item = Result<Bytes, std::io::Error>>
+where
+    R: AsyncRead + Unpin,
+    P: Fn(u64),
+{
+    stream::try_unfold(
+        (reader, progress, 0u64),
+        |(mut reader, progress, sent)| async move {
+            let mut chunk = vec![0; CHUNK_SIZE];
+            let read = reader.read(&mut chunk).await?;
+            if read == 0 {
+                return Ok(None);
+            }
+            chunk.truncate(read);
+            let sent = sent + read as u64;
+            progress(sent);
+            Ok(Some((Bytes::from(chunk), (reader, progress, sent))))
+        },
+    )
+}
+
+// Failed session, reported like a failed task
+fn failed(messages: Vec<LocalizableMessage>) -> Error {
+    Error::TaskFailed(ApiError {
+        status: 500,
+        error_type: ErrorType::Error,
+        messages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chunks, validation_messages, FileSpec, ValidationResult, CHUNK_SIZE};
+    use crate::content::{ChecksumAlgorithm, ChecksumInfo};
+    use futures::stream::TryStreamExt;
+    use std::io::Cursor;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn chunks_report_progress() {
+        let content = vec![7u8; CHUNK_SIZE + 10];
+        let sent = Arc::new(AtomicU64::new(0));
+        let progress = {
+            let sent = sent.clone();
+            move |bytes| sent.store(bytes, Ordering::SeqCst)
+        };
+        let chunks: Vec<_> = chunks(Cursor::new(content), progress)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.len()).sum::<usize>(),
+            CHUNK_SIZE + 10
+        );
+        assert_eq!(sent.load(Ordering::SeqCst), (CHUNK_SIZE + 10) as u64);
+    }
+
+    #[test]
+    fn file_spec_and_validation() {
+        let mut spec = FileSpec::new("ubuntu-20.04.iso", Some(1_048_576));
+        spec.checksum_info = Some(ChecksumInfo {
+            algorithm: Some(ChecksumAlgorithm::Sha256),
+            checksum: "9f86d081".to_owned(),
+        });
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "name": "ubuntu-20.04.iso",
+                "source_type": "PUSH",
+                "size": 1_048_576,
+                "checksum_info": {"algorithm": "SHA256", "checksum": "9f86d081"},
+            })
+        );
+
+        let json = r#"{"has_errors":true,"missing_files":[],"invalid_files":[{"name":"ubuntu-20.04.iso",
+            "error_message":{"id":"com.vmware.vdcs.cls-main.file_checksum_mismatch",
+            "default_message":"Checksum mismatch","args":[]}}]}"#;
+        let result: ValidationResult = serde_json::from_str(json).expect("deserialize");
+        assert!(result.has_errors);
+        assert_eq!(result.invalid_files[0].name, "ubuntu-20.04.iso");
+    }
+
+    #[test]
+    fn missing_files_are_reported() {
+        let json = r#"{"has_errors":true,"missing_files":["ubuntu-20.04.iso"],"invalid_files":[]}"#;
+        let result: ValidationResult = serde_json::from_str(json).expect("deserialize");
+        let messages = validation_messages(result);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].args, vec!["ubuntu-20.04.iso"]);
+        assert_eq!(
+            messages[0].default_message,
+            "File ubuntu-20.04.iso is missing"
+        );
+    }
+}
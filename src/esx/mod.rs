@@ -0,0 +1,2 @@
+// Desired software image of clusters managed by vSphere Lifecycle Manager
+pub mod software;
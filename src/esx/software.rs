@@ -0,0 +1,354 @@
+// Desired software image of a cluster managed by vSphere Lifecycle Manager,
+// which is only served with ApiFlavor::Api and used with it whatever the
+// flavor of the session. The image is changed through a draft which is
+// committed, hosts are scanned against it and remediated. Scans and
+// remediations run as tasks, see cis::tasks::wait_for_task.
+use crate::cis::capabilities::Feature;
+use crate::cis::{Error, Session};
+use crate::id::{ClusterId, HostId};
+use chrono::prelude::*;
+use reqwest::Method;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BaseImageDetails {
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub display_version: String,
+    pub release_date: Option<DateTime<Utc>>,
+}
+
+// ESXi base image, e.g. version "7.0.3-0.50.20036589"
+#[derive(Deserialize, Debug, Clone)]
+pub struct BaseImageInfo {
+    #[serde(default)]
+    pub version: String,
+    pub details: Option<BaseImageDetails>,
+}
+
+// Vendor add-on on top of the base image
+#[derive(Deserialize, Debug, Clone)]
+pub struct AddOnInfo {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ComponentInfo {
+    #[serde(default)]
+    pub version: String,
+}
+
+// Desired image of a cluster, components are keyed by their name
+#[derive(Deserialize, Debug, Clone)]
+pub struct SoftwareInfo {
+    pub base_image: BaseImageInfo,
+    pub add_on: Option<AddOnInfo>,
+    #[serde(default)]
+    pub components: HashMap<String, ComponentInfo>,
+}
+
+// Desired image to set, components missing from it are removed from the
+// current image only if listed in remove_components
+#[derive(Debug, Clone, Default)]
+pub struct DesiredImage {
+    pub base_image_version: Option<String>,
+    // Name and version of the vendor add-on
+    pub add_on: Option<(String, String)>,
+    pub components: HashMap<String, String>,
+    pub remove_components: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ComplianceStatus {
+    Compliant,
+    NonCompliant,
+    Incompatible,
+    Unavailable,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// What the remediation of a host requires
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ComplianceImpact {
+    NoImpact,
+    MaintenanceModeRequired,
+    RebootRequired,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HostCompliance {
+    pub status: ComplianceStatus,
+    pub impact: ComplianceImpact,
+    pub scan_time: Option<DateTime<Utc>>,
+}
+
+// Result of the last scan of the cluster against its desired image
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClusterCompliance {
+    pub status: ComplianceStatus,
+    pub impact: ComplianceImpact,
+    pub scan_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub hosts: HashMap<HostId, HostCompliance>,
+    #[serde(default)]
+    pub compliant_hosts: Vec<HostId>,
+    #[serde(default)]
+    pub non_compliant_hosts: Vec<HostId>,
+    #[serde(default)]
+    pub incompatible_hosts: Vec<HostId>,
+    #[serde(default)]
+    pub unavailable_hosts: Vec<HostId>,
+    pub notifications: Option<serde_json::Value>,
+}
+
+// Remediation of the cluster or some of its hosts, by default with the
+// latest commit of the desired image
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ApplySpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<HostId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept_eula: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct BaseImageSpec<'a> {
+    version: &'a str,
+}
+
+#[derive(Serialize)]
+struct AddOnSpec<'a> {
+    name: &'a str,
+    version: &'a str,
+}
+
+#[derive(Serialize)]
+struct ComponentsUpdateSpec<'a> {
+    components_to_set: &'a HashMap<String, String>,
+    components_to_delete: &'a [String],
+}
+
+#[derive(Serialize)]
+struct CommitSpec<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+}
+
+pub async fn get(session: &Session, cluster: &ClusterId) -> Result<SoftwareInfo, Error> {
+    let session = &api_session(session).await?;
+    let request = session.request(Method::GET, &endpoint(cluster, ""));
+    session.send(request).await
+}
+
+// Set the desired image through a draft and return the identifier of the
+// commit, the draft is deleted if a change fails
+pub async fn set(
+    session: &Session,
+    cluster: &ClusterId,
+    image: &DesiredImage,
+    message: Option<&str>,
+) -> Result<String, Error> {
+    let draft = create_draft(session, cluster).await?;
+    let result = async {
+        if let Some(version) = &image.base_image_version {
+            set_base_image(session, cluster, &draft, version).await?;
+        }
+        if let Some((name, version)) = &image.add_on {
+            set_add_on(session, cluster, &draft, name, version).await?;
+        }
+        if !image.components.is_empty() || !image.remove_components.is_empty() {
+            update_components(
+                session,
+                cluster,
+                &draft,
+                &image.components,
+                &image.remove_components,
+            )
+            .await?;
+        }
+        commit_draft(session, cluster, &draft, message).await
+    }
+    .await;
+    if result.is_err() {
+        // The original error is more useful than a failed delete
+        let _ = delete_draft(session, cluster, &draft).await;
+    }
+    result
+}
+
+// Create a draft of the desired image and return its identifier
+pub async fn create_draft(session: &Session, cluster: &ClusterId) -> Result<String, Error> {
+    let session = &api_session(session).await?;
+    let request = session.request(Method::POST, &endpoint(cluster, "/drafts"));
+    session.send(request).await
+}
+
+pub async fn delete_draft(
+    session: &Session,
+    cluster: &ClusterId,
+    draft: &str,
+) -> Result<(), Error> {
+    let session = &api_session(session).await?;
+    let request = session.request(Method::DELETE, &draft_endpoint(cluster, draft, ""));
+    session.send_empty(request).await
+}
+
+// Set the ESXi version of the draft, e.g. "7.0.3-0.50.20036589"
+pub async fn set_base_image(
+    session: &Session,
+    cluster: &ClusterId,
+    draft: &str,
+    version: &str,
+) -> Result<(), Error> {
+    let session = &api_session(session).await?;
+    let request = session.request(
+        Method::PUT,
+        &draft_endpoint(cluster, draft, "/software/base-image"),
+    );
+    session
+        .send_empty(session.json(request, &BaseImageSpec { version }))
+        .await
+}
+
+pub async fn set_add_on(
+    session: &Session,
+    cluster: &ClusterId,
+    draft: &str,
+    name: &str,
+    version: &str,
+) -> Result<(), Error> {
+    let session = &api_session(session).await?;
+    let request = session.request(
+        Method::PUT,
+        &draft_endpoint(cluster, draft, "/software/add-on"),
+    );
+    session
+        .send_empty(session.json(request, &AddOnSpec { name, version }))
+        .await
+}
+
+// Add or update components by name and version and delete others by name
+pub async fn update_components(
+    session: &Session,
+    cluster: &ClusterId,
+    draft: &str,
+    components_to_set: &HashMap<String, String>,
+    components_to_delete: &[String],
+) -> Result<(), Error> {
+    let session = &api_session(session).await?;
+    let request = session.request(
+        Method::PATCH,
+        &draft_endpoint(cluster, draft, "/software/components"),
+    );
+    let spec = ComponentsUpdateSpec {
+        components_to_set,
+        components_to_delete,
+    };
+    session.send_empty(session.json(request, &spec)).await
+}
+
+// Make the draft the desired image and return the identifier of the commit
+pub async fn commit_draft(
+    session: &Session,
+    cluster: &ClusterId,
+    draft: &str,
+    message: Option<&str>,
+) -> Result<String, Error> {
+    let session = &api_session(session).await?;
+    let request = session.action(Method::POST, &draft_endpoint(cluster, draft, ""), "commit");
+    session
+        .send(session.json(request, &CommitSpec { message }))
+        .await
+}
+
+// Start a scan of the hosts against the desired image, returns the task
+pub async fn scan(session: &Session, cluster: &ClusterId) -> Result<String, Error> {
+    let session = &api_session(session).await?;
+    let request = session
+        .action(Method::POST, &endpoint(cluster, ""), "scan")
+        .query(&[("vmw-tasks", "true")]);
+    session.send(request).await
+}
+
+// Result of the last scan
+pub async fn compliance(
+    session: &Session,
+    cluster: &ClusterId,
+) -> Result<ClusterCompliance, Error> {
+    let session = &api_session(session).await?;
+    let request = session.request(Method::GET, &endpoint(cluster, "/compliance"));
+    session.send(request).await
+}
+
+// Start the remediation of the hosts, returns the task. Hosts are put into
+// maintenance mode and rebooted as required by the image.
+pub async fn apply(
+    session: &Session,
+    cluster: &ClusterId,
+    spec: &ApplySpec,
+) -> Result<String, Error> {
+    let session = &api_session(session).await?;
+    let request = session
+        .action(Method::POST, &endpoint(cluster, ""), "apply")
+        .query(&[("vmw-tasks", "true")]);
+    session.send(session.json(request, spec)).await
+}
+
+// The images are only served with the /api flavor, which the capabilities
+// of the session have to confirm
+async fn api_session(session: &Session) -> Result<Session, Error> {
+    session.require(Feature::LifecycleManager).await?;
+    Ok(session.with_api_flavor())
+}
+
+fn endpoint(cluster: &ClusterId, path: &str) -> String {
+    format!("/esx/settings/clusters/{}/software{}", cluster, path)
+}
+
+fn draft_endpoint(cluster: &ClusterId, draft: &str, path: &str) -> String {
+    endpoint(cluster, &format!("/drafts/{}{}", draft, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClusterCompliance, ComplianceImpact, ComplianceStatus, SoftwareInfo};
+    use crate::id::HostId;
+
+    #[test]
+    fn deserialize_software_and_compliance() {
+        let json = r#"{"base_image":{"version":"7.0.3-0.50.20036589","details":{
+            "display_name":"ESXi","display_version":"7.0 U3g","release_date":"2022-09-01T00:00:00Z"}},
+            "add_on":{"name":"DEL-ESXi","version":"703.A02"},
+            "components":{"VMware-vmkusb-nic-fling":{"version":"1.10-1vmw.703.0.50.55634242"}},
+            "solutions":{},"hardware_support":{}}"#;
+        let software: SoftwareInfo = serde_json::from_str(json).expect("deserialize software");
+        assert_eq!(software.add_on.unwrap().name, "DEL-ESXi");
+        assert_eq!(
+            software.components["VMware-vmkusb-nic-fling"].version,
+            "1.10-1vmw.703.0.50.55634242"
+        );
+
+        let json = r#"{"status":"NON_COMPLIANT","impact":"REBOOT_REQUIRED","scan_time":"2022-10-04T09:12:44Z",
+            "hosts":{"host-21":{"status":"NON_COMPLIANT","impact":"REBOOT_REQUIRED","scan_time":"2022-10-04T09:12:44Z"}},
+            "compliant_hosts":[],"non_compliant_hosts":["host-21"],"incompatible_hosts":[],"unavailable_hosts":[],
+            "stage_status":"NOT_STAGED","notifications":{}}"#;
+        let compliance: ClusterCompliance =
+            serde_json::from_str(json).expect("deserialize compliance");
+        assert_eq!(compliance.status, ComplianceStatus::NonCompliant);
+        assert_eq!(
+            compliance.hosts[&HostId::new("host-21")].impact,
+            ComplianceImpact::RebootRequired
+        );
+        assert_eq!(compliance.non_compliant_hosts, vec![HostId::new("host-21")]);
+    }
+}
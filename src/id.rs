@@ -0,0 +1,155 @@
+// Typed identifiers of managed objects and services. They serialize as the
+// plain identifier string of the API, so they can be used in every request
+// and response, and convert into a DynamicId of their object type.
+use crate::cis::DynamicId;
+use std::convert::TryFrom;
+use std::fmt;
+
+macro_rules! object_id {
+    ($name:ident, $object_type:expr) => {
+        object_id!($name);
+
+        impl $name {
+            // Object type of the identifier in a DynamicId
+            pub fn object_type(&self) -> &'static str {
+                $object_type
+            }
+        }
+    };
+    ($name:ident) => {
+        #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(id: impl Into<String>) -> Self {
+                $name(id.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_string(self) -> String {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                $name(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                $name(id.to_owned())
+            }
+        }
+
+        impl From<&$name> for $name {
+            fn from(id: &$name) -> Self {
+                id.clone()
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<$name> for DynamicId {
+            fn from(id: $name) -> Self {
+                DynamicId::new(id.object_type(), id.0)
+            }
+        }
+
+        impl From<&$name> for DynamicId {
+            fn from(id: &$name) -> Self {
+                DynamicId::new(id.object_type(), id.0.clone())
+            }
+        }
+
+        // Fails with the DynamicId itself if it refers to another object type
+        impl TryFrom<DynamicId> for $name {
+            type Error = DynamicId;
+
+            fn try_from(dynamic: DynamicId) -> Result<Self, Self::Error> {
+                let id = $name(dynamic.id);
+                if id.object_type() == dynamic.object_type {
+                    Ok(id)
+                } else {
+                    Err(DynamicId::new(dynamic.object_type, id.0))
+                }
+            }
+        }
+    };
+}
+
+object_id!(VmId, "VirtualMachine");
+object_id!(HostId, "HostSystem");
+object_id!(ClusterId, "ClusterComputeResource");
+object_id!(DatacenterId, "Datacenter");
+object_id!(DatastoreId, "Datastore");
+object_id!(ResourcePoolId, "ResourcePool");
+object_id!(FolderId, "Folder");
+object_id!(NetworkId);
+object_id!(LibraryId, "com.vmware.content.Library");
+object_id!(LibraryItemId, "com.vmware.content.library.Item");
+object_id!(CategoryId, "com.vmware.cis.tagging.Category");
+object_id!(TagId, "com.vmware.cis.tagging.Tag");
+
+impl NetworkId {
+    // Standard and distributed portgroups share the identifier type but are
+    // different object types, told apart by the identifier prefix
+    pub fn object_type(&self) -> &'static str {
+        if self.0.starts_with("dvportgroup-") {
+            "DistributedVirtualPortgroup"
+        } else {
+            "Network"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NetworkId, VmId};
+    use crate::cis::DynamicId;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn plain_wire_format() {
+        let id: VmId = serde_json::from_str(r#""vm-42""#).expect("deserialize");
+        assert_eq!(id.as_str(), "vm-42");
+        assert_eq!(serde_json::to_string(&id).expect("serialize"), r#""vm-42""#);
+    }
+
+    #[test]
+    fn dynamic_id() {
+        let dynamic = DynamicId::from(VmId::new("vm-42"));
+        assert_eq!(dynamic, DynamicId::new("VirtualMachine", "vm-42"));
+        assert!(VmId::try_from(dynamic).is_ok());
+        assert!(VmId::try_from(DynamicId::new("HostSystem", "host-10")).is_err());
+        let portgroup = DynamicId::from(NetworkId::new("dvportgroup-21"));
+        assert_eq!(portgroup.object_type, "DistributedVirtualPortgroup");
+    }
+}
@@ -5,7 +5,29 @@ extern crate serde;
 
 #[macro_use]
 mod common;
+pub mod appliance;
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod bulk;
+pub mod cancel;
 pub mod cis;
+pub mod content;
+pub mod esx;
+pub mod id;
+#[cfg(feature = "vim25")]
+pub mod license;
+pub mod paging;
+pub mod resolver;
+#[cfg(feature = "vim25")]
+pub mod sso;
+pub mod stats;
+pub mod tagging;
+pub mod vcenter;
+#[cfg(feature = "vim25")]
+pub mod vim25;
+#[cfg(feature = "vsan")]
+pub mod vsan;
 
 #[cfg(test)]
 mod tests {}
@@ -0,0 +1,226 @@
+// License keys of vCenter and their assignment to vCenter and hosts through
+// the LicenseManager of the vim25 API, the REST API has no licensing.
+use crate::id::HostId;
+use crate::vim25::{parse_body, Client, Error, Fault, ManagedObjectReference};
+
+// License key with its usage, counted in the cost unit like "cpuPackage"
+// or "server". A total of 0 is unlimited, e.g. for the evaluation license.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct License {
+    #[serde(default)]
+    pub license_key: String,
+    #[serde(default)]
+    pub edition_key: String,
+    pub name: String,
+    #[serde(default)]
+    pub total: i32,
+    pub used: Option<i32>,
+    #[serde(default)]
+    pub cost_unit: String,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+}
+
+impl License {
+    pub fn is_evaluation(&self) -> bool {
+        self.edition_key == "eval"
+    }
+
+    // Whether more units are used than the license provides
+    pub fn is_overused(&self) -> bool {
+        self.total > 0 && self.used.unwrap_or(0) > self.total
+    }
+}
+
+// Entity a license is assigned to, the entity id is the instance UUID for
+// vCenter and the managed object id for hosts
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Asset {
+    #[serde(default)]
+    pub entity_id: String,
+    pub scope: Option<String>,
+    pub entity_display_name: Option<String>,
+    pub assigned_license: License,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfLicenseInfo {
+    #[serde(default, rename = "LicenseManagerLicenseInfo")]
+    licenses: Vec<License>,
+}
+
+#[derive(Deserialize)]
+struct Assets {
+    #[serde(default, rename = "returnval")]
+    assets: Vec<Asset>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LicenseKeyRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    license_key: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryAssignedLicensesRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity_id: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateAssignedLicenseRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    entity: &'a str,
+    license_key: &'a str,
+}
+
+// License keys known to vCenter with their usage
+pub async fn licenses(client: &Client<'_>) -> Result<Vec<License>, Error> {
+    let license_manager = license_manager(client).await?;
+    let licenses: Option<ArrayOfLicenseInfo> = client
+        .retrieve_property(&license_manager, "licenses")
+        .await?;
+    Ok(licenses.unwrap_or_default().licenses)
+}
+
+// Add a license key to vCenter, it can be assigned afterwards
+pub async fn add(client: &Client<'_>, license_key: &str) -> Result<License, Error> {
+    let license_manager = license_manager(client).await?;
+    let request = LicenseKeyRequest {
+        this: &license_manager,
+        license_key,
+    };
+    client.call("AddLicense", &request).await
+}
+
+// Remove an unassigned license key
+pub async fn remove(client: &Client<'_>, license_key: &str) -> Result<(), Error> {
+    let license_manager = license_manager(client).await?;
+    let request = LicenseKeyRequest {
+        this: &license_manager,
+        license_key,
+    };
+    client.call_empty("RemoveLicense", &request).await
+}
+
+// Entities with their assigned license
+pub async fn assets(client: &Client<'_>) -> Result<Vec<Asset>, Error> {
+    query_assets(client, None).await
+}
+
+pub async fn host_asset(client: &Client<'_>, host: &HostId) -> Result<Option<Asset>, Error> {
+    let assets = query_assets(client, Some(host.as_str())).await?;
+    Ok(assets.into_iter().next())
+}
+
+pub async fn assign_to_host(
+    client: &Client<'_>,
+    host: &HostId,
+    license_key: &str,
+) -> Result<License, Error> {
+    assign(client, host.as_str(), license_key).await
+}
+
+pub async fn assign_to_vcenter(client: &Client<'_>, license_key: &str) -> Result<License, Error> {
+    let content = client.cached_content().await?;
+    let instance_uuid = content
+        .about
+        .instance_uuid
+        .ok_or_else(|| Error::Fault(Fault::not_supported("vCenter")))?;
+    assign(client, &instance_uuid, license_key).await
+}
+
+async fn assign(client: &Client<'_>, entity: &str, license_key: &str) -> Result<License, Error> {
+    let license_assignment_manager = license_assignment_manager(client).await?;
+    let request = UpdateAssignedLicenseRequest {
+        this: &license_assignment_manager,
+        entity,
+        license_key,
+    };
+    client.call("UpdateAssignedLicense", &request).await
+}
+
+async fn query_assets(client: &Client<'_>, entity_id: Option<&str>) -> Result<Vec<Asset>, Error> {
+    let license_assignment_manager = license_assignment_manager(client).await?;
+    let request = QueryAssignedLicensesRequest {
+        this: &license_assignment_manager,
+        entity_id,
+    };
+    let body = client.invoke("QueryAssignedLicenses", &request).await?;
+    let assets: Assets = parse_body(&body)?;
+    Ok(assets.assets)
+}
+
+async fn license_manager(client: &Client<'_>) -> Result<ManagedObjectReference, Error> {
+    let content = client.cached_content().await?;
+    content
+        .license_manager
+        .ok_or_else(|| Error::Fault(Fault::not_supported("LicenseManager")))
+}
+
+// Only vCenter has a LicenseAssignmentManager
+async fn license_assignment_manager(client: &Client<'_>) -> Result<ManagedObjectReference, Error> {
+    let license_manager = license_manager(client).await?;
+    client
+        .retrieve_property(&license_manager, "licenseAssignmentManager")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("LicenseAssignmentManager")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayOfLicenseInfo, Assets};
+    use crate::vim25::parse_body;
+
+    #[test]
+    fn deserialize_licenses() {
+        let xml = r#"<val xsi:type="ArrayOfLicenseManagerLicenseInfo">
+            <LicenseManagerLicenseInfo xsi:type="LicenseManagerLicenseInfo">
+            <licenseKey>00000-00000-00000-00000-00000</licenseKey><editionKey>eval</editionKey>
+            <name>Evaluation Mode</name><total>0</total><costUnit/></LicenseManagerLicenseInfo>
+            <LicenseManagerLicenseInfo xsi:type="LicenseManagerLicenseInfo">
+            <licenseKey>AAAAA-BBBBB-CCCCC-DDDDD-EEEEE</licenseKey><editionKey>esx.enterprisePlus.cpuPackage</editionKey>
+            <name>VMware vSphere 7 Enterprise Plus</name><total>8</total><used>10</used>
+            <costUnit>cpuPackage</costUnit><labels><key>team</key><value>infra</value></labels>
+            </LicenseManagerLicenseInfo></val>"#;
+        let array: ArrayOfLicenseInfo = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(array.licenses.len(), 2);
+        assert!(array.licenses[0].is_evaluation());
+        assert!(!array.licenses[0].is_overused());
+        assert_eq!(array.licenses[1].used, Some(10));
+        assert!(array.licenses[1].is_overused());
+        assert_eq!(array.licenses[1].labels[0].value, "infra");
+    }
+
+    #[test]
+    fn parse_assets() {
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><QueryAssignedLicensesResponse xmlns="urn:vim25">
+            <returnval><entityId>host-10</entityId><scope>4d2e1a52-5b1c-4a6e-9d0f-1c2b3a4d5e6f</scope>
+            <entityDisplayName>esx-01.example.com</entityDisplayName><assignedLicense>
+            <licenseKey>AAAAA-BBBBB-CCCCC-DDDDD-EEEEE</licenseKey><editionKey>esx.enterprisePlus.cpuPackage</editionKey>
+            <name>VMware vSphere 7 Enterprise Plus</name><total>8</total><used>2</used>
+            <costUnit>cpuPackage</costUnit></assignedLicense></returnval>
+            </QueryAssignedLicensesResponse></soapenv:Body></soapenv:Envelope>"#;
+        let assets: Assets = parse_body(body).unwrap();
+        assert_eq!(assets.assets.len(), 1);
+        assert_eq!(assets.assets[0].entity_id, "host-10");
+        assert_eq!(assets.assets[0].assigned_license.total, 8);
+    }
+}
@@ -0,0 +1,91 @@
+// Streams over list calls which return their results in several pages, e.g.
+// because the server caps the number of results of a single call
+use crate::cis::Error;
+use futures::future::Future;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// Items of one page and the cursor of the next page, None for the last page
+#[derive(Debug)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    pub next: Option<C>,
+}
+
+// Stream of the items of a paged list call, the next page is only fetched
+// once the items of the previous one are consumed
+pub struct PagedStream<'a, T> {
+    inner: Pin<Box<dyn Stream<Item = Result<T, Error>> + Send + 'a>>,
+}
+
+impl<'a, T: Send + 'a> PagedStream<'a, T> {
+    // Fetch the pages starting with the cursor of the first page
+    pub fn new<C, F, Fut>(first: C, mut fetch: F) -> Self
+    where
+        C: Send + 'a,
+        F: FnMut(C) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<Page<T, C>, Error>> + Send + 'a,
+    {
+        let pages = stream::try_unfold(Some(first), move |cursor| {
+            let page = cursor.map(&mut fetch);
+            async move {
+                match page {
+                    Some(page) => {
+                        let page = page.await?;
+                        Ok(Some((page.items, page.next)))
+                    }
+                    None => Ok(None),
+                }
+            }
+        });
+        PagedStream::from_pages(pages)
+    }
+
+    // Stream of a list call which returns all results at once
+    pub fn once<Fut>(list: Fut) -> Self
+    where
+        Fut: Future<Output = Result<Vec<T>, Error>> + Send + 'a,
+    {
+        PagedStream::from_pages(stream::once(list))
+    }
+
+    fn from_pages<S>(pages: S) -> Self
+    where
+        S: Stream<Item = Result<Vec<T>, Error>> + Send + 'a,
+    {
+        let items = pages
+            .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+            .try_flatten();
+        PagedStream {
+            inner: items.boxed(),
+        }
+    }
+}
+
+impl<T> Stream for PagedStream<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Page, PagedStream};
+    use futures::executor::block_on;
+    use futures::stream::TryStreamExt;
+
+    #[test]
+    fn items_of_all_pages() {
+        let stream = PagedStream::new(0, |page: u32| async move {
+            Ok(Page {
+                items: vec![page * 10, page * 10 + 1],
+                next: if page < 2 { Some(page + 1) } else { None },
+            })
+        });
+        let items: Vec<u32> = block_on(stream.try_collect()).expect("collect");
+        assert_eq!(items, vec![0, 1, 10, 11, 20, 21]);
+    }
+}
@@ -0,0 +1,257 @@
+// Resolution of inventory names to object identifiers. Names are only unique
+// within a folder, so a name matching several objects is an error instead of
+// picking one of them. Results are cached for a configurable time.
+use crate::cis::{Error, Session};
+use crate::id::{
+    ClusterId, DatacenterId, DatastoreId, FolderId, HostId, NetworkId, ResourcePoolId, VmId,
+};
+use crate::vcenter::{cluster, datacenter, datastore, folder, host, network, resource_pool, vm};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Vm,
+    Host,
+    Cluster,
+    Datacenter,
+    Datastore,
+    Network,
+    ResourcePool,
+    Folder,
+}
+
+struct Entry {
+    ids: Vec<String>,
+    fetched: Instant,
+}
+
+// Resolver with a cache shared by all calls on it
+pub struct Resolver<'a> {
+    session: &'a Session,
+    ttl: Duration,
+    cache: Mutex<HashMap<(Kind, String), Entry>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(session: &'a Session) -> Self {
+        Resolver::with_ttl(session, DEFAULT_TTL)
+    }
+
+    // Cache list results for `ttl`, zero disables the cache
+    pub fn with_ttl(session: &'a Session, ttl: Duration) -> Self {
+        Resolver {
+            session,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Forget all cached results, e.g. after creating or renaming objects
+    pub fn invalidate(&self) {
+        self.cache().clear();
+    }
+
+    pub async fn resolve_vm(&self, name: &str) -> Result<VmId, Error> {
+        self.resolve(Kind::Vm, name).await.map(VmId::from)
+    }
+
+    pub async fn resolve_host(&self, name: &str) -> Result<HostId, Error> {
+        self.resolve(Kind::Host, name).await.map(HostId::from)
+    }
+
+    pub async fn resolve_cluster(&self, name: &str) -> Result<ClusterId, Error> {
+        self.resolve(Kind::Cluster, name).await.map(ClusterId::from)
+    }
+
+    pub async fn resolve_datacenter(&self, name: &str) -> Result<DatacenterId, Error> {
+        self.resolve(Kind::Datacenter, name)
+            .await
+            .map(DatacenterId::from)
+    }
+
+    pub async fn resolve_datastore(&self, name: &str) -> Result<DatastoreId, Error> {
+        self.resolve(Kind::Datastore, name)
+            .await
+            .map(DatastoreId::from)
+    }
+
+    pub async fn resolve_network(&self, name: &str) -> Result<NetworkId, Error> {
+        self.resolve(Kind::Network, name).await.map(NetworkId::from)
+    }
+
+    pub async fn resolve_resource_pool(&self, name: &str) -> Result<ResourcePoolId, Error> {
+        self.resolve(Kind::ResourcePool, name)
+            .await
+            .map(ResourcePoolId::from)
+    }
+
+    pub async fn resolve_folder(&self, name: &str) -> Result<FolderId, Error> {
+        self.resolve(Kind::Folder, name).await.map(FolderId::from)
+    }
+
+    // Fails with ErrorType::NotFound if no object has the name and with
+    // Error::AmbiguousName if several have it
+    async fn resolve(&self, kind: Kind, name: &str) -> Result<String, Error> {
+        let ids = match self.cached(kind, name, Instant::now()) {
+            Some(ids) => ids,
+            None => {
+                let ids = self.list_ids(kind, name).await?;
+                self.store(kind, name, ids.clone(), Instant::now());
+                ids
+            }
+        };
+        pick(name, ids)
+    }
+
+    fn cached(&self, kind: Kind, name: &str, now: Instant) -> Option<Vec<String>> {
+        let cache = self.cache();
+        let entry = cache.get(&(kind, name.to_owned()))?;
+        if now.saturating_duration_since(entry.fetched) < self.ttl {
+            Some(entry.ids.clone())
+        } else {
+            None
+        }
+    }
+
+    fn store(&self, kind: Kind, name: &str, ids: Vec<String>, fetched: Instant) {
+        self.cache()
+            .insert((kind, name.to_owned()), Entry { ids, fetched });
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, HashMap<(Kind, String), Entry>> {
+        self.cache.lock().expect("resolver cache lock")
+    }
+
+    async fn list_ids(&self, kind: Kind, name: &str) -> Result<Vec<String>, Error> {
+        let session = self.session;
+        let names = vec![name.to_owned()];
+        let ids = match kind {
+            Kind::Vm => {
+                let filter = vm::FilterSpec {
+                    names,
+                    ..Default::default()
+                };
+                let list = vm::list(session, &filter).await?;
+                list.into_iter()
+                    .map(|summary| summary.vm.into_string())
+                    .collect()
+            }
+            Kind::Host => {
+                let filter = host::FilterSpec {
+                    names,
+                    ..Default::default()
+                };
+                let list = host::list(session, &filter).await?;
+                list.into_iter()
+                    .map(|summary| summary.host.into_string())
+                    .collect()
+            }
+            Kind::Cluster => {
+                let filter = cluster::FilterSpec {
+                    names,
+                    ..Default::default()
+                };
+                let list = cluster::list(session, &filter).await?;
+                list.into_iter()
+                    .map(|summary| summary.cluster.into_string())
+                    .collect()
+            }
+            Kind::Datacenter => {
+                let filter = datacenter::FilterSpec {
+                    names,
+                    ..Default::default()
+                };
+                let list = datacenter::list(session, &filter).await?;
+                list.into_iter()
+                    .map(|summary| summary.datacenter.into_string())
+                    .collect()
+            }
+            Kind::Datastore => {
+                let filter = datastore::FilterSpec {
+                    names,
+                    ..Default::default()
+                };
+                let list = datastore::list(session, &filter).await?;
+                list.into_iter()
+                    .map(|summary| summary.datastore.into_string())
+                    .collect()
+            }
+            Kind::Network => {
+                let filter = network::FilterSpec {
+                    names,
+                    ..Default::default()
+                };
+                let list = network::list(session, &filter).await?;
+                list.into_iter()
+                    .map(|summary| summary.network.into_string())
+                    .collect()
+            }
+            Kind::ResourcePool => {
+                let filter = resource_pool::FilterSpec {
+                    names,
+                    ..Default::default()
+                };
+                let list = resource_pool::list(session, &filter).await?;
+                list.into_iter()
+                    .map(|summary| summary.resource_pool.into_string())
+                    .collect()
+            }
+            Kind::Folder => {
+                let filter = folder::FilterSpec {
+                    names,
+                    ..Default::default()
+                };
+                let list = folder::list(session, &filter).await?;
+                list.into_iter()
+                    .map(|summary| summary.folder.into_string())
+                    .collect()
+            }
+        };
+        Ok(ids)
+    }
+}
+
+fn pick(name: &str, mut ids: Vec<String>) -> Result<String, Error> {
+    match ids.len() {
+        0 => Err(Error::not_found()),
+        1 => Ok(ids.remove(0)),
+        _ => Err(Error::AmbiguousName {
+            name: name.to_owned(),
+            ids,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pick, Kind, Resolver};
+    use crate::cis::{Error, ErrorType, Session};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn pick_unique_name() {
+        assert_eq!(pick("web-1", vec!["vm-1".into()]).unwrap(), "vm-1");
+        let err = pick("web-1", Vec::new()).unwrap_err();
+        assert_eq!(err.error_type(), Some(&ErrorType::NotFound));
+        match pick("web-1", vec!["vm-1".into(), "vm-2".into()]) {
+            Err(Error::AmbiguousName { ids, .. }) => assert_eq!(ids.len(), 2),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cache_expires() {
+        let session = Session::new("vc.example.com", false).expect("session");
+        let resolver = Resolver::with_ttl(&session, Duration::from_secs(60));
+        let now = Instant::now();
+        resolver.store(Kind::Vm, "web-1", vec!["vm-1".into()], now);
+        assert!(resolver.cached(Kind::Vm, "web-1", now).is_some());
+        assert!(resolver.cached(Kind::Host, "web-1", now).is_none());
+        let later = now + Duration::from_secs(61);
+        assert!(resolver.cached(Kind::Vm, "web-1", later).is_none());
+    }
+}
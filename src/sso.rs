@@ -0,0 +1,212 @@
+// Identity sources and principals of vCenter Single Sign-On. The UserDirectory
+// of the vim25 API searches the same identity sources as the SSO admin
+// service, but shares the login of a vim25 Client instead of requiring a
+// SAML token.
+use crate::vim25::{parse_body, Client, Error, Fault, ManagedObjectReference};
+
+// User or group found in an identity source, the principal is the name
+// without domain, e.g. "devops" of the domain "vsphere.local"
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Principal {
+    #[serde(default)]
+    pub principal: String,
+    pub full_name: Option<String>,
+    #[serde(default)]
+    pub group: bool,
+}
+
+// Search of users and groups, by default for both kinds of principals
+// containing the search string in every identity source
+#[derive(Debug, Clone)]
+pub struct PrincipalSearch {
+    pub domain: Option<String>,
+    pub search: String,
+    pub belongs_to_group: Option<String>,
+    pub exact_match: bool,
+    pub find_users: bool,
+    pub find_groups: bool,
+}
+
+impl PrincipalSearch {
+    pub fn new(search: impl Into<String>) -> Self {
+        PrincipalSearch {
+            domain: None,
+            search: search.into(),
+            belongs_to_group: None,
+            exact_match: false,
+            find_users: true,
+            find_groups: true,
+        }
+    }
+
+    // Only principals of the identity source, e.g. "vsphere.local"
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    // Only principals named exactly like the search string
+    pub fn exact(mut self) -> Self {
+        self.exact_match = true;
+        self
+    }
+
+    pub fn users(mut self) -> Self {
+        self.find_users = true;
+        self.find_groups = false;
+        self
+    }
+
+    pub fn groups(mut self) -> Self {
+        self.find_users = false;
+        self.find_groups = true;
+        self
+    }
+
+    // Only direct members of the group
+    pub fn member_of(mut self, group: impl Into<String>) -> Self {
+        self.belongs_to_group = Some(group.into());
+        self
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfString {
+    #[serde(default, rename = "string")]
+    strings: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Principals {
+    #[serde(default, rename = "returnval")]
+    principals: Vec<Principal>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RetrieveUserGroupsRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain: Option<&'a str>,
+    search_str: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    belongs_to_group: Option<&'a str>,
+    exact_match: bool,
+    find_users: bool,
+    find_groups: bool,
+}
+
+// Names of the identity sources, e.g. "vsphere.local" and Active Directory
+// domains
+pub async fn identity_sources(client: &Client<'_>) -> Result<Vec<String>, Error> {
+    let user_directory = user_directory(client).await?;
+    let domains: Option<ArrayOfString> = client
+        .retrieve_property(&user_directory, "domainList")
+        .await?;
+    Ok(domains.unwrap_or_default().strings)
+}
+
+pub async fn search(
+    client: &Client<'_>,
+    search: &PrincipalSearch,
+) -> Result<Vec<Principal>, Error> {
+    let user_directory = user_directory(client).await?;
+    let request = RetrieveUserGroupsRequest {
+        this: &user_directory,
+        domain: search.domain.as_deref(),
+        search_str: &search.search,
+        belongs_to_group: search.belongs_to_group.as_deref(),
+        exact_match: search.exact_match,
+        find_users: search.find_users,
+        find_groups: search.find_groups,
+    };
+    let body = client.invoke("RetrieveUserGroups", &request).await?;
+    let principals: Principals = parse_body(&body)?;
+    Ok(principals.principals)
+}
+
+// Whether the user exists in the identity source, e.g. before a permission
+// is assigned to "vsphere.local\devops"
+pub async fn user_exists(client: &Client<'_>, domain: &str, name: &str) -> Result<bool, Error> {
+    let search = PrincipalSearch::new(name).domain(domain).exact().users();
+    exists(client, &search).await
+}
+
+pub async fn group_exists(client: &Client<'_>, domain: &str, name: &str) -> Result<bool, Error> {
+    let search = PrincipalSearch::new(name).domain(domain).exact().groups();
+    exists(client, &search).await
+}
+
+async fn exists(client: &Client<'_>, search: &PrincipalSearch) -> Result<bool, Error> {
+    // Identity sources compare names case insensitive
+    let principals = self::search(client, search).await?;
+    Ok(principals
+        .iter()
+        .any(|principal| principal.principal.eq_ignore_ascii_case(&search.search)))
+}
+
+async fn user_directory(client: &Client<'_>) -> Result<ManagedObjectReference, Error> {
+    let content = client.cached_content().await?;
+    content
+        .user_directory
+        .ok_or_else(|| Error::Fault(Fault::not_supported("UserDirectory")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Principal, PrincipalSearch, Principals, RetrieveUserGroupsRequest};
+    use crate::vim25::{envelope, parse_body, ManagedObjectReference};
+
+    #[test]
+    fn retrieve_user_groups_envelope() {
+        let search = PrincipalSearch::new("devops")
+            .domain("vsphere.local")
+            .exact()
+            .groups();
+        let request = RetrieveUserGroupsRequest {
+            this: &ManagedObjectReference::new("UserDirectory", "UserDirectory"),
+            domain: search.domain.as_deref(),
+            search_str: &search.search,
+            belongs_to_group: search.belongs_to_group.as_deref(),
+            exact_match: search.exact_match,
+            find_users: search.find_users,
+            find_groups: search.find_groups,
+        };
+        let xml = envelope("RetrieveUserGroups", &request).unwrap();
+        assert!(xml.contains(
+            "<_this type=\"UserDirectory\">UserDirectory</_this>\
+             <domain>vsphere.local</domain><searchStr>devops</searchStr>\
+             <exactMatch>true</exactMatch><findUsers>false</findUsers>\
+             <findGroups>true</findGroups>"
+        ));
+    }
+
+    #[test]
+    fn parse_principals() {
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><RetrieveUserGroupsResponse xmlns="urn:vim25">
+            <returnval xsi:type="UserSearchResult"><principal>devops</principal>
+            <fullName>DevOps team</fullName><group>true</group></returnval>
+            <returnval xsi:type="UserSearchResult"><principal>devops-bot</principal>
+            <group>false</group></returnval>
+            </RetrieveUserGroupsResponse></soapenv:Body></soapenv:Envelope>"#;
+        let principals: Principals = parse_body(body).unwrap();
+        assert_eq!(
+            principals.principals,
+            vec![
+                Principal {
+                    principal: "devops".to_owned(),
+                    full_name: Some("DevOps team".to_owned()),
+                    group: true,
+                },
+                Principal {
+                    principal: "devops-bot".to_owned(),
+                    full_name: None,
+                    group: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,253 @@
+// Performance data of the vStats API (vSphere 8.0+), which is only served
+// with ApiFlavor::Api. Resources are addressed as "type.<kind>=<id>", e.g.
+// "type.vm=vm-42", counters by their identifier like "cpu.capacity.usage.VM".
+
+// Quick stats of virtual machines and hosts through the vim25 SOAP API
+#[cfg(feature = "vim25")]
+pub mod quick;
+
+use crate::cis::capabilities::Feature;
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query};
+use crate::id::{ClusterId, HostId, VmId};
+use crate::paging::{Page, PagedStream};
+use chrono::prelude::*;
+use reqwest::Method;
+
+// Counter which can be queried for the resources of its address schema
+#[derive(Deserialize, Debug, Clone)]
+pub struct CounterInfo {
+    #[serde(default)]
+    pub cid: String,
+    #[serde(default)]
+    pub metric: String,
+    #[serde(default)]
+    pub resource_address_schema: String,
+}
+
+// Value of a counter for a resource at a point in time
+#[derive(Deserialize, Debug, Clone)]
+pub struct DataPoint {
+    #[serde(default)]
+    pub cid: String,
+    #[serde(default)]
+    pub rid: String,
+    // Seconds since the Unix epoch
+    #[serde(default)]
+    pub ts: i64,
+    #[serde(default)]
+    pub val: f64,
+}
+
+impl DataPoint {
+    // None for timestamps out of the range of DateTime
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        Utc.timestamp_opt(self.ts, 0).single()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct DataPointsResult {
+    #[serde(default)]
+    data_points: Vec<DataPoint>,
+    next: Option<String>,
+}
+
+// Query of data points, unset fields match every data point
+#[derive(Debug, Default, Clone)]
+pub struct DataPointsQuery {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub counter: Option<String>,
+    pub metric: Option<String>,
+    pub resource_types: Vec<String>,
+    pub resources: Vec<String>,
+}
+
+impl DataPointsQuery {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // Only data points in the time range, either end may be open
+    pub fn range(mut self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Self {
+        self.start = start;
+        self.end = end;
+        self
+    }
+
+    pub fn counter(mut self, cid: impl Into<String>) -> Self {
+        self.counter = Some(cid.into());
+        self
+    }
+
+    // Only counters of the metric, e.g. "cpu.capacity.usage"
+    pub fn metric(mut self, metric: impl Into<String>) -> Self {
+        self.metric = Some(metric.into());
+        self
+    }
+
+    // Only resources of the type, e.g. "vm" or "host"
+    pub fn resource_type(mut self, resource_type: impl Into<String>) -> Self {
+        self.resource_types.push(resource_type.into());
+        self
+    }
+
+    // Only the resource with the address, e.g. "type.vm=vm-42"
+    pub fn resource(mut self, address: impl Into<String>) -> Self {
+        self.resources.push(address.into());
+        self
+    }
+
+    pub fn vm(self, vm: &VmId) -> Self {
+        self.resource(format!("type.vm={}", vm))
+    }
+
+    pub fn host(self, host: &HostId) -> Self {
+        self.resource(format!("type.host={}", host))
+    }
+
+    pub fn cluster(self, cluster: &ClusterId) -> Self {
+        self.resource(format!("type.cluster={}", cluster))
+    }
+
+    fn query(&self, page: Option<&str>) -> Query {
+        let mut query = Query::new();
+        if let Some(start) = self.start {
+            query.push(("start", start.timestamp().to_string()));
+        }
+        if let Some(end) = self.end {
+            query.push(("end", end.timestamp().to_string()));
+        }
+        if let Some(counter) = &self.counter {
+            query.push(("cid", counter.clone()));
+        }
+        if let Some(metric) = &self.metric {
+            query.push(("metric", metric.clone()));
+        }
+        push_query(&mut query, "types", &self.resource_types);
+        push_query(&mut query, "rsrcs", &self.resources);
+        if let Some(page) = page {
+            query.push(("page", page.to_owned()));
+        }
+        query
+    }
+}
+
+// Counters known to the vStats service
+pub async fn counters(session: &Session) -> Result<Vec<CounterInfo>, Error> {
+    let session = api_session(session).await?;
+    let request = session.request(Method::GET, "/stats/counters");
+    session.send(request).await
+}
+
+// One page of the data points matching the query, the cursor of the next
+// page is passed to get the following ones
+pub async fn data_points(
+    session: &Session,
+    query: &DataPointsQuery,
+    page: Option<&str>,
+) -> Result<Page<DataPoint, String>, Error> {
+    let session = api_session(session).await?;
+    let request = session
+        .request(Method::GET, "/stats/data/dp")
+        .query(&query.query(page));
+    let result: DataPointsResult = session.send(request).await?;
+    Ok(Page {
+        items: result.data_points,
+        next: result.next,
+    })
+}
+
+// vStats is only served with the /api flavor, which the capabilities of the
+// session have to confirm
+async fn api_session(session: &Session) -> Result<Session, Error> {
+    session.require(Feature::VStats).await?;
+    Ok(session.with_api_flavor())
+}
+
+// Stream all data points matching the query, page by page
+pub fn data_points_stream<'a>(
+    session: &'a Session,
+    query: &DataPointsQuery,
+) -> PagedStream<'a, DataPoint> {
+    let query = query.clone();
+    PagedStream::new(None, move |page: Option<String>| {
+        let query = query.clone();
+        async move {
+            let page = data_points(session, &query, page.as_deref()).await?;
+            Ok(Page {
+                items: page.items,
+                next: page.next.map(Some),
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{counters, DataPointsQuery, DataPointsResult};
+    use crate::cis::transport::MockTransport;
+    use crate::cis::{Error, Session};
+    use crate::id::VmId;
+    use chrono::prelude::*;
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn counters_need_vcenter_8() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/appliance/system/version",
+            StatusCode::OK,
+            r#"{"value":{"version":"7.0.3.01000"}}"#,
+        );
+        mock.on(
+            Method::GET,
+            "/api/appliance/system/version",
+            StatusCode::OK,
+            r#"{"version":"7.0.3.01000"}"#,
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        match counters(&session).await {
+            Err(Error::UnsupportedOnThisVersion { .. }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[test]
+    fn data_points_query() {
+        let query = DataPointsQuery::new()
+            .range(Utc.timestamp_opt(1_650_000_000, 0).single(), None)
+            .metric("cpu.capacity.usage")
+            .vm(&VmId::new("vm-42"))
+            .query(Some("cursor-2"));
+        assert_eq!(
+            query,
+            vec![
+                ("start", "1650000000".to_owned()),
+                ("metric", "cpu.capacity.usage".to_owned()),
+                ("rsrcs", "type.vm=vm-42".to_owned()),
+                ("page", "cursor-2".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn deserialize_data_points() {
+        let json = r#"{"data_points":[{"cid":"cpu.capacity.usage.VM","rid":"type.vm=vm-42",
+            "ts":1650000020,"val":412.5}],"next":"cursor-2"}"#;
+        let result: DataPointsResult = serde_json::from_str(json).expect("deserialize");
+        assert_eq!(result.data_points[0].val, 412.5);
+        assert_eq!(
+            result.data_points[0].time(),
+            Utc.timestamp_opt(1_650_000_020, 0).single()
+        );
+        assert_eq!(result.next.as_deref(), Some("cursor-2"));
+    }
+}
@@ -0,0 +1,63 @@
+// Quick stats are the current values vCenter shows in the summary of a
+// virtual machine or host, they are updated about every 20 seconds
+use crate::id::{HostId, VmId};
+use crate::vim25::{Client, Error, ManagedObjectReference};
+
+// CPU in MHz and memory in MB, unset while the virtual machine is powered off
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VmQuickStats {
+    pub overall_cpu_usage: Option<i32>,
+    pub overall_cpu_demand: Option<i32>,
+    pub guest_memory_usage: Option<i32>,
+    pub host_memory_usage: Option<i32>,
+    pub private_memory: Option<i32>,
+    pub shared_memory: Option<i32>,
+    pub swapped_memory: Option<i32>,
+    pub ballooned_memory: Option<i32>,
+    pub consumed_overhead_memory: Option<i32>,
+    pub uptime_seconds: Option<i64>,
+}
+
+// CPU in MHz and memory in MB, uptime in seconds
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HostQuickStats {
+    pub overall_cpu_usage: Option<i32>,
+    pub overall_memory_usage: Option<i32>,
+    pub distributed_cpu_fairness: Option<i32>,
+    pub distributed_memory_fairness: Option<i32>,
+    pub uptime: Option<i64>,
+}
+
+pub async fn vm(client: &Client<'_>, vm: &VmId) -> Result<VmQuickStats, Error> {
+    let vm = ManagedObjectReference::new(vm.object_type(), vm.as_str());
+    let stats = client.retrieve_property(&vm, "summary.quickStats").await?;
+    Ok(stats.unwrap_or_default())
+}
+
+pub async fn host(client: &Client<'_>, host: &HostId) -> Result<HostQuickStats, Error> {
+    let host = ManagedObjectReference::new(host.object_type(), host.as_str());
+    let stats = client
+        .retrieve_property(&host, "summary.quickStats")
+        .await?;
+    Ok(stats.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VmQuickStats;
+
+    #[test]
+    fn deserialize_vm_quick_stats() {
+        let xml = r#"<val xsi:type="VirtualMachineQuickStats"><overallCpuUsage>412</overallCpuUsage>
+            <overallCpuDemand>450</overallCpuDemand><guestMemoryUsage>1228</guestMemoryUsage>
+            <hostMemoryUsage>4120</hostMemoryUsage><guestHeartbeatStatus>green</guestHeartbeatStatus>
+            <uptimeSeconds>86400</uptimeSeconds></val>"#;
+        let stats: VmQuickStats = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(stats.overall_cpu_usage, Some(412));
+        assert_eq!(stats.guest_memory_usage, Some(1228));
+        assert_eq!(stats.uptime_seconds, Some(86400));
+        assert_eq!(stats.swapped_memory, None);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::cis::{DynamicId, Error, LocalizableMessage, Session};
+use crate::id::TagId;
+use reqwest::Method;
+
+const SERVICE: &str = "cis/tagging/tag-association";
+
+// Outcome of a batch call, the messages describe the failed tags or objects
+#[derive(Deserialize, Debug, Clone)]
+pub struct BatchResult {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(default)]
+    pub error_messages: Vec<LocalizableMessage>,
+}
+
+// Objects a tag is attached to
+#[derive(Deserialize, Debug, Clone)]
+pub struct TagToObjects {
+    pub tag_id: TagId,
+    #[serde(default)]
+    pub object_ids: Vec<DynamicId>,
+}
+
+#[derive(Serialize)]
+struct ObjectParams<'a> {
+    object_id: &'a DynamicId,
+}
+
+#[derive(Serialize)]
+struct ObjectTagsParams<'a> {
+    object_id: &'a DynamicId,
+    tag_ids: &'a [TagId],
+}
+
+#[derive(Serialize)]
+struct ObjectsParams<'a> {
+    object_ids: &'a [DynamicId],
+}
+
+#[derive(Serialize)]
+struct TagsParams<'a> {
+    tag_ids: &'a [TagId],
+}
+
+// Attaches tags to and detaches them from inventory objects
+pub struct TagAssociation<'a> {
+    session: &'a Session,
+}
+
+impl<'a> TagAssociation<'a> {
+    pub fn new(session: &'a Session) -> Self {
+        TagAssociation { session }
+    }
+
+    pub async fn attach(&self, tag: &TagId, object_id: &DynamicId) -> Result<(), Error> {
+        let request =
+            self.session
+                .service_action(Method::POST, SERVICE, Some(tag.as_str()), "attach");
+        let request = self.session.json(request, &ObjectParams { object_id });
+        self.session.send_empty(request).await
+    }
+
+    pub async fn detach(&self, tag: &TagId, object_id: &DynamicId) -> Result<(), Error> {
+        let request =
+            self.session
+                .service_action(Method::POST, SERVICE, Some(tag.as_str()), "detach");
+        let request = self.session.json(request, &ObjectParams { object_id });
+        self.session.send_empty(request).await
+    }
+
+    // Identifiers of the tags attached to the object
+    pub async fn list_attached_tags(&self, object_id: &DynamicId) -> Result<Vec<TagId>, Error> {
+        let request =
+            self.session
+                .service_action(Method::POST, SERVICE, None, "list-attached-tags");
+        let request = self.session.json(request, &ObjectParams { object_id });
+        self.session.send(request).await
+    }
+
+    // Objects the tag is attached to
+    pub async fn list_attached_objects(&self, tag: &TagId) -> Result<Vec<DynamicId>, Error> {
+        let request = self.session.service_action(
+            Method::POST,
+            SERVICE,
+            Some(tag.as_str()),
+            "list-attached-objects",
+        );
+        self.session.send(request).await
+    }
+
+    pub async fn attach_multiple_tags_to_object(
+        &self,
+        object_id: &DynamicId,
+        tag_ids: &[TagId],
+    ) -> Result<BatchResult, Error> {
+        let request = self.session.service_action(
+            Method::POST,
+            SERVICE,
+            None,
+            "attach-multiple-tags-to-object",
+        );
+        let request = self
+            .session
+            .json(request, &ObjectTagsParams { object_id, tag_ids });
+        self.session.send(request).await
+    }
+
+    pub async fn detach_multiple_tags_from_object(
+        &self,
+        object_id: &DynamicId,
+        tag_ids: &[TagId],
+    ) -> Result<BatchResult, Error> {
+        let request = self.session.service_action(
+            Method::POST,
+            SERVICE,
+            None,
+            "detach-multiple-tags-from-object",
+        );
+        let request = self
+            .session
+            .json(request, &ObjectTagsParams { object_id, tag_ids });
+        self.session.send(request).await
+    }
+
+    pub async fn attach_tag_to_multiple_objects(
+        &self,
+        tag: &TagId,
+        object_ids: &[DynamicId],
+    ) -> Result<BatchResult, Error> {
+        let request = self.session.service_action(
+            Method::POST,
+            SERVICE,
+            Some(tag.as_str()),
+            "attach-tag-to-multiple-objects",
+        );
+        let request = self.session.json(request, &ObjectsParams { object_ids });
+        self.session.send(request).await
+    }
+
+    pub async fn list_attached_objects_on_tags(
+        &self,
+        tag_ids: &[TagId],
+    ) -> Result<Vec<TagToObjects>, Error> {
+        let request = self.session.service_action(
+            Method::POST,
+            SERVICE,
+            None,
+            "list-attached-objects-on-tags",
+        );
+        let request = self.session.json(request, &TagsParams { tag_ids });
+        self.session.send(request).await
+    }
+}
@@ -0,0 +1,97 @@
+use crate::cis::{Error, Session};
+use crate::id::CategoryId;
+use reqwest::Method;
+
+const SERVICE: &str = "cis/tagging/category";
+
+// How many tags of a category can be attached to an object
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Cardinality {
+    Single,
+    Multiple,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Tag category, an empty list of associable types allows all object types
+#[derive(Deserialize, Debug, Clone)]
+pub struct CategoryModel {
+    pub id: CategoryId,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub cardinality: Cardinality,
+    #[serde(default)]
+    pub associable_types: Vec<String>,
+    #[serde(default)]
+    pub used_by: Vec<String>,
+}
+
+// Specification of a new category, associable types are object types like "VirtualMachine"
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub name: String,
+    pub description: String,
+    pub cardinality: Cardinality,
+    pub associable_types: Vec<String>,
+}
+
+// Changes to a category, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cardinality: Option<Cardinality>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub associable_types: Option<Vec<String>>,
+}
+
+// List the identifiers of all categories
+pub async fn list(session: &Session) -> Result<Vec<CategoryId>, Error> {
+    let request = session.request(Method::GET, &session.service_endpoint(SERVICE, None));
+    session.send(request).await
+}
+
+pub async fn get(session: &Session, category: &CategoryId) -> Result<CategoryModel, Error> {
+    let request = session.request(
+        Method::GET,
+        &session.service_endpoint(SERVICE, Some(category.as_str())),
+    );
+    session.send(request).await
+}
+
+// Create a category and return its identifier
+pub async fn create(session: &Session, spec: &CreateSpec) -> Result<CategoryId, Error> {
+    let request = session.request(Method::POST, &session.service_endpoint(SERVICE, None));
+    session
+        .send(session.body(request, "create_spec", spec))
+        .await
+}
+
+pub async fn update(
+    session: &Session,
+    category: &CategoryId,
+    spec: &UpdateSpec,
+) -> Result<(), Error> {
+    let request = session.request(
+        Method::PATCH,
+        &session.service_endpoint(SERVICE, Some(category.as_str())),
+    );
+    session
+        .send_empty(session.body(request, "update_spec", spec))
+        .await
+}
+
+// Delete a category including all its tags
+pub async fn delete(session: &Session, category: &CategoryId) -> Result<(), Error> {
+    let request = session.request(
+        Method::DELETE,
+        &session.service_endpoint(SERVICE, Some(category.as_str())),
+    );
+    session.send_empty(request).await
+}
@@ -0,0 +1,7 @@
+// Modules for the tagging service
+pub mod association;
+pub mod category;
+pub mod tag;
+
+pub use crate::cis::DynamicId;
+pub use association::TagAssociation;
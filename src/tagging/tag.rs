@@ -0,0 +1,72 @@
+use crate::cis::{Error, Session};
+use crate::id::{CategoryId, TagId};
+use reqwest::Method;
+
+const SERVICE: &str = "cis/tagging/tag";
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TagModel {
+    pub id: TagId,
+    pub category_id: CategoryId,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub used_by: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub name: String,
+    pub description: String,
+    pub category_id: CategoryId,
+}
+
+// Changes to a tag, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+// List the identifiers of all tags
+pub async fn list(session: &Session) -> Result<Vec<TagId>, Error> {
+    let request = session.request(Method::GET, &session.service_endpoint(SERVICE, None));
+    session.send(request).await
+}
+
+pub async fn get(session: &Session, tag: &TagId) -> Result<TagModel, Error> {
+    let request = session.request(
+        Method::GET,
+        &session.service_endpoint(SERVICE, Some(tag.as_str())),
+    );
+    session.send(request).await
+}
+
+// Create a tag and return its identifier
+pub async fn create(session: &Session, spec: &CreateSpec) -> Result<TagId, Error> {
+    let request = session.request(Method::POST, &session.service_endpoint(SERVICE, None));
+    session
+        .send(session.body(request, "create_spec", spec))
+        .await
+}
+
+pub async fn update(session: &Session, tag: &TagId, spec: &UpdateSpec) -> Result<(), Error> {
+    let request = session.request(
+        Method::PATCH,
+        &session.service_endpoint(SERVICE, Some(tag.as_str())),
+    );
+    session
+        .send_empty(session.body(request, "update_spec", spec))
+        .await
+}
+
+pub async fn delete(session: &Session, tag: &TagId) -> Result<(), Error> {
+    let request = session.request(
+        Method::DELETE,
+        &session.service_endpoint(SERVICE, Some(tag.as_str())),
+    );
+    session.send_empty(request).await
+}
@@ -0,0 +1,176 @@
+// TLS certificate of vCenter and the trusted root chains of its certificate
+// store. Replacing the certificate restarts the vCenter services.
+use crate::cis::{Error, Session};
+use chrono::prelude::*;
+use reqwest::Method;
+
+const TLS: &str = "/vcenter/certificate-management/vcenter/tls";
+const TLS_CSR: &str = "/vcenter/certificate-management/vcenter/tls-csr";
+const TRUSTED_ROOT_CHAINS: &str = "/vcenter/certificate-management/vcenter/trusted-root-chains";
+
+// Certificate used by vCenter for TLS, the certificate itself is PEM encoded
+#[derive(Deserialize, Debug, Clone)]
+pub struct TlsInfo {
+    #[serde(default)]
+    pub version: i64,
+    #[serde(default)]
+    pub serial_number: String,
+    #[serde(default)]
+    pub signature_algorithm: String,
+    #[serde(default)]
+    pub issuer_dn: String,
+    #[serde(default)]
+    pub subject_dn: String,
+    pub valid_from: DateTime<Utc>,
+    pub valid_to: DateTime<Utc>,
+    #[serde(default)]
+    pub thumbprint: String,
+    #[serde(default, rename = "is_CA")]
+    pub is_ca: bool,
+    #[serde(default)]
+    pub subject_alternative_name: Vec<String>,
+    #[serde(default)]
+    pub cert: String,
+}
+
+impl TlsInfo {
+    // Whether the certificate expires before the given time, e.g. to rotate
+    // certificates expiring within the next 30 days
+    pub fn expires_before(&self, time: DateTime<Utc>) -> bool {
+        self.valid_to < time
+    }
+}
+
+// New TLS certificate with its private key, both PEM encoded. The root
+// certificate is required if the issuer is not trusted by vCenter yet.
+#[derive(Serialize, Debug, Clone)]
+pub struct ReplaceSpec {
+    pub cert: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_cert: Option<String>,
+}
+
+// Subject of a certificate signing request, the key is generated by vCenter
+// and used by replace without passing it
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CsrSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub common_name: Option<String>,
+    pub organization: String,
+    pub organization_unit: String,
+    pub locality: String,
+    pub state_or_province: String,
+    pub country: String,
+    pub email_address: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub subject_alt_name: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Csr {
+    csr: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TrustedRootChainSummary {
+    #[serde(default)]
+    pub chain: String,
+}
+
+// PEM encoded certificates of the chain, from the issuing to the root
+// certificate
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CertChain {
+    #[serde(default)]
+    pub cert_chain: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TrustedRootChainInfo {
+    pub cert_chain: CertChain,
+}
+
+#[derive(Serialize)]
+struct TrustedRootChainCreateSpec<'a> {
+    cert_chain: &'a CertChain,
+}
+
+pub async fn tls(session: &Session) -> Result<TlsInfo, Error> {
+    let request = session.request(Method::GET, TLS);
+    session.send(request).await
+}
+
+// Replace the TLS certificate of vCenter, the services restart afterwards
+// so the session has to log in again
+pub async fn replace_tls(session: &Session, spec: &ReplaceSpec) -> Result<(), Error> {
+    let request = session.request(Method::PUT, TLS);
+    session
+        .send_empty(session.body(request, "spec", spec))
+        .await
+}
+
+// Generate a key and a certificate signing request for it, returns the PEM
+// encoded request
+pub async fn generate_csr(session: &Session, spec: &CsrSpec) -> Result<String, Error> {
+    let request = session.request(Method::POST, TLS_CSR);
+    let csr: Csr = session.send(session.body(request, "spec", spec)).await?;
+    Ok(csr.csr)
+}
+
+pub async fn trusted_root_chains(session: &Session) -> Result<Vec<TrustedRootChainSummary>, Error> {
+    let request = session.request(Method::GET, TRUSTED_ROOT_CHAINS);
+    session.send(request).await
+}
+
+pub async fn trusted_root_chain(
+    session: &Session,
+    chain: &str,
+) -> Result<TrustedRootChainInfo, Error> {
+    let request = session.request(Method::GET, &trusted_root_chain_endpoint(chain));
+    session.send(request).await
+}
+
+// Add a chain to the trusted roots of vCenter, returns its identifier
+pub async fn add_trusted_root_chain(
+    session: &Session,
+    cert_chain: &CertChain,
+) -> Result<String, Error> {
+    let request = session.request(Method::POST, TRUSTED_ROOT_CHAINS);
+    let spec = TrustedRootChainCreateSpec { cert_chain };
+    session.send(session.body(request, "spec", &spec)).await
+}
+
+pub async fn delete_trusted_root_chain(session: &Session, chain: &str) -> Result<(), Error> {
+    let request = session.request(Method::DELETE, &trusted_root_chain_endpoint(chain));
+    session.send_empty(request).await
+}
+
+fn trusted_root_chain_endpoint(chain: &str) -> String {
+    format!("{}/{}", TRUSTED_ROOT_CHAINS, chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TlsInfo;
+    use chrono::prelude::*;
+
+    #[test]
+    fn deserialize_tls_info() {
+        let json = r#"{"version":3,"serial_number":"c6:29:6d:4a:1e:7b","signature_algorithm":"SHA256withRSA",
+            "issuer_dn":"CN=CA, DC=vsphere, DC=local","subject_dn":"CN=vcenter.example.com",
+            "valid_from":"2021-03-04T10:00:00.000Z","valid_to":"2023-03-04T10:00:00.000Z",
+            "thumbprint":"8A:0F:3C:9E","is_CA":false,"path_length_constraint":-1,
+            "key_usage":["digitalSignature"],"extended_key_usage":[],
+            "subject_alternative_name":["vcenter.example.com"],
+            "authority_information_access_uri":[],"cert":"-----BEGIN CERTIFICATE-----\n..."}"#;
+        let info: TlsInfo = serde_json::from_str(json).expect("deserialize");
+        assert!(!info.is_ca);
+        assert_eq!(info.subject_alternative_name, vec!["vcenter.example.com"]);
+        assert!(info.expires_before(Utc.with_ymd_and_hms(2023, 4, 1, 0, 0, 0).unwrap()));
+        assert!(!info.expires_before(Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap()));
+    }
+}
@@ -0,0 +1,371 @@
+// DRS and HA configuration through the vim25 SOAP API, the REST API only
+// reports whether they are enabled. Cluster identifiers of the REST API are
+// the values of their managed object references, e.g. "domain-c8".
+use super::rules::{GroupSpec, RuleSpec};
+use crate::id::ClusterId;
+use crate::vim25::{Client, Error, ManagedObjectReference};
+use serde::de::IgnoredAny;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DrsAutomationLevel {
+    Manual,
+    PartiallyAutomated,
+    FullyAutomated,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// DRS settings, the migration threshold ranges from 1 (conservative) to 5
+// (aggressive). Unset fields are left unchanged by an update.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DrsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(rename = "defaultVmBehavior", skip_serializing_if = "Option::is_none")]
+    pub automation_level: Option<DrsAutomationLevel>,
+    #[serde(rename = "vmotionRate", skip_serializing_if = "Option::is_none")]
+    pub migration_threshold: Option<i32>,
+}
+
+// Capacity HA reserves for failover, either for a number of failed hosts or
+// as percentage of the cluster resources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdmissionControlPolicy {
+    HostFailures(i32),
+    ResourcePercentage { cpu: i32, memory: i32 },
+    // Other policies like dedicated failover hosts with their vim25 type
+    Other(String),
+}
+
+// Polymorphic wire format of the policies, the type is the xsi:type attribute
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RawPolicy {
+    #[serde(rename(serialize = "@xsi:type", deserialize = "@type"))]
+    policy_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu_failover_resources_percent: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_failover_resources_percent: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failover_level: Option<i32>,
+}
+
+impl From<RawPolicy> for AdmissionControlPolicy {
+    fn from(raw: RawPolicy) -> Self {
+        match (raw.policy_type.as_str(), raw.failover_level) {
+            ("ClusterFailoverLevelAdmissionControlPolicy", Some(level)) => {
+                AdmissionControlPolicy::HostFailures(level)
+            }
+            ("ClusterFailoverResourcesAdmissionControlPolicy", _) => {
+                AdmissionControlPolicy::ResourcePercentage {
+                    cpu: raw.cpu_failover_resources_percent.unwrap_or(0),
+                    memory: raw.memory_failover_resources_percent.unwrap_or(0),
+                }
+            }
+            _ => AdmissionControlPolicy::Other(raw.policy_type),
+        }
+    }
+}
+
+impl AdmissionControlPolicy {
+    // None for Other, which can't be set
+    fn raw(&self) -> Option<RawPolicy> {
+        let (policy_type, cpu, memory, level) = match *self {
+            AdmissionControlPolicy::HostFailures(level) => (
+                "ClusterFailoverLevelAdmissionControlPolicy",
+                None,
+                None,
+                Some(level),
+            ),
+            AdmissionControlPolicy::ResourcePercentage { cpu, memory } => (
+                "ClusterFailoverResourcesAdmissionControlPolicy",
+                Some(cpu),
+                Some(memory),
+                None,
+            ),
+            AdmissionControlPolicy::Other(_) => return None,
+        };
+        Some(RawPolicy {
+            policy_type: policy_type.to_owned(),
+            cpu_failover_resources_percent: cpu,
+            memory_failover_resources_percent: memory,
+            failover_level: level,
+        })
+    }
+}
+
+// HA settings, unset fields are left unchanged by an update
+#[derive(Debug, Default, Clone)]
+pub struct HaConfig {
+    pub enabled: Option<bool>,
+    pub admission_control_enabled: Option<bool>,
+    pub admission_control_policy: Option<AdmissionControlPolicy>,
+}
+
+// The SOAP API requires the fields in this order
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct DasConfigInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    admission_control_policy: Option<RawPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    admission_control_enabled: Option<bool>,
+}
+
+impl From<DasConfigInfo> for HaConfig {
+    fn from(info: DasConfigInfo) -> Self {
+        HaConfig {
+            enabled: info.enabled,
+            admission_control_enabled: info.admission_control_enabled,
+            admission_control_policy: info.admission_control_policy.map(Into::into),
+        }
+    }
+}
+
+impl From<&HaConfig> for DasConfigInfo {
+    fn from(config: &HaConfig) -> Self {
+        DasConfigInfo {
+            enabled: config.enabled,
+            admission_control_policy: config
+                .admission_control_policy
+                .as_ref()
+                .and_then(AdmissionControlPolicy::raw),
+            admission_control_enabled: config.admission_control_enabled,
+        }
+    }
+}
+
+// Resources of the hosts in the cluster, the effective ones are those of
+// the hosts which are connected and not in maintenance mode
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSummary {
+    #[serde(default, rename = "totalCpu")]
+    pub total_cpu_mhz: i64,
+    #[serde(default, rename = "totalMemory")]
+    pub total_memory_bytes: i64,
+    #[serde(default)]
+    pub num_cpu_cores: i32,
+    #[serde(default, rename = "effectiveCpu")]
+    pub effective_cpu_mhz: i64,
+    #[serde(default, rename = "effectiveMemory")]
+    pub effective_memory_mb: i64,
+    #[serde(default)]
+    pub num_hosts: i32,
+    #[serde(default)]
+    pub num_effective_hosts: i32,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ClusterConfig {
+    pub drs: DrsConfig,
+    pub ha: HaConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigInfoEx {
+    #[serde(default)]
+    das_config: DasConfigInfo,
+    #[serde(default)]
+    drs_config: DrsConfig,
+}
+
+// Changes of a reconfiguration, the SOAP API requires the fields in this order
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ConfigSpecEx<'a> {
+    #[serde(rename = "@xsi:type")]
+    pub(super) spec_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) das_config: Option<DasConfigInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) drs_config: Option<&'a DrsConfig>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) rules_spec: Vec<RuleSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) group_spec: Vec<GroupSpec>,
+}
+
+impl Default for ConfigSpecEx<'_> {
+    fn default() -> Self {
+        ConfigSpecEx {
+            spec_type: "ClusterConfigSpecEx",
+            das_config: None,
+            drs_config: None,
+            rules_spec: Vec::new(),
+            group_spec: Vec::new(),
+        }
+    }
+}
+
+// Modify only changes the settings which are set
+#[derive(Serialize)]
+struct ReconfigureRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    spec: ConfigSpecEx<'a>,
+    modify: bool,
+}
+
+pub async fn get(client: &Client<'_>, cluster: &ClusterId) -> Result<ClusterConfig, Error> {
+    let info: Option<ConfigInfoEx> = client
+        .retrieve_property(&cluster_reference(cluster), "configurationEx")
+        .await?;
+    Ok(info
+        .map(|info| ClusterConfig {
+            drs: info.drs_config,
+            ha: info.das_config.into(),
+        })
+        .unwrap_or_default())
+}
+
+pub async fn resource_summary(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+) -> Result<ResourceSummary, Error> {
+    let summary: Option<ResourceSummary> = client
+        .retrieve_property(&cluster_reference(cluster), "summary")
+        .await?;
+    Ok(summary.unwrap_or_default())
+}
+
+// Change the DRS settings and wait until the cluster is reconfigured
+pub async fn update_drs(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    drs: &DrsConfig,
+) -> Result<(), Error> {
+    let spec = ConfigSpecEx {
+        drs_config: Some(drs),
+        ..Default::default()
+    };
+    reconfigure(client, cluster, spec).await
+}
+
+// Change the HA settings and wait until HA is configured on the hosts
+pub async fn update_ha(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    ha: &HaConfig,
+) -> Result<(), Error> {
+    let spec = ConfigSpecEx {
+        das_config: Some(ha.into()),
+        ..Default::default()
+    };
+    reconfigure(client, cluster, spec).await
+}
+
+pub(super) async fn reconfigure(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    spec: ConfigSpecEx<'_>,
+) -> Result<(), Error> {
+    let cluster = cluster_reference(cluster);
+    let request = ReconfigureRequest {
+        this: &cluster,
+        spec,
+        modify: true,
+    };
+    let task = client
+        .call("ReconfigureComputeResource_Task", &request)
+        .await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+pub(super) fn cluster_reference(cluster: &ClusterId) -> ManagedObjectReference {
+    ManagedObjectReference::new(cluster.object_type(), cluster.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vim25::envelope;
+
+    #[test]
+    fn reconfigure_envelope() {
+        let cluster = cluster_reference(&ClusterId::new("domain-c8"));
+        let ha = HaConfig {
+            enabled: Some(true),
+            admission_control_enabled: Some(true),
+            admission_control_policy: Some(AdmissionControlPolicy::ResourcePercentage {
+                cpu: 25,
+                memory: 25,
+            }),
+        };
+        let drs = DrsConfig {
+            enabled: Some(true),
+            automation_level: Some(DrsAutomationLevel::FullyAutomated),
+            migration_threshold: None,
+        };
+        let request = ReconfigureRequest {
+            this: &cluster,
+            spec: ConfigSpecEx {
+                das_config: Some((&ha).into()),
+                drs_config: Some(&drs),
+                ..Default::default()
+            },
+            modify: true,
+        };
+        let envelope = envelope("ReconfigureComputeResource_Task", &request).unwrap();
+        assert!(envelope.contains(
+            "<spec xsi:type=\"ClusterConfigSpecEx\"><dasConfig><enabled>true</enabled>\
+             <admissionControlPolicy xsi:type=\"ClusterFailoverResourcesAdmissionControlPolicy\">\
+             <cpuFailoverResourcesPercent>25</cpuFailoverResourcesPercent>\
+             <memoryFailoverResourcesPercent>25</memoryFailoverResourcesPercent>\
+             </admissionControlPolicy><admissionControlEnabled>true</admissionControlEnabled>\
+             </dasConfig><drsConfig><enabled>true</enabled>\
+             <defaultVmBehavior>fullyAutomated</defaultVmBehavior></drsConfig></spec>\
+             <modify>true</modify>"
+        ));
+    }
+
+    #[test]
+    fn deserialize_configuration() {
+        let xml = r#"<val xsi:type="ClusterConfigInfoEx"><dasConfig><enabled>true</enabled>
+            <vmMonitoring>vmMonitoringDisabled</vmMonitoring><hostMonitoring>enabled</hostMonitoring>
+            <failoverLevel>1</failoverLevel>
+            <admissionControlPolicy xsi:type="ClusterFailoverLevelAdmissionControlPolicy">
+            <failoverLevel>1</failoverLevel></admissionControlPolicy>
+            <admissionControlEnabled>true</admissionControlEnabled></dasConfig>
+            <drsConfig><enabled>true</enabled><enableVmBehaviorOverrides>true</enableVmBehaviorOverrides>
+            <defaultVmBehavior>partiallyAutomated</defaultVmBehavior><vmotionRate>3</vmotionRate>
+            </drsConfig></val>"#;
+        let info: ConfigInfoEx = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(
+            info.drs_config.automation_level,
+            Some(DrsAutomationLevel::PartiallyAutomated)
+        );
+        assert_eq!(info.drs_config.migration_threshold, Some(3));
+        let ha = HaConfig::from(info.das_config);
+        assert_eq!(
+            ha.admission_control_policy,
+            Some(AdmissionControlPolicy::HostFailures(1))
+        );
+    }
+
+    #[test]
+    fn deserialize_resource_summary() {
+        let xml = r#"<val xsi:type="ClusterComputeResourceSummary"><totalCpu>125312</totalCpu>
+            <totalMemory>824597577728</totalMemory><numCpuCores>48</numCpuCores><numCpuThreads>96</numCpuThreads>
+            <effectiveCpu>114040</effectiveCpu><effectiveMemory>762213</effectiveMemory><numHosts>3</numHosts>
+            <numEffectiveHosts>3</numEffectiveHosts><overallStatus>green</overallStatus>
+            <currentFailoverLevel>1</currentFailoverLevel></val>"#;
+        let summary: ResourceSummary = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(summary.total_cpu_mhz, 125312);
+        assert_eq!(summary.effective_memory_mb, 762213);
+        assert_eq!(summary.num_effective_hosts, 3);
+    }
+}
@@ -0,0 +1,151 @@
+// DRS and HA configuration of a cluster
+#[cfg(feature = "vim25")]
+pub mod config;
+// DRS groups and affinity rules of a cluster
+#[cfg(feature = "vim25")]
+pub mod rules;
+
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query};
+use crate::id::{ClusterId, DatacenterId, FolderId, ResourcePoolId};
+use crate::paging::PagedStream;
+use reqwest::Method;
+
+// Summary of a cluster as returned by the list call
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClusterSummary {
+    pub cluster: ClusterId,
+    pub name: String,
+    #[serde(default)]
+    pub ha_enabled: bool,
+    #[serde(default)]
+    pub drs_enabled: bool,
+}
+
+// Detailed information about a cluster, the resource pool is the root
+// resource pool which holds the resources of the cluster. The cluster API
+// doesn't report the resources themselves, they are only available through
+// vim25 with config::resource_summary (feature vim25).
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClusterInfo {
+    pub name: String,
+    pub resource_pool: ResourcePoolId,
+    // Only reported by the list call, get fills them in. None if the list
+    // call didn't return the cluster.
+    #[serde(default)]
+    pub ha_enabled: Option<bool>,
+    #[serde(default)]
+    pub drs_enabled: Option<bool>,
+}
+
+// Filter for the list call, empty fields match every cluster
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub clusters: Vec<ClusterId>,
+    pub names: Vec<String>,
+    pub folders: Vec<FolderId>,
+    pub datacenters: Vec<DatacenterId>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "clusters", &self.clusters);
+        push_query(&mut query, "names", &self.names);
+        push_query(&mut query, "folders", &self.folders);
+        push_query(&mut query, "datacenters", &self.datacenters);
+        query
+    }
+}
+
+// List the clusters matching the filter (at most 1000 are returned)
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<ClusterSummary>, Error> {
+    let request = session.list("/vcenter/cluster", &filter.query());
+    session.send(request).await
+}
+
+// Stream the clusters matching the filter, they are fetched with a single list call
+pub fn list_stream<'a>(
+    session: &'a Session,
+    filter: &FilterSpec,
+) -> PagedStream<'a, ClusterSummary> {
+    let filter = filter.clone();
+    PagedStream::once(async move { list(session, &filter).await })
+}
+
+pub async fn get(session: &Session, cluster: &ClusterId) -> Result<ClusterInfo, Error> {
+    let request = session.request(Method::GET, &format!("/vcenter/cluster/{}", cluster));
+    let mut info: ClusterInfo = session.send(request).await?;
+    let filter = FilterSpec {
+        clusters: vec![cluster.clone()],
+        ..Default::default()
+    };
+    if let Some(summary) = list(session, &filter).await?.pop() {
+        info.ha_enabled = Some(summary.ha_enabled);
+        info.drs_enabled = Some(summary.drs_enabled);
+    }
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get;
+    use crate::cis::transport::MockTransport;
+    use crate::cis::Session;
+    use crate::id::ClusterId;
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn get_with_drs_and_ha() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/cluster/domain-c8",
+            StatusCode::OK,
+            r#"{"value":{"name":"Compute","resource_pool":"resgroup-9"}}"#,
+        );
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/cluster?filter.clusters=domain-c8",
+            StatusCode::OK,
+            r#"{"value":[{"cluster":"domain-c8","name":"Compute","ha_enabled":true,"drs_enabled":false}]}"#,
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock)
+            .build()
+            .expect("build");
+        let info = get(&session, &ClusterId::new("domain-c8"))
+            .await
+            .expect("get");
+        assert_eq!(info.resource_pool, "resgroup-9");
+        assert_eq!(info.ha_enabled, Some(true));
+        assert_eq!(info.drs_enabled, Some(false));
+    }
+
+    #[tokio::test]
+    async fn get_without_list_entry() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/cluster/domain-c8",
+            StatusCode::OK,
+            r#"{"value":{"name":"Compute","resource_pool":"resgroup-9"}}"#,
+        );
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/cluster?filter.clusters=domain-c8",
+            StatusCode::OK,
+            r#"{"value":[]}"#,
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock)
+            .build()
+            .expect("build");
+        let info = get(&session, &ClusterId::new("domain-c8"))
+            .await
+            .expect("get");
+        assert_eq!(info.ha_enabled, None);
+        assert_eq!(info.drs_enabled, None);
+    }
+}
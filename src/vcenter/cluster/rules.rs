@@ -0,0 +1,458 @@
+// DRS groups of virtual machines or hosts and the rules placing them, e.g.
+// to run licensed virtual machines only on a group of hosts
+use super::config::{cluster_reference, reconfigure, ConfigSpecEx};
+use crate::id::{ClusterId, HostId, VmId};
+use crate::vim25::{Client, Error, ManagedObjectReference};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Group {
+    Vm { name: String, vms: Vec<VmId> },
+    Host { name: String, hosts: Vec<HostId> },
+}
+
+impl Group {
+    pub fn name(&self) -> &str {
+        match self {
+            Group::Vm { name, .. } | Group::Host { name, .. } => name,
+        }
+    }
+}
+
+// Placement a rule enforces. Affinity keeps the virtual machines on the same
+// host and anti affinity on different hosts, a VM/host rule keeps the group
+// of virtual machines on or off a group of hosts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleKind {
+    Affinity(Vec<VmId>),
+    AntiAffinity(Vec<VmId>),
+    VmHost {
+        vm_group: String,
+        affine_host_group: Option<String>,
+        anti_affine_host_group: Option<String>,
+    },
+    // Other rules like dependency rules with their vim25 type
+    Other(String),
+}
+
+// Rule of a cluster, the key is assigned by vCenter and identifies the rule
+// for updates. Mandatory rules are never violated by DRS or HA, others may be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub key: Option<i32>,
+    pub name: String,
+    pub enabled: bool,
+    pub mandatory: bool,
+    pub kind: RuleKind,
+}
+
+impl Rule {
+    pub fn new(name: impl Into<String>, kind: RuleKind) -> Self {
+        Rule {
+            key: None,
+            name: name.into(),
+            enabled: true,
+            mandatory: false,
+            kind,
+        }
+    }
+}
+
+// Polymorphic wire format of the groups, the type is the xsi:type attribute
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct RawGroup {
+    #[serde(rename(serialize = "@xsi:type", deserialize = "@type"))]
+    group_type: String,
+    name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    vm: Vec<ManagedObjectReference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    host: Vec<ManagedObjectReference>,
+}
+
+impl From<&Group> for RawGroup {
+    fn from(group: &Group) -> Self {
+        match group {
+            Group::Vm { name, vms } => RawGroup {
+                group_type: "ClusterVmGroup".to_owned(),
+                name: name.clone(),
+                vm: vms
+                    .iter()
+                    .map(|vm| ManagedObjectReference::new(vm.object_type(), vm.as_str()))
+                    .collect(),
+                host: Vec::new(),
+            },
+            Group::Host { name, hosts } => RawGroup {
+                group_type: "ClusterHostGroup".to_owned(),
+                name: name.clone(),
+                vm: Vec::new(),
+                host: hosts
+                    .iter()
+                    .map(|host| ManagedObjectReference::new(host.object_type(), host.as_str()))
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl RawGroup {
+    fn into_group(self) -> Option<Group> {
+        match self.group_type.as_str() {
+            "ClusterVmGroup" => Some(Group::Vm {
+                name: self.name,
+                vms: self.vm.into_iter().map(|vm| VmId::new(vm.value)).collect(),
+            }),
+            "ClusterHostGroup" => Some(Group::Host {
+                name: self.name,
+                hosts: self
+                    .host
+                    .into_iter()
+                    .map(|host| HostId::new(host.value))
+                    .collect(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+// Polymorphic wire format of the rules, the SOAP API requires the fields in
+// this order
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RawRule {
+    #[serde(rename(serialize = "@xsi:type", deserialize = "@type"))]
+    rule_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<i32>,
+    #[serde(default)]
+    enabled: bool,
+    name: String,
+    #[serde(default)]
+    mandatory: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    vm: Vec<ManagedObjectReference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vm_group_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    affine_host_group_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    anti_affine_host_group_name: Option<String>,
+}
+
+impl From<&Rule> for RawRule {
+    fn from(rule: &Rule) -> Self {
+        let mut raw = RawRule {
+            rule_type: String::new(),
+            key: rule.key,
+            enabled: rule.enabled,
+            name: rule.name.clone(),
+            mandatory: rule.mandatory,
+            vm: Vec::new(),
+            vm_group_name: None,
+            affine_host_group_name: None,
+            anti_affine_host_group_name: None,
+        };
+        let vm_references = |vms: &[VmId]| {
+            vms.iter()
+                .map(|vm| ManagedObjectReference::new(vm.object_type(), vm.as_str()))
+                .collect()
+        };
+        match &rule.kind {
+            RuleKind::Affinity(vms) => {
+                raw.rule_type = "ClusterAffinityRuleSpec".to_owned();
+                raw.vm = vm_references(vms);
+            }
+            RuleKind::AntiAffinity(vms) => {
+                raw.rule_type = "ClusterAntiAffinityRuleSpec".to_owned();
+                raw.vm = vm_references(vms);
+            }
+            RuleKind::VmHost {
+                vm_group,
+                affine_host_group,
+                anti_affine_host_group,
+            } => {
+                raw.rule_type = "ClusterVmHostRuleInfo".to_owned();
+                raw.vm_group_name = Some(vm_group.clone());
+                raw.affine_host_group_name = affine_host_group.clone();
+                raw.anti_affine_host_group_name = anti_affine_host_group.clone();
+            }
+            RuleKind::Other(rule_type) => raw.rule_type = rule_type.clone(),
+        }
+        raw
+    }
+}
+
+impl From<RawRule> for Rule {
+    fn from(raw: RawRule) -> Self {
+        let vms = || {
+            raw.vm
+                .iter()
+                .map(|vm| VmId::new(vm.value.as_str()))
+                .collect()
+        };
+        let kind = match raw.rule_type.as_str() {
+            "ClusterAffinityRuleSpec" => RuleKind::Affinity(vms()),
+            "ClusterAntiAffinityRuleSpec" => RuleKind::AntiAffinity(vms()),
+            "ClusterVmHostRuleInfo" => RuleKind::VmHost {
+                vm_group: raw.vm_group_name.clone().unwrap_or_default(),
+                affine_host_group: raw.affine_host_group_name.clone(),
+                anti_affine_host_group: raw.anti_affine_host_group_name.clone(),
+            },
+            other => RuleKind::Other(other.to_owned()),
+        };
+        Rule {
+            key: raw.key,
+            name: raw.name,
+            enabled: raw.enabled,
+            mandatory: raw.mandatory,
+            kind,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum Operation {
+    Add,
+    Edit,
+    Remove,
+}
+
+// Key of an element to remove, typed as xsd:anyType in the SOAP API
+#[derive(Serialize, Debug)]
+struct RemoveKey {
+    #[serde(rename = "@xsi:type")]
+    key_type: &'static str,
+    #[serde(rename = "$text")]
+    value: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GroupSpec {
+    operation: Operation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remove_key: Option<RemoveKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    info: Option<RawGroup>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct RuleSpec {
+    operation: Operation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remove_key: Option<RemoveKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    info: Option<RawRule>,
+}
+
+#[derive(Deserialize)]
+struct ConfigInfoEx {
+    #[serde(default)]
+    group: Vec<RawGroup>,
+    #[serde(default)]
+    rule: Vec<RawRule>,
+}
+
+async fn config_info(client: &Client<'_>, cluster: &ClusterId) -> Result<ConfigInfoEx, Error> {
+    let info = client
+        .retrieve_property(&cluster_reference(cluster), "configurationEx")
+        .await?;
+    Ok(info.unwrap_or(ConfigInfoEx {
+        group: Vec::new(),
+        rule: Vec::new(),
+    }))
+}
+
+pub async fn groups(client: &Client<'_>, cluster: &ClusterId) -> Result<Vec<Group>, Error> {
+    let info = config_info(client, cluster).await?;
+    Ok(info
+        .group
+        .into_iter()
+        .filter_map(RawGroup::into_group)
+        .collect())
+}
+
+// Add a group, fails if a group with the name exists
+pub async fn add_group(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    group: &Group,
+) -> Result<(), Error> {
+    update_groups(client, cluster, group_spec(Operation::Add, group)).await
+}
+
+// Replace the members of the group with the name
+pub async fn update_group(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    group: &Group,
+) -> Result<(), Error> {
+    update_groups(client, cluster, group_spec(Operation::Edit, group)).await
+}
+
+pub async fn remove_group(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    name: &str,
+) -> Result<(), Error> {
+    let spec = GroupSpec {
+        operation: Operation::Remove,
+        remove_key: Some(RemoveKey {
+            key_type: "xsd:string",
+            value: name.to_owned(),
+        }),
+        info: None,
+    };
+    update_groups(client, cluster, spec).await
+}
+
+pub async fn rules(client: &Client<'_>, cluster: &ClusterId) -> Result<Vec<Rule>, Error> {
+    let info = config_info(client, cluster).await?;
+    Ok(info.rule.into_iter().map(Rule::from).collect())
+}
+
+// Add a rule, the groups of a VM/host rule have to exist
+pub async fn add_rule(client: &Client<'_>, cluster: &ClusterId, rule: &Rule) -> Result<(), Error> {
+    update_rules(client, cluster, rule_spec(Operation::Add, rule)).await
+}
+
+// Update the rule with the key of the rule
+pub async fn update_rule(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    rule: &Rule,
+) -> Result<(), Error> {
+    update_rules(client, cluster, rule_spec(Operation::Edit, rule)).await
+}
+
+pub async fn remove_rule(client: &Client<'_>, cluster: &ClusterId, key: i32) -> Result<(), Error> {
+    let spec = RuleSpec {
+        operation: Operation::Remove,
+        remove_key: Some(RemoveKey {
+            key_type: "xsd:int",
+            value: key.to_string(),
+        }),
+        info: None,
+    };
+    update_rules(client, cluster, spec).await
+}
+
+fn group_spec(operation: Operation, group: &Group) -> GroupSpec {
+    GroupSpec {
+        operation,
+        remove_key: None,
+        info: Some(group.into()),
+    }
+}
+
+fn rule_spec(operation: Operation, rule: &Rule) -> RuleSpec {
+    RuleSpec {
+        operation,
+        remove_key: None,
+        info: Some(rule.into()),
+    }
+}
+
+async fn update_groups(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    spec: GroupSpec,
+) -> Result<(), Error> {
+    let spec = ConfigSpecEx {
+        group_spec: vec![spec],
+        ..Default::default()
+    };
+    reconfigure(client, cluster, spec).await
+}
+
+async fn update_rules(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    spec: RuleSpec,
+) -> Result<(), Error> {
+    let spec = ConfigSpecEx {
+        rules_spec: vec![spec],
+        ..Default::default()
+    };
+    reconfigure(client, cluster, spec).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vim25::envelope;
+
+    #[test]
+    fn rule_spec_envelope() {
+        let mut rule = Rule::new(
+            "licensed-on-licensed-hosts",
+            RuleKind::VmHost {
+                vm_group: "licensed-vms".to_owned(),
+                affine_host_group: Some("licensed-hosts".to_owned()),
+                anti_affine_host_group: None,
+            },
+        );
+        rule.mandatory = true;
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Spec {
+            rules_spec: RuleSpec,
+        }
+        let spec = Spec {
+            rules_spec: rule_spec(Operation::Add, &rule),
+        };
+        let xml = envelope("Spec", &spec).unwrap();
+        assert!(xml.contains(
+            "<rulesSpec><operation>add</operation><info xsi:type=\"ClusterVmHostRuleInfo\">\
+             <enabled>true</enabled><name>licensed-on-licensed-hosts</name>\
+             <mandatory>true</mandatory><vmGroupName>licensed-vms</vmGroupName>\
+             <affineHostGroupName>licensed-hosts</affineHostGroupName></info></rulesSpec>"
+        ));
+
+        let spec = GroupSpec {
+            operation: Operation::Remove,
+            remove_key: Some(RemoveKey {
+                key_type: "xsd:string",
+                value: "licensed-vms".to_owned(),
+            }),
+            info: None,
+        };
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Remove {
+            group_spec: GroupSpec,
+        }
+        let xml = envelope("Remove", &Remove { group_spec: spec }).unwrap();
+        assert!(xml.contains(
+            "<groupSpec><operation>remove</operation>\
+             <removeKey xsi:type=\"xsd:string\">licensed-vms</removeKey></groupSpec>"
+        ));
+    }
+
+    #[test]
+    fn deserialize_groups_and_rules() {
+        let xml = r#"<val xsi:type="ClusterConfigInfoEx">
+            <rule xsi:type="ClusterAntiAffinityRuleSpec"><key>3</key><status>green</status>
+            <enabled>true</enabled><name>separate-dns</name><mandatory>false</mandatory>
+            <vm type="VirtualMachine">vm-41</vm><vm type="VirtualMachine">vm-42</vm></rule>
+            <group xsi:type="ClusterHostGroup"><name>licensed-hosts</name>
+            <host type="HostSystem">host-10</host></group>
+            </val>"#;
+        let info: ConfigInfoEx = quick_xml::de::from_str(xml).unwrap();
+        let rule = Rule::from(info.rule[0].clone());
+        assert_eq!(rule.key, Some(3));
+        assert_eq!(
+            rule.kind,
+            RuleKind::AntiAffinity(vec![VmId::new("vm-41"), VmId::new("vm-42")])
+        );
+        let group = info.group[0].clone().into_group().unwrap();
+        assert_eq!(
+            group,
+            Group::Host {
+                name: "licensed-hosts".to_owned(),
+                hosts: vec![HostId::new("host-10")],
+            }
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query};
+use crate::id::{DatacenterId, FolderId};
+use crate::paging::PagedStream;
+use reqwest::Method;
+
+// Summary of a datacenter as returned by the list call
+#[derive(Deserialize, Debug, Clone)]
+pub struct DatacenterSummary {
+    pub datacenter: DatacenterId,
+    pub name: String,
+}
+
+// Detailed information about a datacenter including its root folders
+#[derive(Deserialize, Debug, Clone)]
+pub struct DatacenterInfo {
+    pub name: String,
+    pub datastore_folder: FolderId,
+    pub host_folder: FolderId,
+    pub network_folder: FolderId,
+    pub vm_folder: FolderId,
+}
+
+// Specification of a new datacenter, without folder it is created in the
+// root folder of vCenter
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<FolderId>,
+}
+
+// Filter for the list call, empty fields match every datacenter
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub datacenters: Vec<DatacenterId>,
+    pub names: Vec<String>,
+    pub folders: Vec<FolderId>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "datacenters", &self.datacenters);
+        push_query(&mut query, "names", &self.names);
+        push_query(&mut query, "folders", &self.folders);
+        query
+    }
+}
+
+// List the datacenters matching the filter (at most 1000 are returned)
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<DatacenterSummary>, Error> {
+    let request = session.list("/vcenter/datacenter", &filter.query());
+    session.send(request).await
+}
+
+// Stream the datacenters matching the filter, they are fetched with a single list call
+pub fn list_stream<'a>(
+    session: &'a Session,
+    filter: &FilterSpec,
+) -> PagedStream<'a, DatacenterSummary> {
+    let filter = filter.clone();
+    PagedStream::once(async move { list(session, &filter).await })
+}
+
+pub async fn get(session: &Session, datacenter: &DatacenterId) -> Result<DatacenterInfo, Error> {
+    let request = session.request(Method::GET, &format!("/vcenter/datacenter/{}", datacenter));
+    session.send(request).await
+}
+
+// Create a datacenter and return its identifier
+pub async fn create(session: &Session, spec: &CreateSpec) -> Result<DatacenterId, Error> {
+    let request = session.body(
+        session.request(Method::POST, "/vcenter/datacenter"),
+        "spec",
+        spec,
+    );
+    session.send(request).await
+}
+
+// Delete a datacenter, without force this fails if it still contains objects
+pub async fn delete(
+    session: &Session,
+    datacenter: &DatacenterId,
+    force: bool,
+) -> Result<(), Error> {
+    let request = session
+        .request(
+            Method::DELETE,
+            &format!("/vcenter/datacenter/{}", datacenter),
+        )
+        .query(&[("force", force)]);
+    session.send_empty(request).await
+}
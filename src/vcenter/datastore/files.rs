@@ -0,0 +1,186 @@
+use crate::cis::{Error, Session};
+use bytes::Bytes;
+use futures::stream::{Stream, TryStreamExt};
+use reqwest::{Body, Method, Request, StatusCode};
+
+// Entry of a datastore directory, directories are listed with a trailing slash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub name: String,
+    pub is_directory: bool,
+}
+
+// File or directory on a datastore. The datacenter is given by its inventory
+// path, e.g. "DC1" or "Europe/DC1", the path is relative to the datastore root.
+#[derive(Debug, Clone, Copy)]
+pub struct DatastorePath<'a> {
+    pub datacenter: &'a str,
+    pub datastore: &'a str,
+    pub path: &'a str,
+}
+
+impl<'a> DatastorePath<'a> {
+    pub fn new(datacenter: &'a str, datastore: &'a str, path: &'a str) -> Self {
+        DatastorePath {
+            datacenter,
+            datastore,
+            path,
+        }
+    }
+
+    fn request(
+        &self,
+        client: &reqwest::Client,
+        hostname: &str,
+        method: Method,
+    ) -> Result<Request, reqwest::Error> {
+        let mut request = client
+            .request(method, &format!("https://{}/folder", hostname))
+            .query(&[("dcPath", self.datacenter), ("dsName", self.datastore)])
+            .build()?;
+        if let Ok(mut segments) = request.url_mut().path_segments_mut() {
+            segments.extend(self.path.split('/').filter(|part| !part.is_empty()));
+        }
+        Ok(request)
+    }
+}
+
+// List a directory, the interface only offers an HTML page of its entries
+pub async fn list(session: &Session, dir: &DatastorePath<'_>) -> Result<Vec<FileEntry>, Error> {
+    let resp = send(session, Method::GET, dir, None).await?;
+    Ok(parse_listing(&resp.text().await?))
+}
+
+// Upload a file, an existing file is overwritten
+pub async fn upload(
+    session: &Session,
+    file: &DatastorePath<'_>,
+    body: impl Into<Body>,
+) -> Result<(), Error> {
+    send(session, Method::PUT, file, Some(body.into())).await?;
+    Ok(())
+}
+
+// Download a file as stream of chunks
+pub async fn download(
+    session: &Session,
+    file: &DatastorePath<'_>,
+) -> Result<impl Stream<Item = Result<Bytes, Error>>, Error> {
+    let resp = send(session, Method::GET, file, None).await?;
+    Ok(resp.bytes_stream().map_err(Error::from))
+}
+
+pub async fn delete(session: &Session, file: &DatastorePath<'_>) -> Result<(), Error> {
+    send(session, Method::DELETE, file, None).await?;
+    Ok(())
+}
+
+async fn send(
+    session: &Session,
+    method: Method,
+    path: &DatastorePath<'_>,
+    body: Option<Body>,
+) -> Result<reqwest::Response, Error> {
+    let mut request = path.request(session.client(), session.hostname(), method)?;
+    *request.body_mut() = body;
+    let resp = session.dispatch(session.authorize(request)).await?;
+    match resp.status() {
+        status if status.is_success() => Ok(resp),
+        StatusCode::NOT_FOUND => Err(Error::not_found()),
+        StatusCode::UNAUTHORIZED => Err(Error::Unauthorized),
+        StatusCode::FORBIDDEN => Err(Error::forbidden()),
+        status => Err(Error::UnexpectedStatusCode(status.as_u16())),
+    }
+}
+
+// Extract the entries from the links of a directory page, the links to the
+// parent directory and datacenter are skipped
+fn parse_listing(html: &str) -> Vec<FileEntry> {
+    html.split("<a ")
+        .skip(1)
+        .filter_map(|link| {
+            let text = link.split_once('>')?.1.split_once("</a>")?.0.trim();
+            if text.is_empty() || text.starts_with("Parent ") {
+                return None;
+            }
+            Some(FileEntry {
+                name: text.trim_end_matches('/').to_owned(),
+                is_directory: text.ends_with('/'),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cis::transport::MockTransport;
+    use crate::cis::ErrorType;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn folder_requests_carry_credentials() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::POST,
+            "/rest/com/vmware/cis/session",
+            StatusCode::OK,
+            r#"{"value":"4d1f0c"}"#,
+        );
+        mock.once(Method::DELETE, "/folder/iso/old.iso", StatusCode::OK, "");
+        mock.once(
+            Method::DELETE,
+            "/folder/iso/old.iso",
+            StatusCode::FORBIDDEN,
+            "",
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        assert!(session.login("root", Some("secret")).await.expect("login"));
+        let file = DatastorePath::new("DC1", "ds1", "iso/old.iso");
+        delete(&session, &file).await.expect("delete");
+        let requests = mock.requests();
+        assert_eq!(
+            requests[1].headers["authorization"],
+            "Basic cm9vdDpzZWNyZXQ="
+        );
+        let err = delete(&session, &file).await.unwrap_err();
+        assert_eq!(err.error_type(), Some(&ErrorType::Unauthorized));
+    }
+
+    #[test]
+    fn url_encodes_path() {
+        let path = DatastorePath::new("Europe/DC1", "ds 1", "iso/ubuntu 20.04.iso");
+        let request = path
+            .request(&reqwest::Client::new(), "vc.example.com", Method::GET)
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "https://vc.example.com/folder/iso/ubuntu%2020.04.iso?dcPath=Europe%2FDC1&dsName=ds+1"
+        );
+    }
+
+    #[test]
+    fn listing() {
+        let html = r#"<html><body><table>
+            <tr><td><a href="/folder?dcPath=DC1">Parent Datacenter</a></td></tr>
+            <tr><td><a href="/folder/iso?dcPath=DC1&amp;dsName=ds1">iso/</a></td></tr>
+            <tr><td><a href="/folder/vmware.log?dcPath=DC1&amp;dsName=ds1">vmware.log</a></td><td>1024</td></tr>
+            </table></body></html>"#;
+        assert_eq!(
+            parse_listing(html),
+            vec![
+                FileEntry {
+                    name: "iso".into(),
+                    is_directory: true,
+                },
+                FileEntry {
+                    name: "vmware.log".into(),
+                    is_directory: false,
+                },
+            ]
+        );
+    }
+}
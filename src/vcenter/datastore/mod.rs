@@ -0,0 +1,101 @@
+// Files on a datastore through the /folder HTTP interface
+pub mod files;
+
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query};
+use crate::id::{DatacenterId, DatastoreId, FolderId};
+use crate::paging::PagedStream;
+use reqwest::Method;
+
+// Type of a datastore
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DatastoreType {
+    #[display(fmt = "VMFS")]
+    Vmfs,
+    #[display(fmt = "NFS")]
+    Nfs,
+    #[display(fmt = "NFS41")]
+    Nfs41,
+    #[display(fmt = "CIFS")]
+    Cifs,
+    #[display(fmt = "VSAN")]
+    Vsan,
+    #[display(fmt = "VFFS")]
+    Vffs,
+    #[display(fmt = "VVOL")]
+    Vvol,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    #[display(fmt = "UNKNOWN")]
+    Unknown,
+}
+
+// Summary of a datastore as returned by the list call
+#[derive(Deserialize, Debug, Clone)]
+pub struct DatastoreSummary {
+    pub datastore: DatastoreId,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub datastore_type: DatastoreType,
+    pub free_space: Option<u64>,
+    pub capacity: Option<u64>,
+}
+
+// Detailed information about a datastore
+#[derive(Deserialize, Debug, Clone)]
+pub struct DatastoreInfo {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub datastore_type: DatastoreType,
+    #[serde(default)]
+    pub accessible: bool,
+    pub free_space: Option<u64>,
+    pub capacity: Option<u64>,
+    #[serde(default)]
+    pub multiple_host_access: bool,
+    #[serde(default)]
+    pub thin_provisioning_supported: bool,
+}
+
+// Filter for the list call, empty fields match every datastore
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub datastores: Vec<DatastoreId>,
+    pub names: Vec<String>,
+    pub types: Vec<DatastoreType>,
+    pub folders: Vec<FolderId>,
+    pub datacenters: Vec<DatacenterId>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "datastores", &self.datastores);
+        push_query(&mut query, "names", &self.names);
+        push_query(&mut query, "types", &self.types);
+        push_query(&mut query, "folders", &self.folders);
+        push_query(&mut query, "datacenters", &self.datacenters);
+        query
+    }
+}
+
+// List the datastores matching the filter (at most 2500 are returned)
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<DatastoreSummary>, Error> {
+    let request = session.list("/vcenter/datastore", &filter.query());
+    session.send(request).await
+}
+
+// Stream the datastores matching the filter, they are fetched with a single list call
+pub fn list_stream<'a>(
+    session: &'a Session,
+    filter: &FilterSpec,
+) -> PagedStream<'a, DatastoreSummary> {
+    let filter = filter.clone();
+    PagedStream::once(async move { list(session, &filter).await })
+}
+
+pub async fn get(session: &Session, datastore: &DatastoreId) -> Result<DatastoreInfo, Error> {
+    let request = session.request(Method::GET, &format!("/vcenter/datastore/{}", datastore));
+    session.send(request).await
+}
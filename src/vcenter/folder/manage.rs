@@ -0,0 +1,64 @@
+// Creation and deletion of folders through the vim25 SOAP API, the REST API
+// can only list folders. Folder identifiers of the REST API are the values
+// of their managed object references, e.g. "group-v3".
+use crate::id::FolderId;
+use crate::vim25::{Client, Error, ManagedObjectReference, This};
+use serde::de::IgnoredAny;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize)]
+struct CreateFolderRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    name: &'a str,
+}
+
+// Create a folder in the parent folder and return its identifier, it holds
+// the same type of objects as the parent
+pub async fn create(client: &Client<'_>, parent: &FolderId, name: &str) -> Result<FolderId, Error> {
+    let parent = folder_reference(parent);
+    let request = CreateFolderRequest {
+        this: &parent,
+        name,
+    };
+    let folder: ManagedObjectReference = client.call("CreateFolder", &request).await?;
+    Ok(FolderId::new(folder.value))
+}
+
+// Delete the folder with everything in it and wait until it is done. Virtual
+// machines in it are deleted from their datastores, powered on ones make
+// the deletion fail.
+pub async fn delete(client: &Client<'_>, folder: &FolderId) -> Result<(), Error> {
+    let folder = folder_reference(folder);
+    let task = client.call("Destroy_Task", &This { this: &folder }).await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+fn folder_reference(folder: &FolderId) -> ManagedObjectReference {
+    ManagedObjectReference::new(folder.object_type(), folder.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{folder_reference, CreateFolderRequest};
+    use crate::id::FolderId;
+    use crate::vim25::envelope;
+
+    #[test]
+    fn create_folder_envelope() {
+        let parent = folder_reference(&FolderId::new("group-v3"));
+        let request = CreateFolderRequest {
+            this: &parent,
+            name: "Production",
+        };
+        let xml = envelope("CreateFolder", &request).unwrap();
+        assert!(xml.contains(
+            r#"<CreateFolder xmlns="urn:vim25"><_this type="Folder">group-v3</_this><name>Production</name></CreateFolder>"#
+        ));
+    }
+}
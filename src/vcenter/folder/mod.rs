@@ -0,0 +1,258 @@
+// Creation and deletion of folders
+#[cfg(feature = "vim25")]
+pub mod manage;
+
+use super::datacenter;
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query};
+use crate::id::{DatacenterId, FolderId};
+use crate::paging::PagedStream;
+use std::collections::{HashMap, HashSet};
+
+// Root folder of the inventory of every vCenter
+const ROOT_FOLDER: &str = "group-d1";
+
+// Type of the objects a folder contains
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FolderType {
+    #[display(fmt = "DATACENTER")]
+    Datacenter,
+    #[display(fmt = "DATASTORE")]
+    Datastore,
+    #[display(fmt = "HOST")]
+    Host,
+    #[display(fmt = "NETWORK")]
+    Network,
+    #[display(fmt = "VIRTUAL_MACHINE")]
+    VirtualMachine,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    #[display(fmt = "UNKNOWN")]
+    Unknown,
+}
+
+// Summary of a folder as returned by the list call
+#[derive(Deserialize, Debug, Clone)]
+pub struct FolderSummary {
+    pub folder: FolderId,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub folder_type: FolderType,
+}
+
+// Filter for the list call, empty fields match every folder
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub folders: Vec<FolderId>,
+    pub names: Vec<String>,
+    pub folder_type: Option<FolderType>,
+    pub parent_folders: Vec<FolderId>,
+    pub datacenters: Vec<DatacenterId>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "folders", &self.folders);
+        push_query(&mut query, "names", &self.names);
+        if let Some(folder_type) = self.folder_type {
+            query.push(("type", folder_type.to_string()));
+        }
+        push_query(&mut query, "parent_folders", &self.parent_folders);
+        push_query(&mut query, "datacenters", &self.datacenters);
+        query
+    }
+}
+
+// List the folders matching the filter (at most 1000 are returned)
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<FolderSummary>, Error> {
+    let request = session.list("/vcenter/folder", &filter.query());
+    session.send(request).await
+}
+
+// Stream the folders matching the filter, they are fetched with a single list call
+pub fn list_stream<'a>(
+    session: &'a Session,
+    filter: &FilterSpec,
+) -> PagedStream<'a, FolderSummary> {
+    let filter = filter.clone();
+    PagedStream::once(async move { list(session, &filter).await })
+}
+
+// The REST API has no get call for folders, so this filters the list call
+// and fails with ErrorType::NotFound if the folder does not exist. Folders
+// can't be created or deleted through the REST API either, see manage.
+pub async fn get(session: &Session, folder: &FolderId) -> Result<FolderSummary, Error> {
+    let filter = FilterSpec {
+        folders: vec![folder.clone()],
+        ..Default::default()
+    };
+    match list(session, &filter).await?.pop() {
+        Some(summary) => Ok(summary),
+        None => Err(Error::not_found()),
+    }
+}
+
+// Walk the folder hierarchy below every datacenter and map inventory paths
+// like "Datacenter1/vm/Production" to the identifier of the object.
+// Datacenters in folders get the path of their folder, e.g.
+// "Europe/Datacenter2/vm".
+pub async fn paths(session: &Session) -> Result<HashMap<String, String>, Error> {
+    let mut paths = HashMap::new();
+    let mut pending = Vec::new();
+    for (path, dc) in datacenter_paths(session, &mut paths).await? {
+        let info = datacenter::get(session, &dc).await?;
+        // The root folders of a datacenter have fixed names
+        pending.push((format!("{}/vm", path), info.vm_folder));
+        pending.push((format!("{}/host", path), info.host_folder));
+        pending.push((format!("{}/datastore", path), info.datastore_folder));
+        pending.push((format!("{}/network", path), info.network_folder));
+        paths.insert(path, dc.into_string());
+    }
+    while let Some((path, folder)) = pending.pop() {
+        let filter = FilterSpec {
+            parent_folders: vec![folder.clone()],
+            ..Default::default()
+        };
+        for child in list(session, &filter).await? {
+            pending.push((format!("{}/{}", path, child.name), child.folder));
+        }
+        paths.insert(path, folder.into_string());
+    }
+    Ok(paths)
+}
+
+// Paths of the datacenters, the folders holding them are added to the paths.
+// The root folder of vCenter isn't part of the paths.
+async fn datacenter_paths(
+    session: &Session,
+    paths: &mut HashMap<String, String>,
+) -> Result<Vec<(String, DatacenterId)>, Error> {
+    let filter = FilterSpec {
+        folder_type: Some(FolderType::Datacenter),
+        ..Default::default()
+    };
+    let folders = list(session, &filter).await?;
+    let mut children = HashMap::new();
+    for folder in &folders {
+        let filter = FilterSpec {
+            folder_type: Some(FolderType::Datacenter),
+            parent_folders: vec![folder.folder.clone()],
+            ..Default::default()
+        };
+        children.insert(folder.folder.clone(), list(session, &filter).await?);
+    }
+    let nested: HashSet<&FolderId> = children
+        .values()
+        .flatten()
+        .map(|child| &child.folder)
+        .collect();
+    let mut pending: Vec<(String, &FolderSummary)> = folders
+        .iter()
+        .filter(|folder| !nested.contains(&folder.folder))
+        .map(|folder| match folder.folder.as_str() {
+            ROOT_FOLDER => (String::new(), folder),
+            _ => (format!("{}/", folder.name), folder),
+        })
+        .collect();
+    let mut datacenters = Vec::new();
+    let mut found = HashSet::new();
+    while let Some((prefix, folder)) = pending.pop() {
+        if !prefix.is_empty() {
+            let path = prefix.trim_end_matches('/').to_owned();
+            paths.insert(path, folder.folder.as_str().to_owned());
+        }
+        for child in children.get(&folder.folder).into_iter().flatten() {
+            pending.push((format!("{}{}/", prefix, child.name), child));
+        }
+        let filter = datacenter::FilterSpec {
+            folders: vec![folder.folder.clone()],
+            ..Default::default()
+        };
+        for dc in datacenter::list(session, &filter).await? {
+            found.insert(dc.datacenter.clone());
+            datacenters.push((format!("{}{}", prefix, dc.name), dc.datacenter));
+        }
+    }
+    // Without the root folder in the list its datacenters are found by none
+    for dc in datacenter::list(session, &Default::default()).await? {
+        if !found.contains(&dc.datacenter) {
+            datacenters.push((dc.name, dc.datacenter));
+        }
+    }
+    Ok(datacenters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::paths;
+    use crate::cis::transport::MockTransport;
+    use crate::cis::Session;
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn paths_of_nested_datacenters() {
+        let mock = Arc::new(MockTransport::new());
+        let routes = [
+            (
+                "/rest/vcenter/folder?filter.type=DATACENTER",
+                r#"[{"folder":"group-d1","name":"Datacenters","type":"DATACENTER"},
+                    {"folder":"group-d5","name":"Europe","type":"DATACENTER"}]"#,
+            ),
+            (
+                "/rest/vcenter/folder?filter.type=DATACENTER&filter.parent_folders=group-d1",
+                r#"[{"folder":"group-d5","name":"Europe","type":"DATACENTER"}]"#,
+            ),
+            (
+                "/rest/vcenter/folder?filter.parent_folders=group-v22",
+                r#"[{"folder":"group-v31","name":"Production","type":"VIRTUAL_MACHINE"}]"#,
+            ),
+            ("/rest/vcenter/folder", "[]"),
+            (
+                "/rest/vcenter/datacenter?filter.folders=group-d1",
+                r#"[{"datacenter":"datacenter-2","name":"Lab"}]"#,
+            ),
+            (
+                "/rest/vcenter/datacenter?filter.folders=group-d5",
+                r#"[{"datacenter":"datacenter-21","name":"Frankfurt"}]"#,
+            ),
+            (
+                "/rest/vcenter/datacenter/datacenter-2",
+                r#"{"name":"Lab","vm_folder":"group-v3","host_folder":"group-h4",
+                    "datastore_folder":"group-s5","network_folder":"group-n6"}"#,
+            ),
+            (
+                "/rest/vcenter/datacenter/datacenter-21",
+                r#"{"name":"Frankfurt","vm_folder":"group-v22","host_folder":"group-h23",
+                    "datastore_folder":"group-s24","network_folder":"group-n25"}"#,
+            ),
+            (
+                "/rest/vcenter/datacenter",
+                r#"[{"datacenter":"datacenter-2","name":"Lab"},
+                    {"datacenter":"datacenter-21","name":"Frankfurt"}]"#,
+            ),
+        ];
+        for (path, value) in routes.iter() {
+            mock.on(
+                Method::GET,
+                path,
+                StatusCode::OK,
+                format!(r#"{{"value":{}}}"#, value),
+            );
+        }
+        let session = Session::builder("vcenter.test")
+            .transport(mock)
+            .build()
+            .expect("build");
+        let paths = paths(&session).await.expect("paths");
+        assert_eq!(paths["Lab"], "datacenter-2");
+        assert_eq!(paths["Lab/vm"], "group-v3");
+        assert_eq!(paths["Europe"], "group-d5");
+        assert_eq!(paths["Europe/Frankfurt"], "datacenter-21");
+        assert_eq!(paths["Europe/Frankfurt/vm/Production"], "group-v31");
+        assert!(!paths.contains_key("Datacenters"));
+        assert_eq!(paths.len(), 12);
+    }
+}
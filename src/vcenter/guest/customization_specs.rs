@@ -0,0 +1,211 @@
+use super::CustomizationSpec;
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query};
+use chrono::prelude::*;
+use reqwest::Method;
+
+// Operating system type a customization specification applies to
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OsType {
+    #[display(fmt = "WINDOWS")]
+    Windows,
+    #[display(fmt = "LINUX")]
+    Linux,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    #[display(fmt = "UNKNOWN")]
+    Unknown,
+}
+
+// Format of an exported customization specification
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Format {
+    Json,
+    Xml,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpecSummary {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(rename = "OS_type")]
+    pub os_type: OsType,
+    pub last_modified: DateTime<Utc>,
+}
+
+// Specification with the fingerprint required to update it
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpecInfo {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub fingerprint: String,
+    pub spec: CustomizationSpec,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub spec: CustomizationSpec,
+}
+
+// Changes to a specification, the fingerprint has to match the current one
+// unless it is unset
+#[derive(Serialize, Debug, Clone)]
+pub struct UpdateSpec {
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    pub spec: CustomizationSpec,
+}
+
+// Filter for the list call, empty fields match every specification
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub names: Vec<String>,
+    pub os_type: Option<OsType>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "names", &self.names);
+        if let Some(os_type) = self.os_type {
+            query.push(("OS_type", os_type.to_string()));
+        }
+        query
+    }
+}
+
+#[derive(Serialize)]
+struct ExportRequest {
+    format: Format,
+}
+
+#[derive(Serialize)]
+struct ImportRequest<'a> {
+    spec: &'a str,
+}
+
+const ENDPOINT: &str = "/vcenter/guest/customization-specs";
+
+fn spec_endpoint(name: &str) -> String {
+    format!("{}/{}", ENDPOINT, name)
+}
+
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<SpecSummary>, Error> {
+    let request = session.list(ENDPOINT, &filter.query());
+    session.send(request).await
+}
+
+pub async fn get(session: &Session, name: &str) -> Result<SpecInfo, Error> {
+    let request = session.request(Method::GET, &spec_endpoint(name));
+    session.send(request).await
+}
+
+pub async fn create(session: &Session, spec: &CreateSpec) -> Result<(), Error> {
+    let request = session.request(Method::POST, ENDPOINT);
+    session
+        .send_empty(session.body(request, "spec", spec))
+        .await
+}
+
+pub async fn update(session: &Session, name: &str, spec: &UpdateSpec) -> Result<(), Error> {
+    let request = session.request(Method::PUT, &spec_endpoint(name));
+    session
+        .send_empty(session.body(request, "spec", spec))
+        .await
+}
+
+pub async fn delete(session: &Session, name: &str) -> Result<(), Error> {
+    let request = session.request(Method::DELETE, &spec_endpoint(name));
+    session.send_empty(request).await
+}
+
+// Export a specification, e.g. to copy it to another vCenter with import
+pub async fn export(session: &Session, name: &str, format: Format) -> Result<String, Error> {
+    let request = session.action(Method::POST, &spec_endpoint(name), "export");
+    session
+        .send(session.json(request, &ExportRequest { format }))
+        .await
+}
+
+// Convert an exported specification into a create specification, it is
+// not stored until passed to create
+pub async fn import(session: &Session, exported: &str) -> Result<CreateSpec, Error> {
+    let request = session.action(Method::POST, ENDPOINT, "import");
+    session
+        .send(session.json(request, &ImportRequest { spec: exported }))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vcenter::guest::{
+        AdapterMapping, ConfigurationSpec, HostnameGenerator, IpSettings, Ipv4, LinuxConfiguration,
+    };
+
+    #[test]
+    fn linux_spec() {
+        let spec = CustomizationSpec {
+            configuration_spec: ConfigurationSpec {
+                linux_config: Some(LinuxConfiguration {
+                    hostname: HostnameGenerator::virtual_machine(),
+                    domain: "lab.example.com".to_owned(),
+                    time_zone: Some("Europe/Berlin".to_owned()),
+                    script_text: None,
+                }),
+                windows_config: None,
+            },
+            global_dns_settings: Default::default(),
+            interfaces: vec![AdapterMapping {
+                mac_address: None,
+                adapter: IpSettings {
+                    ipv4: Some(Ipv4::fixed("10.0.0.10", 24, "10.0.0.1")),
+                    ..Default::default()
+                },
+            }],
+        };
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "configuration_spec": {"linux_config": {
+                    "hostname": {"type": "VIRTUAL_MACHINE"},
+                    "domain": "lab.example.com",
+                    "time_zone": "Europe/Berlin"
+                }},
+                "global_DNS_settings": {},
+                "interfaces": [{"adapter": {"ipv4": {
+                    "type": "STATIC", "ip_address": "10.0.0.10", "prefix": 24,
+                    "gateways": ["10.0.0.1"]
+                }}}]
+            })
+        );
+        let parsed: CustomizationSpec = serde_json::from_value(json).unwrap();
+        assert!(parsed.configuration_spec.windows_config.is_none());
+    }
+
+    #[test]
+    fn filter_query() {
+        let filter = FilterSpec {
+            names: vec!["linux-base".to_owned()],
+            os_type: Some(OsType::Linux),
+        };
+        assert_eq!(
+            filter.query(),
+            vec![
+                ("names", "linux-base".to_owned()),
+                ("OS_type", "LINUX".to_owned())
+            ]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+// Guest customization specifications stored in vCenter
+pub mod customization_specs;
+
+use crate::common::redacted;
+use std::fmt;
+
+// How the host or computer name of a customized guest is chosen
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HostnameType {
+    Fixed,
+    Prefix,
+    VirtualMachine,
+    UserInputRequired,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct HostnameGenerator {
+    #[serde(rename = "type")]
+    pub hostname_type: HostnameType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
+impl HostnameGenerator {
+    pub fn fixed(name: impl Into<String>) -> Self {
+        HostnameGenerator {
+            hostname_type: HostnameType::Fixed,
+            fixed_name: Some(name.into()),
+            prefix: None,
+        }
+    }
+
+    // Use the name of the virtual machine
+    pub fn virtual_machine() -> Self {
+        HostnameGenerator {
+            hostname_type: HostnameType::VirtualMachine,
+            fixed_name: None,
+            prefix: None,
+        }
+    }
+}
+
+// Customization of a Linux guest, the time zone is given as tz database
+// name, e.g. "Europe/Berlin"
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct LinuxConfiguration {
+    pub hostname: HostnameGenerator,
+    #[serde(default)]
+    pub domain: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_text: Option<String>,
+}
+
+// Action after a Windows guest was customized
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RebootOption {
+    Reboot,
+    NoReboot,
+    Shutdown,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct UserData {
+    pub computer_name: HostnameGenerator,
+    #[serde(default)]
+    pub full_name: String,
+    #[serde(default)]
+    pub organization: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_key: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DomainType {
+    Workgroup,
+    Domain,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Workgroup or Active Directory domain the Windows guest joins
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Domain {
+    #[serde(rename = "type")]
+    pub domain_type: DomainType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workgroup: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain_password: Option<String>,
+}
+
+impl fmt::Debug for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Domain")
+            .field("domain_type", &self.domain_type)
+            .field("workgroup", &self.workgroup)
+            .field("domain", &self.domain)
+            .field("domain_username", &self.domain_username)
+            .field("domain_password", &redacted(&self.domain_password))
+            .finish()
+    }
+}
+
+// Settings of the unattended setup, the time zone is a Windows time zone index
+#[derive(Deserialize, Serialize, Clone)]
+pub struct GuiUnattended {
+    #[serde(default)]
+    pub auto_logon: bool,
+    #[serde(default)]
+    pub auto_logon_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub time_zone: u32,
+}
+
+impl fmt::Debug for GuiUnattended {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GuiUnattended")
+            .field("auto_logon", &self.auto_logon)
+            .field("auto_logon_count", &self.auto_logon_count)
+            .field("password", &redacted(&self.password))
+            .field("time_zone", &self.time_zone)
+            .finish()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WindowsSysprep {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gui_run_once_commands: Vec<String>,
+    pub user_data: UserData,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<Domain>,
+    pub gui_unattended: GuiUnattended,
+}
+
+// Customization of a Windows guest with either a sysprep specification or
+// a complete sysprep answer file
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WindowsConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reboot: Option<RebootOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sysprep: Option<WindowsSysprep>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sysprep_xml: Option<String>,
+}
+
+// Exactly one of the configurations matching the guest OS is set
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct ConfigurationSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows_config: Option<WindowsConfiguration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linux_config: Option<LinuxConfiguration>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct GlobalDnsSettings {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns_suffix_list: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns_servers: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Ipv4Type {
+    Dhcp,
+    Static,
+    UserInputRequired,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Ipv4 {
+    #[serde(rename = "type")]
+    pub ipv4_type: Ipv4Type,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gateways: Vec<String>,
+}
+
+impl Ipv4 {
+    pub fn dhcp() -> Self {
+        Ipv4 {
+            ipv4_type: Ipv4Type::Dhcp,
+            ip_address: None,
+            prefix: None,
+            gateways: Vec::new(),
+        }
+    }
+
+    pub fn fixed(ip_address: impl Into<String>, prefix: u8, gateway: impl Into<String>) -> Self {
+        Ipv4 {
+            ipv4_type: Ipv4Type::Static,
+            ip_address: Some(ip_address.into()),
+            prefix: Some(prefix),
+            gateways: vec![gateway.into()],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Ipv6Type {
+    Dhcp,
+    Static,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Ipv6Address {
+    #[serde(default)]
+    pub ip_address: String,
+    #[serde(default)]
+    pub prefix: u8,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Ipv6 {
+    #[serde(rename = "type")]
+    pub ipv6_type: Ipv6Type,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ipv6: Vec<Ipv6Address>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gateways: Vec<String>,
+}
+
+// DNS settings of a network adapter of a Windows guest
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct WindowsNetworkAdapterSettings {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns_servers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_domain: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct IpSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4: Option<Ipv4>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6: Option<Ipv6>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows: Option<WindowsNetworkAdapterSettings>,
+}
+
+// Settings of a network adapter, adapters without MAC address are matched
+// in the order of the virtual hardware
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct AdapterMapping {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    pub adapter: IpSettings,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct CustomizationSpec {
+    pub configuration_spec: ConfigurationSpec,
+    #[serde(rename = "global_DNS_settings")]
+    pub global_dns_settings: GlobalDnsSettings,
+    #[serde(default)]
+    pub interfaces: Vec<AdapterMapping>,
+}
@@ -0,0 +1,251 @@
+// Graphics devices, vGPU profiles and PCI passthrough of a host through the
+// HostGraphicsManager and HostPciPassthruSystem of the vim25 SOAP API
+use super::maintenance::host_reference;
+use crate::id::HostId;
+use crate::vim25::{Client, Error, Fault, ManagedObjectReference};
+use serde::{Deserialize, Deserializer};
+
+// How virtual machines use a graphics device, shared for vGPUs and direct
+// for passthrough
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GraphicsType {
+    Basic,
+    Shared,
+    Direct,
+    SharedDirect,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Graphics device of a host with the virtual machines using it
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphicsDevice {
+    #[serde(default)]
+    pub device_name: String,
+    #[serde(default)]
+    pub vendor_name: String,
+    // PCI address like "0000:3b:00.0"
+    #[serde(default)]
+    pub pci_id: String,
+    pub graphics_type: GraphicsType,
+    #[serde(default, rename = "memorySizeInKB")]
+    pub memory_size_kb: i64,
+    #[serde(default, rename = "vm")]
+    pub vms: Vec<ManagedObjectReference>,
+}
+
+// PCI device of a host which can be passed through, the ids are those of
+// the PCI configuration space, e.g. vendor 0x10de for NVIDIA
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassthroughDevice {
+    pub id: String,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub vendor_name: String,
+    pub device_name: String,
+    pub capable: bool,
+    pub enabled: bool,
+    // Enabling or disabling passthrough takes effect with the next reboot
+    pub active: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfGraphicsInfo {
+    #[serde(default, rename = "HostGraphicsInfo")]
+    devices: Vec<GraphicsDevice>,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfString {
+    #[serde(default)]
+    string: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PciDevice {
+    id: String,
+    #[serde(deserialize_with = "short_as_u16")]
+    vendor_id: u16,
+    #[serde(deserialize_with = "short_as_u16")]
+    device_id: u16,
+    vendor_name: String,
+    device_name: String,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfPciDevice {
+    #[serde(default, rename = "HostPciDevice")]
+    devices: Vec<PciDevice>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PciPassthruInfo {
+    id: String,
+    passthru_enabled: bool,
+    passthru_capable: bool,
+    passthru_active: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfPciPassthruInfo {
+    #[serde(default, rename = "HostPciPassthruInfo")]
+    devices: Vec<PciPassthruInfo>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PciPassthruConfig<'a> {
+    id: &'a str,
+    passthru_enabled: bool,
+}
+
+#[derive(Serialize)]
+struct UpdatePassthruConfigRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    config: Vec<PciPassthruConfig<'a>>,
+}
+
+// PCI ids are xsd:short in the SOAP API, ids above 0x7fff are negative
+fn short_as_u16<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u16, D::Error> {
+    Ok(i16::deserialize(deserializer)? as u16)
+}
+
+pub async fn graphics_devices(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<Vec<GraphicsDevice>, Error> {
+    let graphics_manager = client
+        .retrieve_property(&host_reference(host), "configManager.graphicsManager")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostGraphicsManager")))?;
+    let info: Option<ArrayOfGraphicsInfo> = client
+        .retrieve_property(&graphics_manager, "graphicsInfo")
+        .await?;
+    Ok(info.unwrap_or_default().devices)
+}
+
+// vGPU profiles the host offers, e.g. "grid_t4-8q"
+pub async fn vgpu_profiles(client: &Client<'_>, host: &HostId) -> Result<Vec<String>, Error> {
+    let profiles: Option<ArrayOfString> = client
+        .retrieve_property(&host_reference(host), "config.sharedPassthruGpuTypes")
+        .await?;
+    Ok(profiles.unwrap_or_default().string)
+}
+
+// PCI devices of the host which support passthrough
+pub async fn passthrough_devices(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<Vec<PassthroughDevice>, Error> {
+    let passthru_system = passthru_system(client, host).await?;
+    let passthru: Option<ArrayOfPciPassthruInfo> = client
+        .retrieve_property(&passthru_system, "pciPassthruInfo")
+        .await?;
+    let devices: Option<ArrayOfPciDevice> = client
+        .retrieve_property(&host_reference(host), "hardware.pciDevice")
+        .await?;
+    Ok(join_devices(
+        devices.unwrap_or_default().devices,
+        passthru.unwrap_or_default().devices,
+    ))
+}
+
+// Enable or disable passthrough of PCI devices by their ids, the host has
+// to be rebooted afterwards
+pub async fn set_passthrough(
+    client: &Client<'_>,
+    host: &HostId,
+    devices: &[&str],
+    enabled: bool,
+) -> Result<(), Error> {
+    let passthru_system = passthru_system(client, host).await?;
+    let request = UpdatePassthruConfigRequest {
+        this: &passthru_system,
+        config: devices
+            .iter()
+            .map(|id| PciPassthruConfig {
+                id,
+                passthru_enabled: enabled,
+            })
+            .collect(),
+    };
+    client.call_empty("UpdatePassthruConfig", &request).await
+}
+
+fn join_devices(devices: Vec<PciDevice>, passthru: Vec<PciPassthruInfo>) -> Vec<PassthroughDevice> {
+    passthru
+        .into_iter()
+        .filter(|info| info.passthru_capable)
+        .filter_map(|info| {
+            let device = devices.iter().find(|device| device.id == info.id)?;
+            Some(PassthroughDevice {
+                id: info.id,
+                vendor_id: device.vendor_id,
+                device_id: device.device_id,
+                vendor_name: device.vendor_name.clone(),
+                device_name: device.device_name.clone(),
+                capable: info.passthru_capable,
+                enabled: info.passthru_enabled,
+                active: info.passthru_active,
+            })
+        })
+        .collect()
+}
+
+async fn passthru_system(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<ManagedObjectReference, Error> {
+    client
+        .retrieve_property(&host_reference(host), "configManager.pciPassthruSystem")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostPciPassthruSystem")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        join_devices, ArrayOfGraphicsInfo, ArrayOfPciDevice, ArrayOfPciPassthruInfo, GraphicsType,
+    };
+
+    #[test]
+    fn deserialize_graphics_devices() {
+        let xml = r#"<val xsi:type="ArrayOfHostGraphicsInfo"><HostGraphicsInfo xsi:type="HostGraphicsInfo">
+            <deviceName>NVIDIA Tesla T4</deviceName><vendorName>NVIDIA Corporation</vendorName>
+            <pciId>0000:3b:00.0</pciId><graphicsType>shared</graphicsType><memorySizeInKB>16777216</memorySizeInKB>
+            <vm type="VirtualMachine">vm-1021</vm></HostGraphicsInfo></val>"#;
+        let info: ArrayOfGraphicsInfo = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(info.devices[0].graphics_type, GraphicsType::Shared);
+        assert_eq!(info.devices[0].vms[0].value, "vm-1021");
+    }
+
+    #[test]
+    fn join_passthrough_devices() {
+        let xml = r#"<val xsi:type="ArrayOfHostPciDevice"><HostPciDevice xsi:type="HostPciDevice">
+            <id>0000:3b:00.0</id><classId>768</classId><bus>59</bus><slot>0</slot><function>0</function>
+            <vendorId>4318</vendorId><subVendorId>4318</subVendorId><vendorName>NVIDIA Corporation</vendorName>
+            <deviceId>7864</deviceId><subDeviceId>4770</subDeviceId><parentBridge>0000:3a:00.0</parentBridge>
+            <deviceName>TU104GL [Tesla T4]</deviceName></HostPciDevice>
+            <HostPciDevice xsi:type="HostPciDevice"><id>0000:00:1f.0</id><vendorId>-32634</vendorId>
+            <vendorName>Intel Corporation</vendorName><deviceId>-24320</deviceId><deviceName>LPC Controller</deviceName>
+            </HostPciDevice></val>"#;
+        let devices: ArrayOfPciDevice = quick_xml::de::from_str(xml).unwrap();
+        let xml = r#"<val xsi:type="ArrayOfHostPciPassthruInfo"><HostPciPassthruInfo xsi:type="HostPciPassthruInfo">
+            <id>0000:3b:00.0</id><dependentDevice>0000:3b:00.0</dependentDevice><passthruEnabled>true</passthruEnabled>
+            <passthruCapable>true</passthruCapable><passthruActive>false</passthruActive></HostPciPassthruInfo>
+            <HostPciPassthruInfo xsi:type="HostPciPassthruInfo"><id>0000:00:1f.0</id><dependentDevice>0000:00:1f.0</dependentDevice>
+            <passthruEnabled>false</passthruEnabled><passthruCapable>false</passthruCapable><passthruActive>false</passthruActive>
+            </HostPciPassthruInfo></val>"#;
+        let passthru: ArrayOfPciPassthruInfo = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(devices.devices[1].vendor_id, 0x8086);
+        let joined = join_devices(devices.devices, passthru.devices);
+        assert_eq!(joined.len(), 1);
+        assert_eq!((joined[0].vendor_id, joined[0].device_id), (0x10de, 0x1eb8));
+        assert!(joined[0].enabled && !joined[0].active);
+    }
+}
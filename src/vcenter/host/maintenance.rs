@@ -0,0 +1,137 @@
+// Maintenance mode through the vim25 SOAP API, the REST API has no
+// maintenance mode service. Host identifiers of the REST API are the values
+// of their managed object references, e.g. "host-10".
+use crate::id::HostId;
+use crate::vim25::{Client, Error, ManagedObjectReference};
+use serde::de::IgnoredAny;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// Handling of the vSAN data of a host entering maintenance mode
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VsanObjectAction {
+    NoAction,
+    EnsureObjectAccessibility,
+    EvacuateAllData,
+}
+
+// Options to enter maintenance mode. Powered on virtual machines are only
+// evacuated by DRS in fully automated clusters, otherwise the host waits
+// until they are migrated or powered off. The timeout cancels the task,
+// without timeout it waits indefinitely.
+#[derive(Debug, Default, Clone)]
+pub struct EnterSpec {
+    pub timeout: Option<Duration>,
+    pub evacuate_powered_off_vms: bool,
+    pub vsan_object_action: Option<VsanObjectAction>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VsanHostDecommissionMode {
+    object_action: VsanObjectAction,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HostMaintenanceSpec {
+    vsan_mode: VsanHostDecommissionMode,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EnterRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    timeout: u64,
+    evacuate_powered_off_vms: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maintenance_spec: Option<HostMaintenanceSpec>,
+}
+
+#[derive(Serialize)]
+struct ExitRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    timeout: u64,
+}
+
+pub async fn in_maintenance_mode(client: &Client<'_>, host: &HostId) -> Result<bool, Error> {
+    let in_maintenance_mode = client
+        .retrieve_property(&host_reference(host), "runtime.inMaintenanceMode")
+        .await?;
+    Ok(in_maintenance_mode.unwrap_or(false))
+}
+
+// Put the host into maintenance mode and wait until it is drained
+pub async fn enter(client: &Client<'_>, host: &HostId, spec: &EnterSpec) -> Result<(), Error> {
+    let host = host_reference(host);
+    let request = enter_request(&host, spec);
+    let task = client.call("EnterMaintenanceMode_Task", &request).await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+// Take the host out of maintenance mode and wait until it is done
+pub async fn exit(
+    client: &Client<'_>,
+    host: &HostId,
+    timeout: Option<Duration>,
+) -> Result<(), Error> {
+    let host = host_reference(host);
+    let request = ExitRequest {
+        this: &host,
+        timeout: timeout.map_or(0, |timeout| timeout.as_secs()),
+    };
+    let task = client.call("ExitMaintenanceMode_Task", &request).await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+// A timeout of 0 seconds means no timeout
+fn enter_request<'a>(host: &'a ManagedObjectReference, spec: &EnterSpec) -> EnterRequest<'a> {
+    EnterRequest {
+        this: host,
+        timeout: spec.timeout.map_or(0, |timeout| timeout.as_secs()),
+        evacuate_powered_off_vms: spec.evacuate_powered_off_vms,
+        maintenance_spec: spec
+            .vsan_object_action
+            .map(|object_action| HostMaintenanceSpec {
+                vsan_mode: VsanHostDecommissionMode { object_action },
+            }),
+    }
+}
+
+pub(super) fn host_reference(host: &HostId) -> ManagedObjectReference {
+    ManagedObjectReference::new(host.object_type(), host.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{enter_request, host_reference, EnterSpec, VsanObjectAction};
+    use crate::id::HostId;
+    use crate::vim25::envelope;
+    use std::time::Duration;
+
+    #[test]
+    fn enter_envelope() {
+        let host = host_reference(&HostId::new("host-10"));
+        let spec = EnterSpec {
+            timeout: Some(Duration::from_secs(3600)),
+            evacuate_powered_off_vms: true,
+            vsan_object_action: Some(VsanObjectAction::EnsureObjectAccessibility),
+        };
+        let envelope = envelope("EnterMaintenanceMode_Task", &enter_request(&host, &spec)).unwrap();
+        assert!(envelope.contains(
+            "<_this type=\"HostSystem\">host-10</_this><timeout>3600</timeout>\
+             <evacuatePoweredOffVms>true</evacuatePoweredOffVms><maintenanceSpec><vsanMode>\
+             <objectAction>ensureObjectAccessibility</objectAction></vsanMode></maintenanceSpec>"
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+// Graphics devices, vGPU profiles and PCI passthrough of a host
+#[cfg(feature = "vim25")]
+pub mod graphics;
+// Maintenance mode of a host
+#[cfg(feature = "vim25")]
+pub mod maintenance;
+// Standard switches and VMkernel adapters of a host
+#[cfg(feature = "vim25")]
+pub mod networking;
+// Services, firewall rulesets and lockdown mode of a host
+#[cfg(feature = "vim25")]
+pub mod security;
+// Storage adapters, devices and datastores of a host
+#[cfg(feature = "vim25")]
+pub mod storage;
+mod thumbprint;
+
+pub use thumbprint::fetch_thumbprint;
+
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query, REDACTED};
+use crate::id::{ClusterId, DatacenterId, FolderId, HostId};
+use crate::paging::PagedStream;
+use reqwest::Method;
+use std::fmt;
+
+// Connection state of a host to vCenter
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ConnectionState {
+    #[display(fmt = "CONNECTED")]
+    Connected,
+    #[display(fmt = "DISCONNECTED")]
+    Disconnected,
+    #[display(fmt = "NOT_RESPONDING")]
+    NotResponding,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    #[display(fmt = "UNKNOWN")]
+    Unknown,
+}
+
+// Power state of a host
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PowerState {
+    #[display(fmt = "POWERED_ON")]
+    PoweredOn,
+    #[display(fmt = "POWERED_OFF")]
+    PoweredOff,
+    #[display(fmt = "STANDBY")]
+    Standby,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    #[display(fmt = "UNKNOWN")]
+    Unknown,
+}
+
+// Summary of a host as returned by the list call, the power state is only
+// known for connected hosts
+#[derive(Deserialize, Debug, Clone)]
+pub struct HostSummary {
+    pub host: HostId,
+    pub name: String,
+    pub connection_state: ConnectionState,
+    pub power_state: Option<PowerState>,
+}
+
+// Filter for the list call, empty fields match every host
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub hosts: Vec<HostId>,
+    pub names: Vec<String>,
+    pub folders: Vec<FolderId>,
+    pub datacenters: Vec<DatacenterId>,
+    pub standalone: Option<bool>,
+    pub clusters: Vec<ClusterId>,
+    pub connection_states: Vec<ConnectionState>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "hosts", &self.hosts);
+        push_query(&mut query, "names", &self.names);
+        push_query(&mut query, "folders", &self.folders);
+        push_query(&mut query, "datacenters", &self.datacenters);
+        if let Some(standalone) = self.standalone {
+            query.push(("standalone", standalone.to_string()));
+        }
+        push_query(&mut query, "clusters", &self.clusters);
+        push_query(&mut query, "connection_states", &self.connection_states);
+        query
+    }
+}
+
+// List the hosts matching the filter (at most 2500 are returned)
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<HostSummary>, Error> {
+    let request = session.list("/vcenter/host", &filter.query());
+    session.send(request).await
+}
+
+// Stream the hosts matching the filter, they are fetched with a single list call
+pub fn list_stream<'a>(session: &'a Session, filter: &FilterSpec) -> PagedStream<'a, HostSummary> {
+    let filter = filter.clone();
+    PagedStream::once(async move { list(session, &filter).await })
+}
+
+// How vCenter verifies the certificate of a host it adds
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ThumbprintVerification {
+    // Any certificate is accepted
+    None,
+    // The certificate must match the thumbprint of the spec
+    Thumbprint,
+}
+
+// Specification of a host to add. Hosts are added as standalone hosts to a
+// host folder, by default the one of the only datacenter.
+#[derive(Serialize, Clone)]
+pub struct CreateSpec {
+    pub hostname: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    pub user_name: String,
+    pub password: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<FolderId>,
+    pub thumbprint_verification: ThumbprintVerification,
+    // SHA-1 thumbprint like "8A:0F:3C:...", see fetch_thumbprint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbprint: Option<String>,
+    // Add the host even if another vCenter manages it
+    pub force_add: bool,
+}
+
+impl fmt::Debug for CreateSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateSpec")
+            .field("hostname", &self.hostname)
+            .field("port", &self.port)
+            .field("user_name", &self.user_name)
+            .field("password", &REDACTED)
+            .field("folder", &self.folder)
+            .field("thumbprint_verification", &self.thumbprint_verification)
+            .field("thumbprint", &self.thumbprint)
+            .field("force_add", &self.force_add)
+            .finish()
+    }
+}
+
+impl CreateSpec {
+    // Spec which accepts the certificate of the host with the given thumbprint
+    pub fn new(
+        hostname: impl Into<String>,
+        user_name: impl Into<String>,
+        password: impl Into<String>,
+        thumbprint: impl Into<String>,
+    ) -> Self {
+        CreateSpec {
+            hostname: hostname.into(),
+            port: None,
+            user_name: user_name.into(),
+            password: password.into(),
+            folder: None,
+            thumbprint_verification: ThumbprintVerification::Thumbprint,
+            thumbprint: Some(thumbprint.into()),
+            force_add: false,
+        }
+    }
+}
+
+// Add a host to vCenter and return its identifier, the host is connected
+pub async fn add(session: &Session, spec: &CreateSpec) -> Result<HostId, Error> {
+    let request = session.request(Method::POST, "/vcenter/host");
+    session.send(session.body(request, "spec", spec)).await
+}
+
+// Fetch the thumbprint of the host and add it if confirm accepts the
+// thumbprint, fails with Error::Canceled otherwise. The thumbprint of the
+// spec is replaced.
+pub async fn add_confirmed<F>(
+    session: &Session,
+    spec: &CreateSpec,
+    confirm: F,
+) -> Result<HostId, Error>
+where
+    F: FnOnce(&str) -> bool,
+{
+    let thumbprint = fetch_thumbprint(&spec.hostname, spec.port.unwrap_or(443)).await?;
+    if !confirm(&thumbprint) {
+        return Err(Error::Canceled);
+    }
+    let spec = CreateSpec {
+        thumbprint_verification: ThumbprintVerification::Thumbprint,
+        thumbprint: Some(thumbprint),
+        ..spec.clone()
+    };
+    add(session, &spec).await
+}
+
+// Remove a disconnected host from vCenter
+pub async fn delete(session: &Session, host: &HostId) -> Result<(), Error> {
+    let request = session.request(Method::DELETE, &format!("/vcenter/host/{}", host));
+    session.send_empty(request).await
+}
+
+// Connect a host to vCenter, fails with ErrorType::AlreadyInDesiredState if
+// the host is already connected
+pub async fn connect(session: &Session, host: &HostId) -> Result<(), Error> {
+    let request = session.path_action(&format!("/vcenter/host/{}", host), "connect");
+    session.send_empty(request).await
+}
+
+// Disconnect a host from vCenter, fails with ErrorType::AlreadyInDesiredState
+// if the host is already disconnected
+pub async fn disconnect(session: &Session, host: &HostId) -> Result<(), Error> {
+    let request = session.path_action(&format!("/vcenter/host/{}", host), "disconnect");
+    session.send_empty(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectionState, CreateSpec, FilterSpec};
+
+    #[test]
+    fn filter_query() {
+        let filter = FilterSpec {
+            clusters: vec!["domain-c7".into()],
+            standalone: Some(false),
+            connection_states: vec![ConnectionState::NotResponding],
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.query(),
+            vec![
+                ("standalone", "false".to_string()),
+                ("clusters", "domain-c7".to_string()),
+                ("connection_states", "NOT_RESPONDING".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn serialize_create_spec() {
+        let mut spec = CreateSpec::new("esx01.example.com", "root", "secret", "8A:0F:3C:9E");
+        spec.folder = Some("group-h4".into());
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "hostname": "esx01.example.com",
+                "user_name": "root",
+                "password": "secret",
+                "folder": "group-h4",
+                "thumbprint_verification": "THUMBPRINT",
+                "thumbprint": "8A:0F:3C:9E",
+                "force_add": false,
+            })
+        );
+    }
+}
@@ -0,0 +1,534 @@
+// Standard switches, their portgroups and the VMkernel adapters of a host
+// through the HostNetworkSystem of the vim25 SOAP API
+use super::maintenance::host_reference;
+use crate::id::HostId;
+use crate::vim25::{Client, Error, Fault, ManagedObjectReference};
+
+// Standard switch with the physical NICs bridged to it, e.g. "vmnic0"
+#[derive(Debug, Clone)]
+pub struct VirtualSwitch {
+    pub name: String,
+    pub num_ports: i32,
+    pub num_ports_available: i32,
+    pub mtu: Option<i32>,
+    pub nics: Vec<String>,
+}
+
+// Portgroup of a standard switch, VLAN 4095 passes all VLANs to the guests
+#[derive(Debug, Clone)]
+pub struct Portgroup {
+    pub name: String,
+    pub vlan_id: i32,
+    pub vswitch_name: String,
+}
+
+// IPv4 configuration of a VMkernel adapter, the address and mask are unset
+// with DHCP
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IpConfig {
+    #[serde(default)]
+    pub dhcp: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet_mask: Option<String>,
+}
+
+impl IpConfig {
+    pub fn dhcp() -> Self {
+        IpConfig {
+            dhcp: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn fixed(ip_address: impl Into<String>, subnet_mask: impl Into<String>) -> Self {
+        IpConfig {
+            dhcp: false,
+            ip_address: Some(ip_address.into()),
+            subnet_mask: Some(subnet_mask.into()),
+        }
+    }
+}
+
+// VMkernel adapter like "vmk0" on a portgroup of a standard switch, the
+// portgroup is empty for adapters on distributed switches
+#[derive(Debug, Clone)]
+pub struct VmkernelAdapter {
+    pub device: String,
+    pub portgroup: String,
+    pub ip: IpConfig,
+    pub mac: Option<String>,
+    pub mtu: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetworkConfig {
+    pub vswitches: Vec<VirtualSwitch>,
+    pub portgroups: Vec<Portgroup>,
+    pub vmkernel_adapters: Vec<VmkernelAdapter>,
+}
+
+// Standard switch to add, the NICs are bridged as a team
+#[derive(Debug, Clone)]
+pub struct VirtualSwitchSpec {
+    pub num_ports: i32,
+    pub nics: Vec<String>,
+    pub mtu: Option<i32>,
+}
+
+impl Default for VirtualSwitchSpec {
+    fn default() -> Self {
+        VirtualSwitchSpec {
+            num_ports: 128,
+            nics: Vec::new(),
+            mtu: None,
+        }
+    }
+}
+
+// Traffic a VMkernel adapter is used for
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VmkernelService {
+    Management,
+    Vmotion,
+    FaultToleranceLogging,
+    #[serde(rename = "vSphereReplication")]
+    VsphereReplication,
+    #[serde(rename = "vSphereProvisioning")]
+    VsphereProvisioning,
+    Vsan,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct HostNetworkInfo {
+    #[serde(default)]
+    vswitch: Vec<HostVirtualSwitch>,
+    #[serde(default)]
+    portgroup: Vec<HostPortGroup>,
+    #[serde(default)]
+    vnic: Vec<HostVirtualNic>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HostVirtualSwitch {
+    name: String,
+    num_ports: i32,
+    num_ports_available: i32,
+    mtu: Option<i32>,
+    spec: HostVirtualSwitchSpec,
+}
+
+#[derive(Deserialize)]
+struct HostVirtualSwitchSpec {
+    bridge: Option<BondBridge>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BondBridge {
+    #[serde(default)]
+    nic_device: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct HostPortGroup {
+    spec: HostPortGroupSpec,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HostPortGroupSpec {
+    name: String,
+    vlan_id: i32,
+    vswitch_name: String,
+}
+
+#[derive(Deserialize)]
+struct HostVirtualNic {
+    device: String,
+    #[serde(default)]
+    portgroup: String,
+    spec: HostVirtualNicSpec,
+}
+
+#[derive(Deserialize)]
+struct HostVirtualNicSpec {
+    ip: Option<IpConfig>,
+    mac: Option<String>,
+    mtu: Option<i32>,
+}
+
+impl From<HostNetworkInfo> for NetworkConfig {
+    fn from(info: HostNetworkInfo) -> Self {
+        NetworkConfig {
+            vswitches: info
+                .vswitch
+                .into_iter()
+                .map(|vswitch| VirtualSwitch {
+                    name: vswitch.name,
+                    num_ports: vswitch.num_ports,
+                    num_ports_available: vswitch.num_ports_available,
+                    mtu: vswitch.mtu,
+                    nics: vswitch
+                        .spec
+                        .bridge
+                        .map(|bridge| bridge.nic_device)
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            portgroups: info
+                .portgroup
+                .into_iter()
+                .map(|portgroup| Portgroup {
+                    name: portgroup.spec.name,
+                    vlan_id: portgroup.spec.vlan_id,
+                    vswitch_name: portgroup.spec.vswitch_name,
+                })
+                .collect(),
+            vmkernel_adapters: info
+                .vnic
+                .into_iter()
+                .map(|vnic| VmkernelAdapter {
+                    device: vnic.device,
+                    portgroup: vnic.portgroup,
+                    ip: vnic.spec.ip.unwrap_or_default(),
+                    mac: vnic.spec.mac,
+                    mtu: vnic.spec.mtu,
+                })
+                .collect(),
+        }
+    }
+}
+
+// Wire formats of the specs, the SOAP API requires the fields in this order
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BridgeSpec<'a> {
+    #[serde(rename = "@xsi:type")]
+    bridge_type: &'static str,
+    nic_device: &'a [String],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SwitchSpec<'a> {
+    num_ports: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bridge: Option<BridgeSpec<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mtu: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct NetworkPolicy {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PortgroupSpec<'a> {
+    name: &'a str,
+    vlan_id: i32,
+    vswitch_name: &'a str,
+    // Unset policies are inherited from the switch
+    policy: NetworkPolicy,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VirtualNicSpec<'a> {
+    ip: &'a IpConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mtu: Option<i32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddVirtualSwitchRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    vswitch_name: &'a str,
+    spec: SwitchSpec<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoveVirtualSwitchRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    vswitch_name: &'a str,
+}
+
+#[derive(Serialize)]
+struct AddPortGroupRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    portgrp: PortgroupSpec<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemovePortGroupRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    pg_name: &'a str,
+}
+
+#[derive(Serialize)]
+struct AddVirtualNicRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    portgroup: &'a str,
+    nic: VirtualNicSpec<'a>,
+}
+
+#[derive(Serialize)]
+struct UpdateVirtualNicRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    device: &'a str,
+    nic: VirtualNicSpec<'a>,
+}
+
+#[derive(Serialize)]
+struct DeviceRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    device: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NicTypeRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    nic_type: VmkernelService,
+    device: &'a str,
+}
+
+pub async fn network_config(client: &Client<'_>, host: &HostId) -> Result<NetworkConfig, Error> {
+    let network_system = network_system(client, host).await?;
+    let info: Option<HostNetworkInfo> = client
+        .retrieve_property(&network_system, "networkInfo")
+        .await?;
+    Ok(info.unwrap_or_default().into())
+}
+
+pub async fn add_vswitch(
+    client: &Client<'_>,
+    host: &HostId,
+    name: &str,
+    spec: &VirtualSwitchSpec,
+) -> Result<(), Error> {
+    let network_system = network_system(client, host).await?;
+    let bridge = if spec.nics.is_empty() {
+        None
+    } else {
+        Some(BridgeSpec {
+            bridge_type: "HostVirtualSwitchBondBridge",
+            nic_device: &spec.nics,
+        })
+    };
+    let request = AddVirtualSwitchRequest {
+        this: &network_system,
+        vswitch_name: name,
+        spec: SwitchSpec {
+            num_ports: spec.num_ports,
+            bridge,
+            mtu: spec.mtu,
+        },
+    };
+    client.call_empty("AddVirtualSwitch", &request).await
+}
+
+// Remove a standard switch with its portgroups, it fails while VMkernel
+// adapters or virtual machines are connected
+pub async fn remove_vswitch(client: &Client<'_>, host: &HostId, name: &str) -> Result<(), Error> {
+    let network_system = network_system(client, host).await?;
+    let request = RemoveVirtualSwitchRequest {
+        this: &network_system,
+        vswitch_name: name,
+    };
+    client.call_empty("RemoveVirtualSwitch", &request).await
+}
+
+pub async fn add_portgroup(
+    client: &Client<'_>,
+    host: &HostId,
+    name: &str,
+    vswitch_name: &str,
+    vlan_id: i32,
+) -> Result<(), Error> {
+    let network_system = network_system(client, host).await?;
+    let request = AddPortGroupRequest {
+        this: &network_system,
+        portgrp: PortgroupSpec {
+            name,
+            vlan_id,
+            vswitch_name,
+            policy: NetworkPolicy {},
+        },
+    };
+    client.call_empty("AddPortGroup", &request).await
+}
+
+pub async fn remove_portgroup(client: &Client<'_>, host: &HostId, name: &str) -> Result<(), Error> {
+    let network_system = network_system(client, host).await?;
+    let request = RemovePortGroupRequest {
+        this: &network_system,
+        pg_name: name,
+    };
+    client.call_empty("RemovePortGroup", &request).await
+}
+
+// Add a VMkernel adapter to the portgroup of a standard switch, returns its
+// device name like "vmk1"
+pub async fn add_vmkernel_adapter(
+    client: &Client<'_>,
+    host: &HostId,
+    portgroup: &str,
+    ip: &IpConfig,
+    mtu: Option<i32>,
+) -> Result<String, Error> {
+    let network_system = network_system(client, host).await?;
+    let request = AddVirtualNicRequest {
+        this: &network_system,
+        portgroup,
+        nic: VirtualNicSpec { ip, mtu },
+    };
+    client.call("AddVirtualNic", &request).await
+}
+
+pub async fn update_vmkernel_ip(
+    client: &Client<'_>,
+    host: &HostId,
+    device: &str,
+    ip: &IpConfig,
+) -> Result<(), Error> {
+    let network_system = network_system(client, host).await?;
+    let request = UpdateVirtualNicRequest {
+        this: &network_system,
+        device,
+        nic: VirtualNicSpec { ip, mtu: None },
+    };
+    client.call_empty("UpdateVirtualNic", &request).await
+}
+
+pub async fn remove_vmkernel_adapter(
+    client: &Client<'_>,
+    host: &HostId,
+    device: &str,
+) -> Result<(), Error> {
+    let network_system = network_system(client, host).await?;
+    let request = DeviceRequest {
+        this: &network_system,
+        device,
+    };
+    client.call_empty("RemoveVirtualNic", &request).await
+}
+
+// Use the VMkernel adapter for the traffic, e.g. vMotion
+pub async fn enable_service(
+    client: &Client<'_>,
+    host: &HostId,
+    device: &str,
+    service: VmkernelService,
+) -> Result<(), Error> {
+    let virtual_nic_manager = virtual_nic_manager(client, host).await?;
+    let request = NicTypeRequest {
+        this: &virtual_nic_manager,
+        nic_type: service,
+        device,
+    };
+    client.call_empty("SelectVnicForNicType", &request).await
+}
+
+pub async fn disable_service(
+    client: &Client<'_>,
+    host: &HostId,
+    device: &str,
+    service: VmkernelService,
+) -> Result<(), Error> {
+    let virtual_nic_manager = virtual_nic_manager(client, host).await?;
+    let request = NicTypeRequest {
+        this: &virtual_nic_manager,
+        nic_type: service,
+        device,
+    };
+    client.call_empty("DeselectVnicForNicType", &request).await
+}
+
+async fn network_system(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<ManagedObjectReference, Error> {
+    client
+        .retrieve_property(&host_reference(host), "configManager.networkSystem")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostNetworkSystem")))
+}
+
+async fn virtual_nic_manager(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<ManagedObjectReference, Error> {
+    client
+        .retrieve_property(&host_reference(host), "configManager.virtualNicManager")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostVirtualNicManager")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AddVirtualNicRequest, HostNetworkInfo, IpConfig, NetworkConfig, VirtualNicSpec};
+    use crate::vim25::{envelope, ManagedObjectReference};
+
+    #[test]
+    fn deserialize_network_info() {
+        let xml = r#"<val xsi:type="HostNetworkInfo">
+            <vswitch><name>vSwitch0</name><key>key-vim.host.VirtualSwitch-vSwitch0</key>
+            <numPorts>2560</numPorts><numPortsAvailable>2547</numPortsAvailable><mtu>1500</mtu>
+            <portgroup>key-vim.host.PortGroup-Management Network</portgroup>
+            <pnic>key-vim.host.PhysicalNic-vmnic0</pnic><spec><numPorts>128</numPorts>
+            <bridge xsi:type="HostVirtualSwitchBondBridge"><nicDevice>vmnic0</nicDevice>
+            <nicDevice>vmnic1</nicDevice></bridge><policy/><mtu>1500</mtu></spec></vswitch>
+            <portgroup><key>key-vim.host.PortGroup-Management Network</key>
+            <vswitch>key-vim.host.VirtualSwitch-vSwitch0</vswitch><computedPolicy/>
+            <spec><name>Management Network</name><vlanId>110</vlanId><vswitchName>vSwitch0</vswitchName>
+            <policy/></spec></portgroup>
+            <vnic><device>vmk0</device><key>key-vim.host.VirtualNic-vmk0</key>
+            <portgroup>Management Network</portgroup><spec><ip><dhcp>false</dhcp>
+            <ipAddress>10.0.110.21</ipAddress><subnetMask>255.255.255.0</subnetMask></ip>
+            <mac>00:50:56:6a:1c:02</mac><mtu>1500</mtu></spec></vnic></val>"#;
+        let info: HostNetworkInfo = quick_xml::de::from_str(xml).unwrap();
+        let config = NetworkConfig::from(info);
+        assert_eq!(config.vswitches[0].nics, vec!["vmnic0", "vmnic1"]);
+        assert_eq!(config.portgroups[0].vlan_id, 110);
+        assert_eq!(config.vmkernel_adapters[0].device, "vmk0");
+        assert_eq!(
+            config.vmkernel_adapters[0].ip,
+            IpConfig::fixed("10.0.110.21", "255.255.255.0")
+        );
+    }
+
+    #[test]
+    fn add_virtual_nic_envelope() {
+        let request = AddVirtualNicRequest {
+            this: &ManagedObjectReference::new("HostNetworkSystem", "networkSystem-10"),
+            portgroup: "vMotion",
+            nic: VirtualNicSpec {
+                ip: &IpConfig::fixed("10.0.120.21", "255.255.255.0"),
+                mtu: Some(9000),
+            },
+        };
+        let xml = envelope("AddVirtualNic", &request).unwrap();
+        assert!(xml.contains(
+            "<portgroup>vMotion</portgroup><nic><ip><dhcp>false</dhcp>\
+             <ipAddress>10.0.120.21</ipAddress><subnetMask>255.255.255.0</subnetMask></ip>\
+             <mtu>9000</mtu></nic>"
+        ));
+    }
+}
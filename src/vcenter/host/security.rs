@@ -0,0 +1,286 @@
+// Services, firewall rulesets and lockdown mode of a host through the
+// HostServiceSystem, HostFirewallSystem and HostAccessManager of the vim25
+// SOAP API
+use super::maintenance::host_reference;
+use crate::id::HostId;
+use crate::vim25::{Client, Error, Fault, ManagedObjectReference};
+
+// When a service is started, automatic starts it with its firewall ports
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ServicePolicy {
+    On,
+    Off,
+    Automatic,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Service of a host like "TSM-SSH" or "ntpd", the rulesets are the keys of
+// the firewall rulesets it uses
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Service {
+    pub key: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub running: bool,
+    pub policy: ServicePolicy,
+    #[serde(default, rename = "ruleset")]
+    pub rulesets: Vec<String>,
+}
+
+// Hosts allowed to connect, all of them if all_ip is set
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowedHosts {
+    #[serde(default)]
+    pub all_ip: bool,
+    #[serde(default)]
+    pub ip_address: Vec<String>,
+}
+
+// Firewall ruleset of a host like "sshServer" or "ntpClient"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Ruleset {
+    pub key: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub enabled: bool,
+    pub service: Option<String>,
+    pub allowed_hosts: Option<AllowedHosts>,
+}
+
+// Lockdown mode of a host, in normal mode the host is only managed through
+// vCenter and the DCUI, in strict mode also the DCUI is disabled
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockdownMode {
+    #[serde(rename = "lockdownDisabled")]
+    Disabled,
+    #[serde(rename = "lockdownNormal")]
+    Normal,
+    #[serde(rename = "lockdownStrict")]
+    Strict,
+}
+
+#[derive(Deserialize, Default)]
+struct HostServiceInfo {
+    #[serde(default)]
+    service: Vec<Service>,
+}
+
+#[derive(Deserialize, Default)]
+struct HostFirewallInfo {
+    #[serde(default)]
+    ruleset: Vec<Ruleset>,
+}
+
+#[derive(Serialize)]
+struct IdRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    id: &'a str,
+}
+
+#[derive(Serialize)]
+struct UpdateServicePolicyRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    id: &'a str,
+    policy: ServicePolicy,
+}
+
+#[derive(Serialize)]
+struct ChangeLockdownModeRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    mode: LockdownMode,
+}
+
+pub async fn services(client: &Client<'_>, host: &HostId) -> Result<Vec<Service>, Error> {
+    let service_system = service_system(client, host).await?;
+    let info: Option<HostServiceInfo> = client
+        .retrieve_property(&service_system, "serviceInfo")
+        .await?;
+    Ok(info.unwrap_or_default().service)
+}
+
+pub async fn start_service(client: &Client<'_>, host: &HostId, key: &str) -> Result<(), Error> {
+    service_call(client, host, "StartService", key).await
+}
+
+pub async fn stop_service(client: &Client<'_>, host: &HostId, key: &str) -> Result<(), Error> {
+    service_call(client, host, "StopService", key).await
+}
+
+pub async fn restart_service(client: &Client<'_>, host: &HostId, key: &str) -> Result<(), Error> {
+    service_call(client, host, "RestartService", key).await
+}
+
+// Change when the service is started, this doesn't start or stop it
+pub async fn set_service_policy(
+    client: &Client<'_>,
+    host: &HostId,
+    key: &str,
+    policy: ServicePolicy,
+) -> Result<(), Error> {
+    let service_system = service_system(client, host).await?;
+    let request = UpdateServicePolicyRequest {
+        this: &service_system,
+        id: key,
+        policy,
+    };
+    client.call_empty("UpdateServicePolicy", &request).await
+}
+
+pub async fn firewall_rulesets(client: &Client<'_>, host: &HostId) -> Result<Vec<Ruleset>, Error> {
+    let firewall_system = firewall_system(client, host).await?;
+    let info: Option<HostFirewallInfo> = client
+        .retrieve_property(&firewall_system, "firewallInfo")
+        .await?;
+    Ok(info.unwrap_or_default().ruleset)
+}
+
+pub async fn enable_ruleset(client: &Client<'_>, host: &HostId, key: &str) -> Result<(), Error> {
+    firewall_call(client, host, "EnableRuleset", key).await
+}
+
+pub async fn disable_ruleset(client: &Client<'_>, host: &HostId, key: &str) -> Result<(), Error> {
+    firewall_call(client, host, "DisableRuleset", key).await
+}
+
+pub async fn lockdown_mode(client: &Client<'_>, host: &HostId) -> Result<LockdownMode, Error> {
+    let access_manager = access_manager(client, host).await?;
+    let mode: Option<String> = client
+        .retrieve_property(&access_manager, "lockdownMode")
+        .await?;
+    match mode.as_deref() {
+        Some("lockdownNormal") => Ok(LockdownMode::Normal),
+        Some("lockdownStrict") => Ok(LockdownMode::Strict),
+        _ => Ok(LockdownMode::Disabled),
+    }
+}
+
+// Change the lockdown mode, fails if vCenter manages the host with a user
+// missing from the exception users
+pub async fn set_lockdown_mode(
+    client: &Client<'_>,
+    host: &HostId,
+    mode: LockdownMode,
+) -> Result<(), Error> {
+    let access_manager = access_manager(client, host).await?;
+    let request = ChangeLockdownModeRequest {
+        this: &access_manager,
+        mode,
+    };
+    client.call_empty("ChangeLockdownMode", &request).await
+}
+
+async fn service_call(
+    client: &Client<'_>,
+    host: &HostId,
+    method: &str,
+    key: &str,
+) -> Result<(), Error> {
+    let service_system = service_system(client, host).await?;
+    let request = IdRequest {
+        this: &service_system,
+        id: key,
+    };
+    client.call_empty(method, &request).await
+}
+
+async fn firewall_call(
+    client: &Client<'_>,
+    host: &HostId,
+    method: &str,
+    key: &str,
+) -> Result<(), Error> {
+    let firewall_system = firewall_system(client, host).await?;
+    let request = IdRequest {
+        this: &firewall_system,
+        id: key,
+    };
+    client.call_empty(method, &request).await
+}
+
+async fn service_system(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<ManagedObjectReference, Error> {
+    client
+        .retrieve_property(&host_reference(host), "configManager.serviceSystem")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostServiceSystem")))
+}
+
+async fn firewall_system(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<ManagedObjectReference, Error> {
+    client
+        .retrieve_property(&host_reference(host), "configManager.firewallSystem")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostFirewallSystem")))
+}
+
+async fn access_manager(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<ManagedObjectReference, Error> {
+    client
+        .retrieve_property(&host_reference(host), "configManager.hostAccessManager")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostAccessManager")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ChangeLockdownModeRequest, HostFirewallInfo, HostServiceInfo, LockdownMode, ServicePolicy,
+    };
+    use crate::vim25::{envelope, ManagedObjectReference};
+
+    #[test]
+    fn deserialize_services_and_rulesets() {
+        let xml = r#"<val xsi:type="HostServiceInfo"><service><key>TSM-SSH</key><label>SSH</label>
+            <required>false</required><uninstallable>false</uninstallable><running>true</running>
+            <ruleset>sshServer</ruleset><policy>off</policy></service></val>"#;
+        let info: HostServiceInfo = quick_xml::de::from_str(xml).unwrap();
+        assert!(info.service[0].running);
+        assert_eq!(info.service[0].policy, ServicePolicy::Off);
+        assert_eq!(info.service[0].rulesets, vec!["sshServer"]);
+
+        let xml = r#"<val xsi:type="HostFirewallInfo"><defaultPolicy><incomingBlocked>true</incomingBlocked>
+            </defaultPolicy><ruleset><key>ntpClient</key><label>NTP Client</label><required>false</required>
+            <rule><port>123</port><direction>outbound</direction><portType>dst</portType><protocol>udp</protocol></rule>
+            <service>ntpd</service><enabled>true</enabled><allowedHosts><allIp>false</allIp>
+            <ipAddress>10.0.0.5</ipAddress></allowedHosts></ruleset></val>"#;
+        let info: HostFirewallInfo = quick_xml::de::from_str(xml).unwrap();
+        let ruleset = &info.ruleset[0];
+        assert!(ruleset.enabled);
+        assert_eq!(ruleset.service.as_deref(), Some("ntpd"));
+        assert_eq!(
+            ruleset.allowed_hosts.as_ref().unwrap().ip_address,
+            vec!["10.0.0.5"]
+        );
+    }
+
+    #[test]
+    fn change_lockdown_mode_envelope() {
+        let request = ChangeLockdownModeRequest {
+            this: &ManagedObjectReference::new("HostAccessManager", "hostAccessManager-10"),
+            mode: LockdownMode::Normal,
+        };
+        let xml = envelope("ChangeLockdownMode", &request).unwrap();
+        assert!(xml.contains("<mode>lockdownNormal</mode>"));
+    }
+}
@@ -0,0 +1,435 @@
+// Storage adapters, devices and datastores of a host through the
+// HostStorageSystem and HostDatastoreSystem of the vim25 SOAP API
+use super::maintenance::host_reference;
+use crate::id::{DatastoreId, HostId};
+use crate::vim25::{parse_body, Client, Error, Fault, ManagedObjectReference, This};
+
+// Host bus adapter like "vmhba0", the type is the vim25 type of the adapter,
+// e.g. "HostFibreChannelHba" or "HostInternetScsiHba"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageAdapter {
+    #[serde(rename = "@type", default)]
+    pub adapter_type: String,
+    pub key: String,
+    #[serde(default)]
+    pub device: String,
+    #[serde(default)]
+    pub model: String,
+    pub driver: Option<String>,
+    #[serde(default)]
+    pub status: String,
+}
+
+// SCSI device visible to the host, only disks have a capacity
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDevice {
+    pub key: String,
+    #[serde(default)]
+    pub uuid: String,
+    #[serde(default)]
+    pub canonical_name: String,
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub lun_type: String,
+    pub vendor: Option<String>,
+    pub model: Option<String>,
+    // Path to pass to create_vmfs_datastore, e.g. "/vmfs/devices/disks/naa.6000c29"
+    pub device_path: Option<String>,
+    pub capacity: Option<DiskCapacity>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskCapacity {
+    #[serde(default)]
+    pub block_size: i32,
+    #[serde(default)]
+    pub block: i64,
+}
+
+impl DiskCapacity {
+    pub fn bytes(&self) -> i64 {
+        i64::from(self.block_size) * self.block
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NfsAccessMode {
+    ReadWrite,
+    ReadOnly,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NfsVersion {
+    #[serde(rename = "NFS")]
+    V3,
+    #[serde(rename = "NFS41")]
+    V41,
+}
+
+// NFS export to mount as datastore, e.g. "/exports/ds01" of "nas.example.com"
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NfsSpec {
+    pub remote_host: String,
+    pub remote_path: String,
+    #[serde(rename = "localPath")]
+    pub name: String,
+    pub access_mode: NfsAccessMode,
+    #[serde(rename = "type")]
+    pub version: NfsVersion,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct StorageDeviceInfo {
+    #[serde(default)]
+    host_bus_adapter: Vec<StorageAdapter>,
+    #[serde(default)]
+    scsi_lun: Vec<StorageDevice>,
+}
+
+// VMFS create spec as proposed by QueryVmfsDatastoreCreateOptions. It is
+// sent back unchanged except for the volume name, so the fields are in the
+// order the SOAP API requires.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VmfsDatastoreCreateSpec {
+    disk_uuid: String,
+    partition: DiskPartitionSpec,
+    vmfs: VmfsSpec,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiskPartitionSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partition_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chs: Option<DiskDimensionsChs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_sectors: Option<i64>,
+    #[serde(default)]
+    partition: Vec<DiskPartitionAttributes>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct DiskDimensionsChs {
+    cylinder: i64,
+    head: i32,
+    sector: i32,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiskPartitionAttributes {
+    partition: i32,
+    start_sector: i64,
+    end_sector: i64,
+    #[serde(rename = "type")]
+    partition_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    guid: Option<String>,
+    logical: bool,
+    attributes: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partition_alignment: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VmfsSpec {
+    extent: ScsiDiskPartition,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_size_mb: Option<i32>,
+    major_version: i32,
+    volume_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_size: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unmap_granularity: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unmap_priority: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScsiDiskPartition {
+    disk_name: String,
+    partition: i32,
+}
+
+#[derive(Deserialize)]
+struct VmfsDatastoreOption {
+    spec: VmfsDatastoreCreateSpec,
+}
+
+#[derive(Deserialize)]
+struct VmfsDatastoreOptions {
+    #[serde(default, rename = "returnval")]
+    options: Vec<VmfsDatastoreOption>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryVmfsOptionsRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    device_path: &'a str,
+}
+
+#[derive(Serialize)]
+struct CreateVmfsRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    spec: VmfsDatastoreCreateSpec,
+}
+
+#[derive(Serialize)]
+struct CreateNasRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    spec: &'a NfsSpec,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VmfsVolumeRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    vmfs_uuid: &'a str,
+}
+
+#[derive(Serialize)]
+struct RemoveDatastoreRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    datastore: &'a ManagedObjectReference,
+}
+
+// Rescan all host bus adapters for new devices, e.g. after LUNs were
+// presented to the host
+pub async fn rescan_all_hba(client: &Client<'_>, host: &HostId) -> Result<(), Error> {
+    let storage_system = storage_system(client, host).await?;
+    let request = This {
+        this: &storage_system,
+    };
+    client.call_empty("RescanAllHba", &request).await
+}
+
+// Rescan the devices for new VMFS volumes
+pub async fn rescan_vmfs(client: &Client<'_>, host: &HostId) -> Result<(), Error> {
+    let storage_system = storage_system(client, host).await?;
+    let request = This {
+        this: &storage_system,
+    };
+    client.call_empty("RescanVmfs", &request).await
+}
+
+pub async fn adapters(client: &Client<'_>, host: &HostId) -> Result<Vec<StorageAdapter>, Error> {
+    Ok(storage_device_info(client, host).await?.host_bus_adapter)
+}
+
+pub async fn devices(client: &Client<'_>, host: &HostId) -> Result<Vec<StorageDevice>, Error> {
+    Ok(storage_device_info(client, host).await?.scsi_lun)
+}
+
+// Create a VMFS datastore using the whole disk, the datastore is mounted on
+// the other hosts seeing the disk after a rescan
+pub async fn create_vmfs_datastore(
+    client: &Client<'_>,
+    host: &HostId,
+    device_path: &str,
+    name: &str,
+) -> Result<DatastoreId, Error> {
+    let datastore_system = datastore_system(client, host).await?;
+    let request = QueryVmfsOptionsRequest {
+        this: &datastore_system,
+        device_path,
+    };
+    let body = client
+        .invoke("QueryVmfsDatastoreCreateOptions", &request)
+        .await?;
+    let options: VmfsDatastoreOptions = parse_body(&body)?;
+    let mut spec = options
+        .options
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Fault(Fault::not_supported(device_path)))?
+        .spec;
+    spec.vmfs.volume_name = name.to_owned();
+    let request = CreateVmfsRequest {
+        this: &datastore_system,
+        spec,
+    };
+    let datastore: ManagedObjectReference = client.call("CreateVmfsDatastore", &request).await?;
+    Ok(DatastoreId::new(datastore.value))
+}
+
+// Mount the NFS export as datastore on the host
+pub async fn create_nfs_datastore(
+    client: &Client<'_>,
+    host: &HostId,
+    spec: &NfsSpec,
+) -> Result<DatastoreId, Error> {
+    let datastore_system = datastore_system(client, host).await?;
+    let request = CreateNasRequest {
+        this: &datastore_system,
+        spec,
+    };
+    let datastore: ManagedObjectReference = client.call("CreateNasDatastore", &request).await?;
+    Ok(DatastoreId::new(datastore.value))
+}
+
+// Unmount an NFS datastore from the host, the export is left unchanged
+pub async fn remove_nfs_datastore(
+    client: &Client<'_>,
+    host: &HostId,
+    datastore: &DatastoreId,
+) -> Result<(), Error> {
+    let datastore_system = datastore_system(client, host).await?;
+    let request = RemoveDatastoreRequest {
+        this: &datastore_system,
+        datastore: &datastore_reference(datastore),
+    };
+    client.call_empty("RemoveDatastore", &request).await
+}
+
+// Mount an unmounted VMFS datastore on the host
+pub async fn mount_vmfs(
+    client: &Client<'_>,
+    host: &HostId,
+    datastore: &DatastoreId,
+) -> Result<(), Error> {
+    vmfs_volume_call(client, host, datastore, "MountVmfsVolume").await
+}
+
+// Unmount a VMFS datastore from the host, it fails while virtual machines
+// on the datastore are registered on the host
+pub async fn unmount_vmfs(
+    client: &Client<'_>,
+    host: &HostId,
+    datastore: &DatastoreId,
+) -> Result<(), Error> {
+    vmfs_volume_call(client, host, datastore, "UnmountVmfsVolume").await
+}
+
+async fn vmfs_volume_call(
+    client: &Client<'_>,
+    host: &HostId,
+    datastore: &DatastoreId,
+    method: &str,
+) -> Result<(), Error> {
+    let vmfs_uuid: String = client
+        .retrieve_property(&datastore_reference(datastore), "info.vmfs.uuid")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("VMFS volume")))?;
+    let storage_system = storage_system(client, host).await?;
+    let request = VmfsVolumeRequest {
+        this: &storage_system,
+        vmfs_uuid: &vmfs_uuid,
+    };
+    client.call_empty(method, &request).await
+}
+
+async fn storage_device_info(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<StorageDeviceInfo, Error> {
+    let storage_system = storage_system(client, host).await?;
+    let info: Option<StorageDeviceInfo> = client
+        .retrieve_property(&storage_system, "storageDeviceInfo")
+        .await?;
+    Ok(info.unwrap_or_default())
+}
+
+async fn storage_system(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<ManagedObjectReference, Error> {
+    client
+        .retrieve_property(&host_reference(host), "configManager.storageSystem")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostStorageSystem")))
+}
+
+async fn datastore_system(
+    client: &Client<'_>,
+    host: &HostId,
+) -> Result<ManagedObjectReference, Error> {
+    client
+        .retrieve_property(&host_reference(host), "configManager.datastoreSystem")
+        .await?
+        .ok_or_else(|| Error::Fault(Fault::not_supported("HostDatastoreSystem")))
+}
+
+fn datastore_reference(datastore: &DatastoreId) -> ManagedObjectReference {
+    ManagedObjectReference::new(datastore.object_type(), datastore.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vim25::envelope;
+
+    #[test]
+    fn deserialize_storage_device_info() {
+        let xml = r#"<val xsi:type="HostStorageDeviceInfo">
+            <hostBusAdapter xsi:type="HostBlockHba"><key>key-vim.host.BlockHba-vmhba0</key>
+            <device>vmhba0</device><bus>3</bus><status>unknown</status><model>PVSCSI SCSI Controller</model>
+            <driver>pvscsi</driver><pci>0000:03:00.0</pci></hostBusAdapter>
+            <hostBusAdapter xsi:type="HostInternetScsiHba"><key>key-vim.host.InternetScsiHba-vmhba64</key>
+            <device>vmhba64</device><bus>0</bus><status>online</status><model>iSCSI Software Adapter</model>
+            <driver>iscsi_vmk</driver></hostBusAdapter>
+            <scsiLun xsi:type="HostScsiDisk"><deviceName>/vmfs/devices/disks/naa.6000c29</deviceName>
+            <deviceType>disk</deviceType><key>key-vim.host.ScsiDisk-0200</key><uuid>0200000000</uuid>
+            <canonicalName>naa.6000c29</canonicalName><displayName>Local VMware Disk</displayName>
+            <lunType>disk</lunType><vendor>VMware  </vendor><model>Virtual disk    </model>
+            <capacity><blockSize>512</blockSize><block>209715200</block></capacity>
+            <devicePath>/vmfs/devices/disks/naa.6000c29</devicePath></scsiLun></val>"#;
+        let info: StorageDeviceInfo = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(info.host_bus_adapter.len(), 2);
+        assert_eq!(info.host_bus_adapter[1].adapter_type, "HostInternetScsiHba");
+        assert_eq!(info.host_bus_adapter[1].device, "vmhba64");
+        let disk = &info.scsi_lun[0];
+        assert_eq!(disk.canonical_name, "naa.6000c29");
+        assert_eq!(
+            disk.capacity.map(|capacity| capacity.bytes()),
+            Some(107_374_182_400)
+        );
+    }
+
+    #[test]
+    fn create_vmfs_from_options() {
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><QueryVmfsDatastoreCreateOptionsResponse xmlns="urn:vim25">
+            <returnval><info xsi:type="VmfsDatastoreAllExtentOption"><layout/><partitionFormatChange>true</partitionFormatChange></info>
+            <spec xsi:type="VmfsDatastoreCreateSpec"><diskUuid>0200000000</diskUuid>
+            <partition><partitionFormat>gpt</partitionFormat><chs><cylinder>13054</cylinder><head>255</head><sector>63</sector></chs>
+            <totalSectors>209715200</totalSectors><partition><partition>1</partition><startSector>2048</startSector>
+            <endSector>209715166</endSector><type>vmfs</type><logical>false</logical><attributes>0</attributes></partition></partition>
+            <vmfs><extent><diskName>naa.6000c29</diskName><partition>1</partition></extent>
+            <majorVersion>6</majorVersion><volumeName></volumeName></vmfs></spec></returnval>
+            </QueryVmfsDatastoreCreateOptionsResponse></soapenv:Body></soapenv:Envelope>"#;
+        let options: VmfsDatastoreOptions = parse_body(body).unwrap();
+        let mut spec = options.options.into_iter().next().unwrap().spec;
+        spec.vmfs.volume_name = "ds-local-01".to_owned();
+        let request = CreateVmfsRequest {
+            this: &ManagedObjectReference::new("HostDatastoreSystem", "datastoreSystem-10"),
+            spec,
+        };
+        let xml = envelope("CreateVmfsDatastore", &request).unwrap();
+        assert!(xml.contains(
+            "<spec><diskUuid>0200000000</diskUuid><partition><partitionFormat>gpt</partitionFormat>\
+             <chs><cylinder>13054</cylinder><head>255</head><sector>63</sector></chs>\
+             <totalSectors>209715200</totalSectors><partition><partition>1</partition>\
+             <startSector>2048</startSector><endSector>209715166</endSector><type>vmfs</type>\
+             <logical>false</logical><attributes>0</attributes></partition></partition>\
+             <vmfs><extent><diskName>naa.6000c29</diskName><partition>1</partition></extent>\
+             <majorVersion>6</majorVersion><volumeName>ds-local-01</volumeName></vmfs></spec>"
+        ));
+    }
+}
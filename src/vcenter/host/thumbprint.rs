@@ -0,0 +1,84 @@
+// SHA-1 thumbprint of the certificate of a host, in the form vCenter expects
+// for the thumbprint verification when adding the host
+use crate::cis::Error;
+use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_rustls::rustls::{
+    Certificate, ClientConfig, RootCertStore, ServerCertVerified, ServerCertVerifier, TLSError,
+};
+use tokio_rustls::TlsConnector;
+use webpki::DNSNameRef;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// The certificate is only read to show its thumbprint, never trusted
+struct AcceptAnyCertificate;
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _roots: &RootCertStore,
+        _presented_certs: &[Certificate],
+        _dns_name: DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> Result<ServerCertVerified, TLSError> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+// Connect to the host and return the thumbprint of its certificate, e.g.
+// "8A:0F:3C:...". This has to be confirmed by the user, it proves nothing
+// about the identity of the host.
+pub async fn fetch_thumbprint(hostname: &str, port: u16) -> Result<String, Error> {
+    let mut config = ClientConfig::new();
+    config
+        .dangerous()
+        .set_certificate_verifier(Arc::new(AcceptAnyCertificate));
+    // SNI needs a DNS name, hosts added by their IP address get a placeholder
+    let dns_name = DNSNameRef::try_from_ascii_str(hostname)
+        .unwrap_or_else(|_| DNSNameRef::try_from_ascii_str("esxi.invalid").unwrap());
+    let handshake = async {
+        let stream = TcpStream::connect((hostname, port)).await?;
+        TlsConnector::from(Arc::new(config))
+            .connect(dns_name, stream)
+            .await
+    };
+    let stream = timeout(CONNECT_TIMEOUT, handshake)
+        .await
+        .map_err(|_| Error::Timeout)??;
+    let (_, session) = stream.get_ref();
+    let certificate = tokio_rustls::rustls::Session::get_peer_certificates(session)
+        .and_then(|certificates| certificates.into_iter().next())
+        .ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "no certificate presented")
+        })?;
+    Ok(format_thumbprint(
+        digest(&SHA1_FOR_LEGACY_USE_ONLY, &certificate.0).as_ref(),
+    ))
+}
+
+fn format_thumbprint(digest: &[u8]) -> String {
+    digest
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_thumbprint;
+    use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY};
+
+    #[test]
+    fn thumbprint_format() {
+        let thumbprint = format_thumbprint(digest(&SHA1_FOR_LEGACY_USE_ONLY, b"abc").as_ref());
+        assert_eq!(
+            thumbprint,
+            "A9:99:3E:36:47:06:81:6A:BA:3E:25:71:78:50:C2:6C:9C:D0:D8:9D"
+        );
+    }
+}
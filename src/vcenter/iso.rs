@@ -0,0 +1,37 @@
+use crate::cis::{Error, Session};
+use crate::id::{LibraryItemId, VmId};
+use reqwest::Method;
+
+const SERVICE: &str = "vcenter/iso/image";
+
+#[derive(Serialize)]
+struct MountParams<'a> {
+    vm: &'a VmId,
+}
+
+#[derive(Serialize)]
+struct UnmountParams<'a> {
+    cdrom: &'a str,
+}
+
+// Mount an ISO image library item on a new CD-ROM of the virtual machine and
+// return the identifier of the CD-ROM
+pub async fn mount(
+    session: &Session,
+    library_item: &LibraryItemId,
+    vm: &VmId,
+) -> Result<String, Error> {
+    let request =
+        session.service_action(Method::POST, SERVICE, Some(library_item.as_str()), "mount");
+    session
+        .send(session.json(request, &MountParams { vm }))
+        .await
+}
+
+// Unmount the ISO image and remove the CD-ROM created by mount
+pub async fn unmount(session: &Session, vm: &VmId, cdrom: &str) -> Result<(), Error> {
+    let request = session.service_action(Method::POST, SERVICE, Some(vm.as_str()), "unmount");
+    session
+        .send_empty(session.json(request, &UnmountParams { cdrom }))
+        .await
+}
@@ -0,0 +1,16 @@
+// Modules for the vCenter inventory and its managed objects
+pub mod certificate_management;
+pub mod cluster;
+pub mod datacenter;
+pub mod datastore;
+pub mod folder;
+pub mod guest;
+pub mod host;
+pub mod iso;
+pub mod namespace_management;
+pub mod network;
+pub mod resource_pool;
+pub mod services;
+pub mod storage;
+pub mod vm;
+pub mod vm_template;
@@ -0,0 +1,104 @@
+// vSphere with Tanzu: clusters enabled as Supervisor and their namespaces
+// (vSphere 7.0+)
+
+// Namespaces of Supervisor clusters
+pub mod namespaces;
+
+use crate::cis::{Error, LocalizableMessage, Session};
+use crate::id::ClusterId;
+use reqwest::Method;
+
+// State of the Supervisor configuration of a cluster
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ConfigStatus {
+    Configuring,
+    Removing,
+    Running,
+    Error,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Health of the Kubernetes control plane of a Supervisor cluster
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum KubernetesStatus {
+    Ready,
+    Warning,
+    Error,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Message {
+    pub severity: Severity,
+    pub details: Option<LocalizableMessage>,
+}
+
+// Summary of a cluster enabled as Supervisor
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClusterSummary {
+    pub cluster: ClusterId,
+    #[serde(default)]
+    pub cluster_name: String,
+    pub config_status: ConfigStatus,
+    pub kubernetes_status: KubernetesStatus,
+}
+
+// Details of a Supervisor cluster, the messages explain errors and warnings
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClusterInfo {
+    pub config_status: ConfigStatus,
+    pub kubernetes_status: KubernetesStatus,
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub kubernetes_status_messages: Vec<Message>,
+    pub api_server_cluster_endpoint: Option<String>,
+    #[serde(default)]
+    pub api_servers: Vec<String>,
+}
+
+// Clusters enabled as Supervisor, clusters without vSphere with Tanzu are
+// not listed
+pub async fn clusters(session: &Session) -> Result<Vec<ClusterSummary>, Error> {
+    let request = session.request(Method::GET, "/vcenter/namespace-management/clusters");
+    session.send(request).await
+}
+
+pub async fn cluster(session: &Session, cluster: &ClusterId) -> Result<ClusterInfo, Error> {
+    let request = session.request(
+        Method::GET,
+        &format!("/vcenter/namespace-management/clusters/{}", cluster),
+    );
+    session.send(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClusterSummary, ConfigStatus, KubernetesStatus};
+
+    #[test]
+    fn deserialize_cluster_summary() {
+        let summary: ClusterSummary = serde_json::from_str(
+            r#"{"cluster": "domain-c8", "cluster_name": "wcp-cluster",
+                "config_status": "RUNNING", "kubernetes_status": "WARNING"}"#,
+        )
+        .expect("deserialize");
+        assert_eq!(summary.cluster, "domain-c8");
+        assert_eq!(summary.config_status, ConfigStatus::Running);
+        assert_eq!(summary.kubernetes_status, KubernetesStatus::Warning);
+    }
+}
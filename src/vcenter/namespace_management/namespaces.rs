@@ -0,0 +1,222 @@
+use super::{ConfigStatus, Message};
+use crate::cis::capabilities::Feature;
+use crate::cis::{Error, Session};
+use crate::id::ClusterId;
+use reqwest::Method;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SubjectType {
+    User,
+    Group,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccessRole {
+    Owner,
+    Edit,
+    View,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Permission of an SSO user or group on a namespace, e.g. subject "devops"
+// of domain "vsphere.local"
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Access {
+    pub role: AccessRole,
+    pub subject_type: SubjectType,
+    #[serde(default)]
+    pub subject: String,
+    #[serde(default)]
+    pub domain: String,
+}
+
+// Storage policy available to a namespace, the limit in MiB caps the
+// storage requested with the policy
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageSpec {
+    #[serde(default)]
+    pub policy: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+}
+
+// Resource quota of a namespace, CPU in MHz and memory and storage in MiB.
+// Unset limits are unlimited.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ResourceQuota {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_request_limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pod_count: Option<i64>,
+}
+
+// Resources used by a namespace, CPU in MHz and memory and storage in MiB
+#[derive(Deserialize, Debug, Clone)]
+pub struct Stats {
+    #[serde(default)]
+    pub cpu_used: i64,
+    #[serde(default)]
+    pub memory_used: i64,
+    #[serde(default)]
+    pub storage_used: i64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NamespaceSummary {
+    #[serde(default)]
+    pub namespace: String,
+    pub cluster: ClusterId,
+    pub config_status: ConfigStatus,
+    #[serde(default)]
+    pub description: String,
+    pub stats: Option<Stats>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NamespaceInfo {
+    pub cluster: ClusterId,
+    pub config_status: ConfigStatus,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub access_list: Vec<Access>,
+    #[serde(default)]
+    pub storage_specs: Vec<StorageSpec>,
+    pub resource_spec: Option<ResourceQuota>,
+    pub stats: Option<Stats>,
+    #[serde(default)]
+    pub messages: Vec<Message>,
+}
+
+// Namespace to create, names are DNS labels unique per vCenter
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub cluster: ClusterId,
+    pub namespace: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub access_list: Vec<Access>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub storage_specs: Vec<StorageSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_spec: Option<ResourceQuota>,
+}
+
+impl CreateSpec {
+    pub fn new(cluster: ClusterId, namespace: impl Into<String>) -> Self {
+        CreateSpec {
+            cluster,
+            namespace: namespace.into(),
+            description: None,
+            access_list: Vec::new(),
+            storage_specs: Vec::new(),
+            resource_spec: None,
+        }
+    }
+}
+
+// Changes to a namespace, unset fields are left unchanged and set lists
+// replace the current ones
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_list: Option<Vec<Access>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_specs: Option<Vec<StorageSpec>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_spec: Option<ResourceQuota>,
+}
+
+const ENDPOINT: &str = "/vcenter/namespaces/instances";
+
+pub async fn list(session: &Session) -> Result<Vec<NamespaceSummary>, Error> {
+    session.require(Feature::Namespaces).await?;
+    let request = session.request(Method::GET, ENDPOINT);
+    session.send(request).await
+}
+
+pub async fn get(session: &Session, namespace: &str) -> Result<NamespaceInfo, Error> {
+    session.require(Feature::Namespaces).await?;
+    let request = session.request(Method::GET, &endpoint(namespace));
+    session.send(request).await
+}
+
+// Create a namespace, it is configured in the background while its
+// config_status is CONFIGURING
+pub async fn create(session: &Session, spec: &CreateSpec) -> Result<(), Error> {
+    session.require(Feature::Namespaces).await?;
+    let request = session.request(Method::POST, ENDPOINT);
+    session
+        .send_empty(session.body(request, "spec", spec))
+        .await
+}
+
+pub async fn update(session: &Session, namespace: &str, spec: &UpdateSpec) -> Result<(), Error> {
+    session.require(Feature::Namespaces).await?;
+    let request = session.request(Method::PATCH, &endpoint(namespace));
+    session
+        .send_empty(session.body(request, "spec", spec))
+        .await
+}
+
+// Delete a namespace with the workloads running in it
+pub async fn delete(session: &Session, namespace: &str) -> Result<(), Error> {
+    session.require(Feature::Namespaces).await?;
+    let request = session.request(Method::DELETE, &endpoint(namespace));
+    session.send_empty(request).await
+}
+
+fn endpoint(namespace: &str) -> String {
+    format!("{}/{}", ENDPOINT, namespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Access, AccessRole, CreateSpec, ResourceQuota, StorageSpec, SubjectType};
+    use crate::id::ClusterId;
+
+    #[test]
+    fn serialize_create_spec() {
+        let mut spec = CreateSpec::new(ClusterId::new("domain-c8"), "team-a");
+        spec.access_list.push(Access {
+            role: AccessRole::Edit,
+            subject_type: SubjectType::Group,
+            subject: "team-a-devs".to_owned(),
+            domain: "vsphere.local".to_owned(),
+        });
+        spec.storage_specs.push(StorageSpec {
+            policy: "aa6d5a82-1c88-45da-85d3-3d74b91a5bad".to_owned(),
+            limit: Some(102_400),
+        });
+        spec.resource_spec = Some(ResourceQuota {
+            cpu_limit: Some(20_000),
+            ..Default::default()
+        });
+        let json = serde_json::to_value(&spec).expect("serialize");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "cluster": "domain-c8",
+                "namespace": "team-a",
+                "access_list": [{"role": "EDIT", "subject_type": "GROUP",
+                    "subject": "team-a-devs", "domain": "vsphere.local"}],
+                "storage_specs": [{"policy": "aa6d5a82-1c88-45da-85d3-3d74b91a5bad", "limit": 102400}],
+                "resource_spec": {"cpu_limit": 20000}
+            })
+        );
+    }
+}
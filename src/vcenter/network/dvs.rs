@@ -0,0 +1,461 @@
+// Distributed switches and their portgroups. The REST API only lists
+// distributed portgroups, switches and portgroup changes go through the
+// vim25 SOAP API.
+use super::{FilterSpec, NetworkSummary, NetworkType};
+use crate::cis::{self, Session};
+use crate::id::NetworkId;
+use crate::vim25::{Client, Error, Fault, ManagedObjectReference, This};
+use serde::de::IgnoredAny;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Distributed switch with the names of its portgroups
+#[derive(Debug, Clone)]
+pub struct DistributedSwitch {
+    pub switch: ManagedObjectReference,
+    pub name: String,
+    pub uuid: String,
+    pub num_ports: i32,
+    pub portgroup_names: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DvsSummary {
+    name: String,
+    uuid: String,
+    num_ports: i32,
+    #[serde(default)]
+    portgroup_name: Vec<String>,
+}
+
+// VLAN of the ports of a portgroup, None for untagged traffic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Vlan {
+    None,
+    Id(i32),
+    // Guest VLAN tagging for the ranges of VLAN ids
+    Trunk(Vec<RangeInclusive<i32>>),
+    // Private VLAN id of the switch
+    Pvlan(i32),
+}
+
+// Load balancing of the uplinks of a portgroup
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadBalancing {
+    #[serde(rename = "loadbalance_srcid")]
+    SourcePort,
+    #[serde(rename = "loadbalance_srcmac")]
+    SourceMac,
+    #[serde(rename = "loadbalance_ip")]
+    IpHash,
+    #[serde(rename = "loadbalance_loadbased")]
+    PhysicalNicLoad,
+    #[serde(rename = "failover_explicit")]
+    ExplicitFailover,
+}
+
+// Teaming of the uplinks of a portgroup, uplinks are named like "Uplink 1"
+#[derive(Debug, Clone)]
+pub struct TeamingPolicy {
+    pub load_balancing: LoadBalancing,
+    pub notify_switches: bool,
+    // Whether to fail back to an active uplink once it recovered
+    pub failback: bool,
+    pub active_uplinks: Vec<String>,
+    pub standby_uplinks: Vec<String>,
+}
+
+// Early binding portgroup to create on a distributed switch
+#[derive(Debug, Clone)]
+pub struct PortgroupSpec {
+    pub name: String,
+    pub num_ports: i32,
+    pub vlan: Vlan,
+    pub teaming: Option<TeamingPolicy>,
+}
+
+impl PortgroupSpec {
+    pub fn new(name: impl Into<String>, vlan: Vlan) -> Self {
+        PortgroupSpec {
+            name: name.into(),
+            num_ports: 8,
+            vlan,
+            teaming: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Inherited<T> {
+    inherited: bool,
+    value: T,
+}
+
+impl<T> From<T> for Inherited<T> {
+    fn from(value: T) -> Self {
+        Inherited {
+            inherited: false,
+            value,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct NumericRange {
+    start: i32,
+    end: i32,
+}
+
+// Wire format of the VLAN specs, the SOAP API requires the fields in this order
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VlanSpec {
+    #[serde(rename = "@xsi:type")]
+    spec_type: &'static str,
+    inherited: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pvlan_id: Option<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    vlan_id: Vec<VlanId>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum VlanId {
+    Id(i32),
+    Range(NumericRange),
+}
+
+impl From<&Vlan> for VlanSpec {
+    fn from(vlan: &Vlan) -> Self {
+        let spec = VlanSpec {
+            spec_type: "VmwareDistributedVirtualSwitchVlanIdSpec",
+            inherited: false,
+            pvlan_id: None,
+            vlan_id: Vec::new(),
+        };
+        match vlan {
+            Vlan::None => VlanSpec {
+                vlan_id: vec![VlanId::Id(0)],
+                ..spec
+            },
+            Vlan::Id(id) => VlanSpec {
+                vlan_id: vec![VlanId::Id(*id)],
+                ..spec
+            },
+            Vlan::Trunk(ranges) => VlanSpec {
+                spec_type: "VmwareDistributedVirtualSwitchTrunkVlanSpec",
+                vlan_id: ranges
+                    .iter()
+                    .map(|range| {
+                        VlanId::Range(NumericRange {
+                            start: *range.start(),
+                            end: *range.end(),
+                        })
+                    })
+                    .collect(),
+                ..spec
+            },
+            Vlan::Pvlan(id) => VlanSpec {
+                spec_type: "VmwareDistributedVirtualSwitchPvlanSpec",
+                pvlan_id: Some(*id),
+                ..spec
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UplinkPortOrder<'a> {
+    inherited: bool,
+    #[serde(rename = "activeUplinkPort")]
+    active: &'a [String],
+    #[serde(rename = "standbyUplinkPort")]
+    standby: &'a [String],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UplinkTeamingPolicy<'a> {
+    inherited: bool,
+    policy: Inherited<LoadBalancing>,
+    notify_switches: Inherited<bool>,
+    // Rolling order is the inverse of failback
+    rolling_order: Inherited<bool>,
+    uplink_port_order: UplinkPortOrder<'a>,
+}
+
+impl<'a> From<&'a TeamingPolicy> for UplinkTeamingPolicy<'a> {
+    fn from(teaming: &'a TeamingPolicy) -> Self {
+        UplinkTeamingPolicy {
+            inherited: false,
+            policy: teaming.load_balancing.into(),
+            notify_switches: teaming.notify_switches.into(),
+            rolling_order: (!teaming.failback).into(),
+            uplink_port_order: UplinkPortOrder {
+                inherited: false,
+                active: &teaming.active_uplinks,
+                standby: &teaming.standby_uplinks,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PortSetting<'a> {
+    #[serde(rename = "@xsi:type")]
+    setting_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vlan: Option<VlanSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uplink_teaming_policy: Option<UplinkTeamingPolicy<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PortgroupConfigSpec<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ports: Option<i32>,
+    default_port_config: PortSetting<'a>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    binding: Option<&'static str>,
+}
+
+// Request of the create and reconfigure calls of portgroups
+#[derive(Serialize)]
+struct PortgroupRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    spec: PortgroupConfigSpec<'a>,
+}
+
+#[derive(Serialize)]
+struct CreateContainerViewRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    container: &'a ManagedObjectReference,
+    #[serde(rename = "type")]
+    object_type: &'a str,
+    recursive: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfManagedObjectReference {
+    #[serde(default, rename = "ManagedObjectReference")]
+    references: Vec<ManagedObjectReference>,
+}
+
+// Distributed portgroups of all switches
+pub async fn portgroups(session: &Session) -> Result<Vec<NetworkSummary>, cis::Error> {
+    let filter = FilterSpec {
+        types: vec![NetworkType::DistributedPortgroup],
+        ..Default::default()
+    };
+    super::list(session, &filter).await
+}
+
+pub async fn switches(client: &Client<'_>) -> Result<Vec<DistributedSwitch>, Error> {
+    let mut switches = Vec::new();
+    for switch in switch_references(client).await? {
+        let summary: Option<DvsSummary> = client.retrieve_property(&switch, "summary").await?;
+        // Switches removed since the view was created have no summary
+        if let Some(summary) = summary {
+            switches.push(DistributedSwitch {
+                switch,
+                name: summary.name,
+                uuid: summary.uuid,
+                num_ports: summary.num_ports,
+                portgroup_names: summary.portgroup_name,
+            });
+        }
+    }
+    Ok(switches)
+}
+
+// Portgroups of the switch including its uplink portgroup
+pub async fn switch_portgroups(
+    client: &Client<'_>,
+    switch: &ManagedObjectReference,
+) -> Result<Vec<NetworkId>, Error> {
+    let portgroups: Option<ArrayOfManagedObjectReference> =
+        client.retrieve_property(switch, "portgroup").await?;
+    Ok(portgroups
+        .unwrap_or_default()
+        .references
+        .into_iter()
+        .map(|portgroup| NetworkId::new(portgroup.value))
+        .collect())
+}
+
+// Create a portgroup on the switch and wait until it is available on the
+// hosts of the switch
+pub async fn create_portgroup(
+    client: &Client<'_>,
+    switch: &ManagedObjectReference,
+    spec: &PortgroupSpec,
+) -> Result<(), Error> {
+    let request = PortgroupRequest {
+        this: switch,
+        spec: PortgroupConfigSpec {
+            config_version: None,
+            name: Some(&spec.name),
+            num_ports: Some(spec.num_ports),
+            default_port_config: PortSetting {
+                setting_type: "VMwareDVSPortSetting",
+                vlan: Some((&spec.vlan).into()),
+                uplink_teaming_policy: spec.teaming.as_ref().map(Into::into),
+            },
+            binding: Some("earlyBinding"),
+        },
+    };
+    let task = client.call("CreateDVPortgroup_Task", &request).await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+pub async fn update_vlan(
+    client: &Client<'_>,
+    portgroup: &NetworkId,
+    vlan: &Vlan,
+) -> Result<(), Error> {
+    let setting = PortSetting {
+        setting_type: "VMwareDVSPortSetting",
+        vlan: Some(vlan.into()),
+        uplink_teaming_policy: None,
+    };
+    reconfigure_portgroup(client, portgroup, setting).await
+}
+
+pub async fn update_teaming(
+    client: &Client<'_>,
+    portgroup: &NetworkId,
+    teaming: &TeamingPolicy,
+) -> Result<(), Error> {
+    let setting = PortSetting {
+        setting_type: "VMwareDVSPortSetting",
+        vlan: None,
+        uplink_teaming_policy: Some(teaming.into()),
+    };
+    reconfigure_portgroup(client, portgroup, setting).await
+}
+
+async fn reconfigure_portgroup(
+    client: &Client<'_>,
+    portgroup: &NetworkId,
+    setting: PortSetting<'_>,
+) -> Result<(), Error> {
+    let portgroup = ManagedObjectReference::new(portgroup.object_type(), portgroup.as_str());
+    // Reconfigurations based on an outdated version are rejected
+    let config_version = client
+        .retrieve_property(&portgroup, "config.configVersion")
+        .await?;
+    let request = PortgroupRequest {
+        this: &portgroup,
+        spec: PortgroupConfigSpec {
+            config_version,
+            name: None,
+            num_ports: None,
+            default_port_config: setting,
+            binding: None,
+        },
+    };
+    let task = client.call("ReconfigureDVPortgroup_Task", &request).await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+// Switches of all datacenters, found with a container view of the root folder
+async fn switch_references(client: &Client<'_>) -> Result<Vec<ManagedObjectReference>, Error> {
+    let content = client.cached_content().await?;
+    let view_manager = content
+        .view_manager
+        .ok_or_else(|| Error::Fault(Fault::not_supported("ViewManager")))?;
+    let request = CreateContainerViewRequest {
+        this: &view_manager,
+        container: &content.root_folder,
+        object_type: "VmwareDistributedVirtualSwitch",
+        recursive: true,
+    };
+    let view: ManagedObjectReference = client.call("CreateContainerView", &request).await?;
+    let switches: Result<Option<ArrayOfManagedObjectReference>, Error> =
+        client.retrieve_property(&view, "view").await;
+    client
+        .call_empty("DestroyView", &This { this: &view })
+        .await?;
+    Ok(switches?.unwrap_or_default().references)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vim25::envelope;
+
+    #[test]
+    fn create_portgroup_envelope() {
+        let switch = ManagedObjectReference::new("VmwareDistributedVirtualSwitch", "dvs-21");
+        let teaming = TeamingPolicy {
+            load_balancing: LoadBalancing::PhysicalNicLoad,
+            notify_switches: true,
+            failback: true,
+            active_uplinks: vec!["Uplink 1".to_owned()],
+            standby_uplinks: vec!["Uplink 2".to_owned()],
+        };
+        let request = PortgroupRequest {
+            this: &switch,
+            spec: PortgroupConfigSpec {
+                config_version: None,
+                name: Some("vlan-120"),
+                num_ports: Some(8),
+                default_port_config: PortSetting {
+                    setting_type: "VMwareDVSPortSetting",
+                    vlan: Some((&Vlan::Id(120)).into()),
+                    uplink_teaming_policy: Some((&teaming).into()),
+                },
+                binding: Some("earlyBinding"),
+            },
+        };
+        let xml = envelope("CreateDVPortgroup_Task", &request).unwrap();
+        assert!(xml.contains(
+            "<spec><name>vlan-120</name><numPorts>8</numPorts>\
+             <defaultPortConfig xsi:type=\"VMwareDVSPortSetting\">\
+             <vlan xsi:type=\"VmwareDistributedVirtualSwitchVlanIdSpec\">\
+             <inherited>false</inherited><vlanId>120</vlanId></vlan>\
+             <uplinkTeamingPolicy><inherited>false</inherited>\
+             <policy><inherited>false</inherited><value>loadbalance_loadbased</value></policy>\
+             <notifySwitches><inherited>false</inherited><value>true</value></notifySwitches>\
+             <rollingOrder><inherited>false</inherited><value>false</value></rollingOrder>\
+             <uplinkPortOrder><inherited>false</inherited>\
+             <activeUplinkPort>Uplink 1</activeUplinkPort>\
+             <standbyUplinkPort>Uplink 2</standbyUplinkPort></uplinkPortOrder>\
+             </uplinkTeamingPolicy></defaultPortConfig><type>earlyBinding</type></spec>"
+        ));
+    }
+
+    #[test]
+    fn trunk_vlan_spec() {
+        let spec = VlanSpec::from(&Vlan::Trunk(vec![100..=199, 300..=300]));
+        #[derive(Serialize)]
+        struct Spec {
+            vlan: VlanSpec,
+        }
+        let xml = envelope("Spec", &Spec { vlan: spec }).unwrap();
+        assert!(xml.contains(
+            "<vlan xsi:type=\"VmwareDistributedVirtualSwitchTrunkVlanSpec\">\
+             <inherited>false</inherited><vlanId><start>100</start><end>199</end></vlanId>\
+             <vlanId><start>300</start><end>300</end></vlanId></vlan>"
+        ));
+    }
+}
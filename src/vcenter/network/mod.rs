@@ -0,0 +1,70 @@
+// Distributed switches and portgroups
+#[cfg(feature = "vim25")]
+pub mod dvs;
+
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query};
+use crate::id::{DatacenterId, FolderId, NetworkId};
+use crate::paging::PagedStream;
+
+// Type of a network
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NetworkType {
+    #[display(fmt = "STANDARD_PORTGROUP")]
+    StandardPortgroup,
+    #[display(fmt = "DISTRIBUTED_PORTGROUP")]
+    DistributedPortgroup,
+    #[display(fmt = "OPAQUE_NETWORK")]
+    OpaqueNetwork,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    #[display(fmt = "UNKNOWN")]
+    Unknown,
+}
+
+// Summary of a network as returned by the list call
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkSummary {
+    pub network: NetworkId,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub network_type: NetworkType,
+}
+
+// Filter for the list call, empty fields match every network
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub networks: Vec<NetworkId>,
+    pub names: Vec<String>,
+    pub types: Vec<NetworkType>,
+    pub folders: Vec<FolderId>,
+    pub datacenters: Vec<DatacenterId>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "networks", &self.networks);
+        push_query(&mut query, "names", &self.names);
+        push_query(&mut query, "types", &self.types);
+        push_query(&mut query, "folders", &self.folders);
+        push_query(&mut query, "datacenters", &self.datacenters);
+        query
+    }
+}
+
+// List the networks matching the filter (at most 1000 are returned)
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<NetworkSummary>, Error> {
+    let request = session.list("/vcenter/network", &filter.query());
+    session.send(request).await
+}
+
+// Stream the networks matching the filter, they are fetched with a single list call
+pub fn list_stream<'a>(
+    session: &'a Session,
+    filter: &FilterSpec,
+) -> PagedStream<'a, NetworkSummary> {
+    let filter = filter.clone();
+    PagedStream::once(async move { list(session, &filter).await })
+}
@@ -0,0 +1,172 @@
+use crate::cis::{Error, Session};
+use crate::common::{push_query, Query};
+use crate::id::{ClusterId, DatacenterId, HostId, ResourcePoolId};
+use crate::paging::PagedStream;
+use reqwest::Method;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SharesLevel {
+    Low,
+    Normal,
+    High,
+    Custom,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Shares of a resource pool, the number of shares is only used with level CUSTOM
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SharesInfo {
+    pub level: SharesLevel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shares: Option<i64>,
+}
+
+// Allocation of CPU (MHz) or memory (MB) of a resource pool, a limit of -1
+// means unlimited
+#[derive(Deserialize, Debug, Clone)]
+pub struct ResourceAllocationInfo {
+    #[serde(default)]
+    pub reservation: i64,
+    #[serde(default)]
+    pub expandable_reservation: bool,
+    #[serde(default)]
+    pub limit: i64,
+    pub shares: SharesInfo,
+}
+
+// Allocation of CPU (MHz) or memory (MB) for create and update calls, unset
+// fields use the defaults or are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct ResourceAllocationSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reservation: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expandable_reservation: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shares: Option<SharesInfo>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ResourcePoolSummary {
+    pub resource_pool: ResourcePoolId,
+    pub name: String,
+}
+
+// Detailed information about a resource pool, the allocations are reported
+// since vSphere 7.0
+#[derive(Deserialize, Debug, Clone)]
+pub struct ResourcePoolInfo {
+    pub name: String,
+    #[serde(default)]
+    pub resource_pools: Vec<ResourcePoolId>,
+    pub cpu_allocation: Option<ResourceAllocationInfo>,
+    pub memory_allocation: Option<ResourceAllocationInfo>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub name: String,
+    pub parent: ResourcePoolId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_allocation: Option<ResourceAllocationSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_allocation: Option<ResourceAllocationSpec>,
+}
+
+// Changes to a resource pool, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_allocation: Option<ResourceAllocationSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_allocation: Option<ResourceAllocationSpec>,
+}
+
+// Filter for the list call, empty fields match every resource pool
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub resource_pools: Vec<ResourcePoolId>,
+    pub names: Vec<String>,
+    pub parent_resource_pools: Vec<ResourcePoolId>,
+    pub datacenters: Vec<DatacenterId>,
+    pub hosts: Vec<HostId>,
+    pub clusters: Vec<ClusterId>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "resource_pools", &self.resource_pools);
+        push_query(&mut query, "names", &self.names);
+        push_query(
+            &mut query,
+            "parent_resource_pools",
+            &self.parent_resource_pools,
+        );
+        push_query(&mut query, "datacenters", &self.datacenters);
+        push_query(&mut query, "hosts", &self.hosts);
+        push_query(&mut query, "clusters", &self.clusters);
+        query
+    }
+}
+
+// List the resource pools matching the filter (at most 1000 are returned)
+pub async fn list(
+    session: &Session,
+    filter: &FilterSpec,
+) -> Result<Vec<ResourcePoolSummary>, Error> {
+    let request = session.list("/vcenter/resource-pool", &filter.query());
+    session.send(request).await
+}
+
+// Stream the resource pools matching the filter, they are fetched with a single list call
+pub fn list_stream<'a>(
+    session: &'a Session,
+    filter: &FilterSpec,
+) -> PagedStream<'a, ResourcePoolSummary> {
+    let filter = filter.clone();
+    PagedStream::once(async move { list(session, &filter).await })
+}
+
+pub async fn get(
+    session: &Session,
+    resource_pool: &ResourcePoolId,
+) -> Result<ResourcePoolInfo, Error> {
+    let request = session.request(Method::GET, &endpoint(resource_pool));
+    session.send(request).await
+}
+
+// Create a resource pool and return its identifier (vSphere 7.0+)
+pub async fn create(session: &Session, spec: &CreateSpec) -> Result<ResourcePoolId, Error> {
+    let request = session.request(Method::POST, "/vcenter/resource-pool");
+    session.send(session.body(request, "spec", spec)).await
+}
+
+// Update a resource pool (vSphere 7.0+)
+pub async fn update(
+    session: &Session,
+    resource_pool: &ResourcePoolId,
+    spec: &UpdateSpec,
+) -> Result<(), Error> {
+    let request = session.request(Method::PATCH, &endpoint(resource_pool));
+    session
+        .send_empty(session.body(request, "spec", spec))
+        .await
+}
+
+// Delete a resource pool, its virtual machines are moved to the parent (vSphere 7.0+)
+pub async fn delete(session: &Session, resource_pool: &ResourcePoolId) -> Result<(), Error> {
+    let request = session.request(Method::DELETE, &endpoint(resource_pool));
+    session.send_empty(request).await
+}
+
+fn endpoint(resource_pool: &ResourcePoolId) -> String {
+    format!("/vcenter/resource-pool/{}", resource_pool)
+}
@@ -0,0 +1,94 @@
+use crate::cis::{Error, LocalizableMessage, Session};
+use crate::common::MapResponse;
+use reqwest::Method;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StartupType {
+    Manual,
+    Automatic,
+    Disabled,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum State {
+    Starting,
+    Stopping,
+    Started,
+    Stopped,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Health {
+    Degraded,
+    Healthy,
+    HealthyWithWarnings,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// vCenter service managed by vMon like "vpxd", the health is only reported
+// for started services
+#[derive(Deserialize, Debug, Clone)]
+pub struct ServiceInfo {
+    #[serde(default)]
+    pub name_key: String,
+    #[serde(default)]
+    pub description_key: String,
+    pub startup_type: StartupType,
+    pub state: State,
+    pub health: Option<Health>,
+    #[serde(default)]
+    pub health_messages: Vec<LocalizableMessage>,
+}
+
+// Changes to a service, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup_type: Option<StartupType>,
+}
+
+// Services of vCenter keyed by their name
+pub async fn list(session: &Session) -> Result<HashMap<String, ServiceInfo>, Error> {
+    let request = session.request(Method::GET, "/vcenter/services");
+    let services: MapResponse<ServiceInfo> = session.send(request).await?;
+    Ok(services.into())
+}
+
+pub async fn get(session: &Session, service: &str) -> Result<ServiceInfo, Error> {
+    let request = session.request(Method::GET, &format!("/vcenter/services/{}", service));
+    session.send(request).await
+}
+
+pub async fn update(session: &Session, service: &str, spec: &UpdateSpec) -> Result<(), Error> {
+    let request = session.request(Method::PATCH, &format!("/vcenter/services/{}", service));
+    session
+        .send_empty(session.body(request, "spec", spec))
+        .await
+}
+
+pub async fn start(session: &Session, service: &str) -> Result<(), Error> {
+    service_action(session, service, "start").await
+}
+
+pub async fn stop(session: &Session, service: &str) -> Result<(), Error> {
+    service_action(session, service, "stop").await
+}
+
+pub async fn restart(session: &Session, service: &str) -> Result<(), Error> {
+    service_action(session, service, "restart").await
+}
+
+async fn service_action(session: &Session, service: &str, action: &str) -> Result<(), Error> {
+    let request = session.path_action(&format!("/vcenter/services/{}", service), action);
+    session.send_empty(request).await
+}
@@ -0,0 +1,2 @@
+// Storage policies of the storage policy based management (SPBM)
+pub mod policies;
@@ -0,0 +1,70 @@
+use crate::cis::{Error, Session};
+use crate::common::{push_query, MapResponse, Query};
+use crate::id::VmId;
+use reqwest::Method;
+use std::collections::HashMap;
+
+// Compliance of a virtual machine or disk with its storage policy
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ComplianceStatus {
+    Compliant,
+    NonCompliant,
+    UnknownCompliance,
+    NotApplicable,
+    OutOfDate,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PolicySummary {
+    #[serde(default)]
+    pub policy: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+// Usage of a storage policy by a virtual machine, disks are given by their identifiers
+#[derive(Deserialize, Debug, Clone)]
+pub struct PolicyUsage {
+    #[serde(default)]
+    pub vm_home: bool,
+    #[serde(default)]
+    pub disks: Vec<String>,
+}
+
+// Filter for the list call, empty fields match every policy
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub policies: Vec<String>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "policies", &self.policies);
+        query
+    }
+}
+
+const ENDPOINT: &str = "/vcenter/storage/policies";
+
+// List the storage policies matching the filter, the policy identifiers
+// are used in disk and VM storage policy specifications
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<PolicySummary>, Error> {
+    let request = session.list(ENDPOINT, &filter.query());
+    session.send(request).await
+}
+
+// Virtual machines using the storage policy keyed by their identifiers
+pub async fn vms(session: &Session, policy: &str) -> Result<HashMap<VmId, PolicyUsage>, Error> {
+    let request = session.request(Method::GET, &format!("{}/{}/vm", ENDPOINT, policy));
+    let vms: MapResponse<PolicyUsage> = session.send(request).await?;
+    let vms: HashMap<String, PolicyUsage> = vms.into();
+    Ok(vms
+        .into_iter()
+        .map(|(vm, usage)| (vm.into(), usage))
+        .collect())
+}
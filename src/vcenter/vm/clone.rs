@@ -0,0 +1,84 @@
+use super::{PlacementSpec, Vm};
+use crate::cis::{Error, Session};
+use crate::common::serialize_map;
+use crate::id::{DatastoreId, VmId};
+use crate::vcenter::vm_template::GuestCustomizationSpec;
+use reqwest::Method;
+use std::collections::HashMap;
+
+// Target datastore of a disk of the clone
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct DiskCloneSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore: Option<DatastoreId>,
+}
+
+// Clone of an existing virtual machine, unset placement fields are taken
+// from the source. Disks are keyed by their identifiers in the source.
+#[derive(Serialize, Debug, Clone)]
+pub struct CloneSpec {
+    pub source: VmId,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<PlacementSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disks_to_remove: Vec<String>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub disks_to_update: HashMap<String, DiskCloneSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power_on: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guest_customization_spec: Option<GuestCustomizationSpec>,
+}
+
+impl CloneSpec {
+    pub fn new(source: impl Into<VmId>, name: impl Into<String>) -> Self {
+        CloneSpec {
+            source: source.into(),
+            name: name.into(),
+            placement: None,
+            disks_to_remove: Vec::new(),
+            disks_to_update: HashMap::new(),
+            power_on: None,
+            guest_customization_spec: None,
+        }
+    }
+}
+
+impl<'a> Vm<'a> {
+    // Clone a virtual machine (vSphere 7.0 or later) and return a handle to the clone
+    pub async fn clone(session: &'a Session, spec: &CloneSpec) -> Result<Vm<'a>, Error> {
+        let request = session.action(Method::POST, "/vcenter/vm", "clone");
+        let id: VmId = session.send(session.body(request, "spec", spec)).await?;
+        Ok(Vm::new(session, id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CloneSpec, DiskCloneSpec};
+
+    #[test]
+    fn clone_spec() {
+        let mut spec = CloneSpec::new("vm-10", "web-2");
+        spec.power_on = Some(true);
+        spec.disks_to_update.insert(
+            "2000".into(),
+            DiskCloneSpec {
+                datastore: Some("datastore-12".into()),
+            },
+        );
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "source": "vm-10",
+                "name": "web-2",
+                "disks_to_update": {"2000": {"datastore": "datastore-12"}},
+                "power_on": true
+            })
+        );
+    }
+}
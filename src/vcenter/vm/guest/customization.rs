@@ -0,0 +1,72 @@
+use crate::cis::Error;
+use crate::vcenter::guest::CustomizationSpec;
+use crate::vcenter::vm::Vm;
+use chrono::prelude::*;
+use reqwest::Method;
+
+// Customization to apply, either a stored specification by name or an
+// inline specification
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct SetSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec: Option<CustomizationSpec>,
+}
+
+impl SetSpec {
+    pub fn by_name(name: impl Into<String>) -> Self {
+        SetSpec {
+            name: Some(name.into()),
+            spec: None,
+        }
+    }
+
+    pub fn inline(spec: CustomizationSpec) -> Self {
+        SetSpec {
+            name: None,
+            spec: Some(spec),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CustomizationStatus {
+    Idle,
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CustomizationInfo {
+    pub status: CustomizationStatus,
+    pub error: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl Vm<'_> {
+    // Apply a customization to a powered off virtual machine, it runs in the
+    // guest on the next power on
+    pub async fn customize_guest(&self, spec: &SetSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PUT, &self.endpoint("/guest/customization"));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Status of the last customization of the guest
+    pub async fn guest_customization(&self) -> Result<CustomizationInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &self.endpoint("/guest/customization"));
+        self.session.send(request).await
+    }
+}
@@ -0,0 +1,170 @@
+use super::Credentials;
+use crate::cis::capabilities::Feature;
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use bytes::Bytes;
+use futures::stream::{Stream, TryStreamExt};
+use reqwest::{Body, Method, StatusCode};
+
+#[derive(Serialize, Debug, Clone)]
+struct FileCreationAttributes {
+    overwrite: bool,
+    size: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct TransferSpec<'a> {
+    path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes: Option<FileCreationAttributes>,
+}
+
+#[derive(Serialize)]
+struct TransferRequest<'a> {
+    credentials: &'a Credentials,
+    spec: TransferSpec<'a>,
+}
+
+impl Vm<'_> {
+    // Upload a file into the guest, the size has to match the body. The
+    // transfer goes directly to the ESXi host of the virtual machine.
+    pub async fn upload_file(
+        &self,
+        credentials: &Credentials,
+        guest_path: &str,
+        body: impl Into<Body>,
+        size: u64,
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let attributes = FileCreationAttributes { overwrite, size };
+        let url = self
+            .initiate_transfer(credentials, guest_path, Some(attributes))
+            .await?;
+        let request = self.session.client().put(&url).body(body).build()?;
+        let resp = self.session.dispatch(request).await?;
+        transfer_status(resp.status())
+    }
+
+    // Download a file from the guest as stream of chunks
+    pub async fn download_file(
+        &self,
+        credentials: &Credentials,
+        guest_path: &str,
+    ) -> Result<impl Stream<Item = Result<Bytes, Error>>, Error> {
+        let url = self
+            .initiate_transfer(credentials, guest_path, None)
+            .await?;
+        let request = self.session.client().get(&url).build()?;
+        let resp = self.session.dispatch(request).await?;
+        transfer_status(resp.status())?;
+        Ok(resp.bytes_stream().map_err(Error::from))
+    }
+
+    // Download a small file from the guest into memory
+    pub async fn read_file(
+        &self,
+        credentials: &Credentials,
+        guest_path: &str,
+    ) -> Result<Vec<u8>, Error> {
+        let chunks: Vec<Bytes> = self
+            .download_file(credentials, guest_path)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(chunks.concat())
+    }
+
+    // Request a one time URL of the ESXi host for the transfer of the file,
+    // only served with the /api flavor
+    async fn initiate_transfer(
+        &self,
+        credentials: &Credentials,
+        path: &str,
+        attributes: Option<FileCreationAttributes>,
+    ) -> Result<String, Error> {
+        self.session.require(Feature::GuestOperations).await?;
+        let session = self.session.with_api_flavor();
+        let endpoint = self.endpoint("/guest/filesystem");
+        let request = session.action(Method::POST, &endpoint, "create");
+        let body = TransferRequest {
+            credentials,
+            spec: TransferSpec { path, attributes },
+        };
+        session.send(session.json(request, &body)).await
+    }
+}
+
+fn transfer_status(status: StatusCode) -> Result<(), Error> {
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(Error::UnexpectedStatusCode(status.as_u16()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cis::transport::MockTransport;
+    use crate::cis::Session;
+    use std::sync::Arc;
+
+    #[test]
+    fn upload_request() {
+        let credentials = Credentials::username_password("root", "secret");
+        let body = TransferRequest {
+            credentials: &credentials,
+            spec: TransferSpec {
+                path: "/etc/motd",
+                attributes: Some(FileCreationAttributes {
+                    overwrite: true,
+                    size: 12,
+                }),
+            },
+        };
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(
+            json["spec"],
+            serde_json::json!({"path": "/etc/motd", "attributes": {"overwrite": true, "size": 12}})
+        );
+    }
+
+    #[tokio::test]
+    async fn transfer_uses_api_flavor() {
+        let mock = Arc::new(MockTransport::new());
+        // Guest operations need vCenter 7.0 U2 whose /api flavor answers
+        mock.on(
+            Method::GET,
+            "/rest/appliance/system/version",
+            StatusCode::OK,
+            r#"{"value":{"version":"7.0.3.01000"}}"#,
+        );
+        mock.on(
+            Method::GET,
+            "/api/appliance/system/version",
+            StatusCode::OK,
+            r#"{"version":"7.0.3.01000"}"#,
+        );
+        mock.on(
+            Method::POST,
+            "/api/vcenter/vm/vm-42/guest/filesystem?action=create",
+            StatusCode::CREATED,
+            r#""https://esxi-1.test:443/guestFile?id=17&token=5273""#,
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        let credentials = Credentials::username_password("root", "secret");
+        let url = Vm::new(&session, "vm-42")
+            .initiate_transfer(&credentials, "/etc/motd", None)
+            .await
+            .expect("initiate transfer");
+        assert_eq!(url, "https://esxi-1.test:443/guestFile?id=17&token=5273");
+        let requests = mock.requests();
+        assert_eq!(
+            requests[2].url.path(),
+            "/api/vcenter/vm/vm-42/guest/filesystem"
+        );
+    }
+}
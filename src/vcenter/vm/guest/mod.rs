@@ -0,0 +1,320 @@
+// Customization of the guest operating system
+pub mod customization;
+// File transfers to and from the guest operating system
+pub mod filesystem;
+// Processes in the guest operating system
+pub mod processes;
+
+use super::Vm;
+use crate::cis::{Error, ErrorType, LocalizableMessage};
+use crate::common::{redacted, MapResponse};
+use reqwest::Method;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::time::{delay_for, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Type of the credentials of a guest operation
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CredentialsType {
+    UsernamePassword,
+    SamlBearerToken,
+}
+
+// Credentials of a guest user for guest operations, which require VMware
+// Tools and the API flavor of vSphere 7.0 U2 or later
+#[derive(Serialize, Clone)]
+pub struct Credentials {
+    pub interactive_session: bool,
+    #[serde(rename = "type")]
+    pub credentials_type: CredentialsType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saml_token: Option<String>,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("interactive_session", &self.interactive_session)
+            .field("credentials_type", &self.credentials_type)
+            .field("user_name", &self.user_name)
+            .field("password", &redacted(&self.password))
+            .field("saml_token", &redacted(&self.saml_token))
+            .finish()
+    }
+}
+
+impl Credentials {
+    pub fn username_password(user_name: impl Into<String>, password: impl Into<String>) -> Self {
+        Credentials {
+            interactive_session: false,
+            credentials_type: CredentialsType::UsernamePassword,
+            user_name: Some(user_name.into()),
+            password: Some(password.into()),
+            saml_token: None,
+        }
+    }
+
+    pub fn saml_bearer_token(token: impl Into<String>) -> Self {
+        Credentials {
+            interactive_session: false,
+            credentials_type: CredentialsType::SamlBearerToken,
+            user_name: None,
+            password: None,
+            saml_token: Some(token.into()),
+        }
+    }
+}
+
+// Family of a guest operating system
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OsFamily {
+    Windows,
+    Linux,
+    Netware,
+    Solaris,
+    Darwin,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Other,
+}
+
+// Identity of the guest operating system as reported by VMware Tools
+#[derive(Deserialize, Debug, Clone)]
+pub struct Identity {
+    pub name: String,
+    pub family: OsFamily,
+    pub full_name: LocalizableMessage,
+    #[serde(default)]
+    pub host_name: String,
+    pub ip_address: Option<String>,
+}
+
+// DNS configuration of the guest operating system
+#[derive(Deserialize, Debug, Clone)]
+pub struct DnsConfig {
+    #[serde(default)]
+    pub ip_addresses: Vec<String>,
+    #[serde(default)]
+    pub search_domains: Vec<String>,
+}
+
+// Host and domain name as assigned by DNS
+#[derive(Deserialize, Debug, Clone)]
+pub struct DnsAssignedValues {
+    #[serde(default)]
+    pub host_name: String,
+    #[serde(default)]
+    pub domain_name: String,
+}
+
+// Network configuration of the guest operating system
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkingInfo {
+    pub dns_values: Option<DnsAssignedValues>,
+    pub dns: Option<DnsConfig>,
+}
+
+// Origin of an IP address of a guest network interface
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum IpAddressOrigin {
+    Manual,
+    Dhcp,
+    Linklayer,
+    Random,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Other,
+}
+
+// State of an IP address of a guest network interface
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum IpAddressStatus {
+    Preferred,
+    Deprecated,
+    Invalid,
+    Inaccessible,
+    Tentative,
+    Duplicate,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct IpAddressInfo {
+    #[serde(default)]
+    pub ip_address: String,
+    #[serde(default)]
+    pub prefix_length: u32,
+    pub origin: Option<IpAddressOrigin>,
+    pub state: IpAddressStatus,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct IpConfig {
+    #[serde(default)]
+    pub ip_addresses: Vec<IpAddressInfo>,
+}
+
+// Network interface of the guest operating system, nic is the identifier of
+// the virtual Ethernet adapter it belongs to
+#[derive(Deserialize, Debug, Clone)]
+pub struct InterfaceInfo {
+    pub mac_address: Option<String>,
+    pub nic: Option<String>,
+    pub ip: Option<IpConfig>,
+}
+
+// Local filesystem of the guest operating system, sizes are given in bytes
+#[derive(Deserialize, Debug, Clone)]
+pub struct LocalFilesystemInfo {
+    #[serde(default)]
+    pub capacity: u64,
+    #[serde(default)]
+    pub free_space: u64,
+}
+
+// All guest calls fail with ErrorType::ServiceUnavailable if VMware Tools
+// are not running in the guest
+impl Vm<'_> {
+    pub async fn guest_identity(&self) -> Result<Identity, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &self.endpoint("/guest/identity"));
+        self.session.send(request).await
+    }
+
+    pub async fn guest_networking(&self) -> Result<NetworkingInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &self.endpoint("/guest/networking"));
+        self.session.send(request).await
+    }
+
+    pub async fn guest_interfaces(&self) -> Result<Vec<InterfaceInfo>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &self.endpoint("/guest/networking/interfaces"));
+        self.session.send(request).await
+    }
+
+    // Local filesystems of the guest keyed by their mount point
+    pub async fn guest_local_filesystems(
+        &self,
+    ) -> Result<HashMap<String, LocalFilesystemInfo>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &self.endpoint("/guest/local-filesystem"));
+        let filesystems: MapResponse<LocalFilesystemInfo> = self.session.send(request).await?;
+        Ok(filesystems.into())
+    }
+
+    // Poll until the guest reports an IP address and return the addresses of
+    // all its interfaces, loopback and link-local ones are skipped. Fails with
+    // Error::Timeout if no address was reported in time. The future can be
+    // dropped at any time, e.g. in select!, there is nothing to clean up.
+    pub async fn wait_for_guest_ip(&self, timeout: Duration) -> Result<Vec<IpAddr>, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let ips = match self.guest_ips().await {
+                Ok(ips) => ips,
+                // VMware Tools are not running yet
+                Err(err) if err.error_type() == Some(&ErrorType::ServiceUnavailable) => Vec::new(),
+                Err(err) => return Err(err),
+            };
+            if !ips.is_empty() {
+                return Ok(ips);
+            }
+            if Instant::now() + POLL_INTERVAL > deadline {
+                return Err(Error::Timeout);
+            }
+            delay_for(POLL_INTERVAL).await;
+        }
+    }
+
+    // Usable addresses of the interfaces, the identity only has the primary
+    // address and is used if the interfaces report none
+    async fn guest_ips(&self) -> Result<Vec<IpAddr>, Error> {
+        let mut ips: Vec<IpAddr> = self
+            .guest_interfaces()
+            .await?
+            .into_iter()
+            .filter_map(|interface| interface.ip)
+            .flat_map(|ip| ip.ip_addresses)
+            .filter(|address| address.state == IpAddressStatus::Preferred)
+            .filter_map(|address| address.ip_address.parse().ok())
+            .filter(is_usable)
+            .collect();
+        if ips.is_empty() {
+            let identity = self.guest_identity().await?;
+            ips.extend(
+                identity
+                    .ip_address
+                    .and_then(|ip| ip.parse().ok())
+                    .filter(is_usable),
+            );
+        }
+        Ok(ips)
+    }
+}
+
+// Loopback and link-local addresses can't be used to reach the guest
+fn is_usable(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => !ip.is_loopback() && !ip.is_link_local() && !ip.is_unspecified(),
+        IpAddr::V6(ip) => {
+            !ip.is_loopback() && !ip.is_unspecified() && ip.segments()[0] & 0xffc0 != 0xfe80
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_usable, Credentials, LocalFilesystemInfo};
+    use crate::common::MapResponse;
+    use std::collections::HashMap;
+    use std::net::IpAddr;
+
+    #[test]
+    fn debug_redacts_secrets() {
+        let debug = format!("{:?}", Credentials::username_password("root", "secret"));
+        assert!(debug.contains("\"root\"") && debug.contains("<redacted>"));
+        assert!(!debug.contains("secret"));
+        let debug = format!("{:?}", Credentials::saml_bearer_token("PHNhbWw+"));
+        assert!(!debug.contains("PHNhbWw+"));
+    }
+
+    #[test]
+    fn usable_guest_ips() {
+        let usable = |ip: &str| is_usable(&ip.parse::<IpAddr>().unwrap());
+        assert!(usable("10.0.12.34"));
+        assert!(usable("2001:db8::12"));
+        assert!(!usable("127.0.0.1"));
+        assert!(!usable("169.254.10.1"));
+        assert!(!usable("fe80::250:56ff:fe8a:1"));
+        assert!(!usable("::1"));
+    }
+
+    #[test]
+    fn local_filesystems_from_both_flavors() {
+        let rest: MapResponse<LocalFilesystemInfo> =
+            serde_json::from_str(r#"[{"key":"/","value":{"capacity":100,"free_space":40}}]"#)
+                .expect("deserialize rest map");
+        let api: MapResponse<LocalFilesystemInfo> =
+            serde_json::from_str(r#"{"/":{"capacity":100,"free_space":40}}"#)
+                .expect("deserialize api map");
+        for map in [rest, api] {
+            let map: HashMap<String, LocalFilesystemInfo> = map.into();
+            assert_eq!(map["/"].free_space, 40);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use super::Credentials;
+use crate::cis::capabilities::Feature;
+use crate::cis::Error;
+use crate::common::serialize_map;
+use crate::vcenter::vm::Vm;
+use chrono::prelude::*;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// Program to start in the guest, the path has to be absolute
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<String>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub environment_variables: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_minimized: Option<bool>,
+}
+
+impl CreateSpec {
+    pub fn new(path: impl Into<String>) -> Self {
+        CreateSpec {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProcessSummary {
+    pub name: String,
+    #[serde(default)]
+    pub pid: u64,
+    #[serde(default)]
+    pub owner: String,
+    #[serde(default)]
+    pub command: String,
+    pub started: DateTime<Utc>,
+}
+
+// Process in the guest, exit code and finish time are set once it exited.
+// Exited processes are only kept for a few minutes.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProcessInfo {
+    pub name: String,
+    #[serde(default)]
+    pub owner: String,
+    #[serde(default)]
+    pub command: String,
+    pub started: DateTime<Utc>,
+    pub finished: Option<DateTime<Utc>>,
+    pub exit_code: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ProcessRequest<'a, T: Serialize> {
+    credentials: &'a Credentials,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spec: Option<&'a T>,
+}
+
+impl Vm<'_> {
+    // Start a program in the guest and return its process id without
+    // waiting for it to exit
+    pub async fn start_process(
+        &self,
+        credentials: &Credentials,
+        spec: &CreateSpec,
+    ) -> Result<u64, Error> {
+        self.process_action("", "create", credentials, Some(spec))
+            .await
+    }
+
+    pub async fn processes(&self, credentials: &Credentials) -> Result<Vec<ProcessSummary>, Error> {
+        self.process_action::<(), _>("", "list", credentials, None)
+            .await
+    }
+
+    pub async fn process(&self, credentials: &Credentials, pid: u64) -> Result<ProcessInfo, Error> {
+        let path = format!("/{}", pid);
+        self.process_action::<(), _>(&path, "get", credentials, None)
+            .await
+    }
+
+    // Terminate a process, on Linux with SIGTERM and SIGKILL after a delay
+    pub async fn terminate_process(
+        &self,
+        credentials: &Credentials,
+        pid: u64,
+    ) -> Result<(), Error> {
+        self.session.require(Feature::GuestOperations).await?;
+        let session = self.session.with_api_flavor();
+        let endpoint = self.endpoint(&format!("/guest/processes/{}", pid));
+        let request = session.action(Method::POST, &endpoint, "delete");
+        let body = ProcessRequest::<()> {
+            credentials,
+            spec: None,
+        };
+        session.send_empty(session.json(request, &body)).await
+    }
+
+    // Guest operations take the credentials in the body, so all of them are
+    // POST actions. They are only served with the /api flavor.
+    async fn process_action<T, R>(
+        &self,
+        path: &str,
+        action: &str,
+        credentials: &Credentials,
+        spec: Option<&T>,
+    ) -> Result<R, Error>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        self.session.require(Feature::GuestOperations).await?;
+        let session = self.session.with_api_flavor();
+        let endpoint = self.endpoint(&format!("/guest/processes{}", path));
+        let request = session.action(Method::POST, &endpoint, action);
+        let body = ProcessRequest { credentials, spec };
+        session.send(session.json(request, &body)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cis::transport::MockTransport;
+    use crate::cis::Session;
+    use reqwest::StatusCode;
+    use std::sync::Arc;
+
+    #[test]
+    fn create_request() {
+        let credentials = Credentials::username_password("root", "secret");
+        let mut spec = CreateSpec::new("/bin/sh");
+        spec.arguments = Some("-c 'cloud-init status --wait'".to_owned());
+        spec.environment_variables
+            .insert("LANG".to_owned(), "C".to_owned());
+        let body = ProcessRequest {
+            credentials: &credentials,
+            spec: Some(&spec),
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "credentials": {
+                    "interactive_session": false,
+                    "type": "USERNAME_PASSWORD",
+                    "user_name": "root",
+                    "password": "secret"
+                },
+                "spec": {
+                    "path": "/bin/sh",
+                    "arguments": "-c 'cloud-init status --wait'",
+                    "environment_variables": {"LANG": "C"}
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn processes_use_api_flavor() {
+        let mock = Arc::new(MockTransport::new());
+        // Guest operations need vCenter 7.0 U2 whose /api flavor answers
+        mock.on(
+            Method::GET,
+            "/rest/appliance/system/version",
+            StatusCode::OK,
+            r#"{"value":{"version":"7.0.3.01000"}}"#,
+        );
+        mock.on(
+            Method::GET,
+            "/api/appliance/system/version",
+            StatusCode::OK,
+            r#"{"version":"7.0.3.01000"}"#,
+        );
+        mock.on(
+            Method::POST,
+            "/api/vcenter/vm/vm-42/guest/processes?action=create",
+            StatusCode::CREATED,
+            "4242",
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        let credentials = Credentials::username_password("root", "secret");
+        let pid = Vm::new(&session, "vm-42")
+            .start_process(&credentials, &CreateSpec::new("/bin/true"))
+            .await
+            .expect("start process");
+        assert_eq!(pid, 4242);
+        let requests = mock.requests();
+        assert_eq!(
+            requests[2].url.path(),
+            "/api/vcenter/vm/vm-42/guest/processes"
+        );
+    }
+}
@@ -0,0 +1,5 @@
+// Storage adapters of a virtual machine, disks and CD-ROM devices are
+// attached to them by bus number
+pub mod nvme;
+pub mod sata;
+pub mod scsi;
@@ -0,0 +1,65 @@
+use super::super::device_endpoint;
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Specification of a new virtual NVMe adapter, unset fields are chosen by the server
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bus: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pci_slot_number: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NvmeSummary {
+    #[serde(default)]
+    pub adapter: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NvmeInfo {
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub bus: u32,
+    pub pci_slot_number: Option<u32>,
+}
+
+impl Vm<'_> {
+    pub async fn nvme_adapters(&self) -> Result<Vec<NvmeSummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "adapter/nvme", None));
+        self.session.send(request).await
+    }
+
+    pub async fn nvme_adapter(&self, adapter: &str) -> Result<NvmeInfo, Error> {
+        let request = self.session.request(
+            Method::GET,
+            &device_endpoint(self, "adapter/nvme", Some(adapter)),
+        );
+        self.session.send(request).await
+    }
+
+    // Add a virtual NVMe adapter and return its identifier, requires
+    // virtual hardware version 13 or later
+    pub async fn create_nvme_adapter(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "adapter/nvme", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Remove a virtual NVMe adapter, fails if devices are still attached
+    pub async fn delete_nvme_adapter(&self, adapter: &str) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::DELETE,
+            &device_endpoint(self, "adapter/nvme", Some(adapter)),
+        );
+        self.session.send_empty(request).await
+    }
+}
@@ -0,0 +1,78 @@
+use super::super::device_endpoint;
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Emulated type of a virtual SATA adapter
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SataType {
+    Ahci,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Specification of a new virtual SATA adapter, unset fields are chosen by the server
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub adapter_type: Option<SataType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bus: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pci_slot_number: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SataSummary {
+    #[serde(default)]
+    pub adapter: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SataInfo {
+    #[serde(default)]
+    pub label: String,
+    #[serde(rename = "type")]
+    pub adapter_type: SataType,
+    #[serde(default)]
+    pub bus: u32,
+    pub pci_slot_number: Option<u32>,
+}
+
+impl Vm<'_> {
+    pub async fn sata_adapters(&self) -> Result<Vec<SataSummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "adapter/sata", None));
+        self.session.send(request).await
+    }
+
+    pub async fn sata_adapter(&self, adapter: &str) -> Result<SataInfo, Error> {
+        let request = self.session.request(
+            Method::GET,
+            &device_endpoint(self, "adapter/sata", Some(adapter)),
+        );
+        self.session.send(request).await
+    }
+
+    // Add a virtual SATA adapter and return its identifier
+    pub async fn create_sata_adapter(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "adapter/sata", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Remove a virtual SATA adapter, fails if devices are still attached
+    pub async fn delete_sata_adapter(&self, adapter: &str) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::DELETE,
+            &device_endpoint(self, "adapter/sata", Some(adapter)),
+        );
+        self.session.send_empty(request).await
+    }
+}
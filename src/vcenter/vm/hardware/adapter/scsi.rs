@@ -0,0 +1,141 @@
+use super::super::{device_endpoint, ScsiAddressSpec};
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Emulated type of a virtual SCSI adapter
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScsiType {
+    Buslogic,
+    Lsilogic,
+    Lsilogicsas,
+    Pvscsi,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Bus sharing of a virtual SCSI adapter, virtual shares the disks between
+// virtual machines on the same host and physical across hosts
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Sharing {
+    None,
+    Virtual,
+    Physical,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Specification of a new virtual SCSI adapter, unset fields are chosen by the server
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub adapter_type: Option<ScsiType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bus: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pci_slot_number: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sharing: Option<Sharing>,
+}
+
+// Changes to a virtual SCSI adapter, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sharing: Option<Sharing>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScsiSummary {
+    #[serde(default)]
+    pub adapter: String,
+}
+
+// Virtual SCSI adapter, the address is the bus and the unit of the adapter
+// itself on it
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScsiInfo {
+    #[serde(default)]
+    pub label: String,
+    #[serde(rename = "type")]
+    pub adapter_type: ScsiType,
+    pub scsi: ScsiAddressSpec,
+    pub pci_slot_number: Option<u32>,
+    pub sharing: Sharing,
+}
+
+impl Vm<'_> {
+    pub async fn scsi_adapters(&self) -> Result<Vec<ScsiSummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "adapter/scsi", None));
+        self.session.send(request).await
+    }
+
+    pub async fn scsi_adapter(&self, adapter: &str) -> Result<ScsiInfo, Error> {
+        let request = self.session.request(
+            Method::GET,
+            &device_endpoint(self, "adapter/scsi", Some(adapter)),
+        );
+        self.session.send(request).await
+    }
+
+    // Add a virtual SCSI adapter and return its identifier
+    pub async fn create_scsi_adapter(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "adapter/scsi", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Bus sharing can only be changed while the virtual machine is powered off
+    pub async fn update_scsi_adapter(&self, adapter: &str, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::PATCH,
+            &device_endpoint(self, "adapter/scsi", Some(adapter)),
+        );
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Remove a virtual SCSI adapter, fails if devices are still attached
+    pub async fn delete_scsi_adapter(&self, adapter: &str) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::DELETE,
+            &device_endpoint(self, "adapter/scsi", Some(adapter)),
+        );
+        self.session.send_empty(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CreateSpec, ScsiInfo, ScsiType, Sharing};
+
+    #[test]
+    fn scsi_adapter() {
+        let spec = CreateSpec {
+            adapter_type: Some(ScsiType::Pvscsi),
+            bus: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({"type": "PVSCSI", "bus": 1})
+        );
+        let info: ScsiInfo = serde_json::from_str(
+            r#"{"label":"SCSI controller 1","type":"PVSCSI","scsi":{"bus":1,"unit":7},
+            "pci_slot_number":224,"sharing":"NONE"}"#,
+        )
+        .expect("deserialize");
+        assert_eq!(info.scsi.unit, Some(7));
+        assert_eq!(info.sharing, Sharing::None);
+    }
+}
@@ -0,0 +1,90 @@
+use super::super::device_endpoint;
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Type of a device in the boot order of a virtual machine
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeviceType {
+    Cdrom,
+    Disk,
+    Ethernet,
+    Floppy,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Entry of the boot order of a new virtual machine
+#[derive(Serialize, Debug, Clone)]
+pub struct EntryCreateSpec {
+    #[serde(rename = "type")]
+    pub device_type: DeviceType,
+}
+
+// Entry of the boot order, ethernet entries name a nic and disk entries the
+// disks to try in order
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Entry {
+    #[serde(rename = "type")]
+    pub device_type: DeviceType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disks: Option<Vec<String>>,
+}
+
+impl Entry {
+    pub fn new(device_type: DeviceType) -> Self {
+        Entry {
+            device_type,
+            nic: None,
+            disks: None,
+        }
+    }
+}
+
+// The set call takes the devices as a named field in both flavors
+#[derive(Serialize)]
+struct SetRequest<'a> {
+    devices: &'a [Entry],
+}
+
+impl Vm<'_> {
+    pub async fn boot_devices(&self) -> Result<Vec<Entry>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "boot/device", None));
+        self.session.send(request).await
+    }
+
+    // Replace the boot order, an empty list restores the default order
+    pub async fn set_boot_devices(&self, devices: &[Entry]) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PUT, &device_endpoint(self, "boot/device", None));
+        self.session
+            .send_empty(self.session.json(request, &SetRequest { devices }))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_request() {
+        let mut disk = Entry::new(DeviceType::Disk);
+        disk.disks = Some(vec!["2000".to_owned()]);
+        let devices = [Entry::new(DeviceType::Cdrom), disk];
+        let json = serde_json::to_value(SetRequest { devices: &devices }).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "devices": [{"type": "CDROM"}, {"type": "DISK", "disks": ["2000"]}]
+            })
+        );
+    }
+}
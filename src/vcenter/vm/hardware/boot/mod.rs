@@ -0,0 +1,102 @@
+// Boot devices of a virtual machine
+pub mod device;
+
+use super::device_endpoint;
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Firmware used to boot a virtual machine
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BootType {
+    Bios,
+    Efi,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Protocol used for network boot with EFI firmware
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NetworkProtocol {
+    Ipv4,
+    Ipv6,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Boot configuration of a new virtual machine, delays are given in milliseconds
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub boot_type: Option<BootType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub efi_legacy_boot: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_protocol: Option<NetworkProtocol>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_delay: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enter_setup_mode: Option<bool>,
+}
+
+// Changes to the boot configuration, unset fields are left unchanged. Secure
+// boot is not part of the REST boot service and has to be set through vim25.
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub boot_type: Option<BootType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub efi_legacy_boot: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_protocol: Option<NetworkProtocol>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_delay: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enter_setup_mode: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BootInfo {
+    #[serde(rename = "type")]
+    pub boot_type: BootType,
+    pub efi_legacy_boot: Option<bool>,
+    pub network_protocol: Option<NetworkProtocol>,
+    #[serde(default)]
+    pub delay: u64,
+    #[serde(default)]
+    pub retry: bool,
+    #[serde(default)]
+    pub retry_delay: u64,
+    #[serde(default)]
+    pub enter_setup_mode: bool,
+}
+
+impl Vm<'_> {
+    pub async fn boot(&self) -> Result<BootInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "boot", None));
+        self.session.send(request).await
+    }
+
+    pub async fn update_boot(&self, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PATCH, &device_endpoint(self, "boot", None));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+}
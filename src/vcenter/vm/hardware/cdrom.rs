@@ -0,0 +1,180 @@
+use super::{device_endpoint, ConnectionState, IdeAddressSpec, SataAddressSpec};
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Type of the adapter a virtual CD-ROM device is attached to
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HostBusAdapterType {
+    Ide,
+    Sata,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Type of the media backing a virtual CD-ROM device
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackingType {
+    IsoFile,
+    HostDevice,
+    ClientDevice,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Access mode of a CD-ROM device backed by a client device
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeviceAccessType {
+    Emulation,
+    Passthru,
+    PassthruExclusive,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Media backing of a virtual CD-ROM device
+#[derive(Serialize, Debug, Clone)]
+pub struct BackingSpec {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iso_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_device: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_access_type: Option<DeviceAccessType>,
+}
+
+impl BackingSpec {
+    // ISO image on a datastore, e.g. "[datastore1] iso/install.iso"
+    pub fn iso_file(iso_file: impl Into<String>) -> Self {
+        BackingSpec {
+            backing_type: BackingType::IsoFile,
+            iso_file: Some(iso_file.into()),
+            host_device: None,
+            device_access_type: None,
+        }
+    }
+}
+
+// Specification of a new virtual CD-ROM device, unset fields are chosen by the server
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub adapter_type: Option<HostBusAdapterType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide: Option<IdeAddressSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata: Option<SataAddressSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Changes to a virtual CD-ROM device, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Media backing of a virtual CD-ROM device as reported by the get call
+#[derive(Deserialize, Debug, Clone)]
+pub struct BackingInfo {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    pub iso_file: Option<String>,
+    pub host_device: Option<String>,
+    pub auto_detect: Option<bool>,
+    pub device_access_type: Option<DeviceAccessType>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CdromSummary {
+    #[serde(default)]
+    pub cdrom: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CdromInfo {
+    #[serde(rename = "type")]
+    pub adapter_type: HostBusAdapterType,
+    #[serde(default)]
+    pub label: String,
+    pub ide: Option<IdeAddressSpec>,
+    pub sata: Option<SataAddressSpec>,
+    pub backing: BackingInfo,
+    pub state: ConnectionState,
+    #[serde(default)]
+    pub start_connected: bool,
+    #[serde(default)]
+    pub allow_guest_control: bool,
+}
+
+impl Vm<'_> {
+    pub async fn cdroms(&self) -> Result<Vec<CdromSummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "cdrom", None));
+        self.session.send(request).await
+    }
+
+    pub async fn cdrom(&self, cdrom: &str) -> Result<CdromInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "cdrom", Some(cdrom)));
+        self.session.send(request).await
+    }
+
+    // Add a virtual CD-ROM device and return its identifier
+    pub async fn create_cdrom(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "cdrom", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Changing the backing of a connected device swaps the mounted media
+    pub async fn update_cdrom(&self, cdrom: &str, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PATCH, &device_endpoint(self, "cdrom", Some(cdrom)));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn delete_cdrom(&self, cdrom: &str) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::DELETE, &device_endpoint(self, "cdrom", Some(cdrom)));
+        self.session.send_empty(request).await
+    }
+
+    pub async fn connect_cdrom(&self, cdrom: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "cdrom", Some(cdrom));
+        let request = self.session.path_action(&endpoint, "connect");
+        self.session.send_empty(request).await
+    }
+
+    pub async fn disconnect_cdrom(&self, cdrom: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "cdrom", Some(cdrom));
+        let request = self.session.path_action(&endpoint, "disconnect");
+        self.session.send_empty(request).await
+    }
+}
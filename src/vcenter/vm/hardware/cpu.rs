@@ -0,0 +1,48 @@
+use super::device_endpoint;
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Changes to the CPU configuration of a virtual machine, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cores_per_socket: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hot_add_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hot_remove_enabled: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CpuInfo {
+    #[serde(default)]
+    pub count: u32,
+    #[serde(default)]
+    pub cores_per_socket: u32,
+    #[serde(default)]
+    pub hot_add_enabled: bool,
+    #[serde(default)]
+    pub hot_remove_enabled: bool,
+}
+
+impl Vm<'_> {
+    pub async fn cpu(&self) -> Result<CpuInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "cpu", None));
+        self.session.send(request).await
+    }
+
+    // Changing the count of a powered on virtual machine requires hot add or remove
+    pub async fn update_cpu(&self, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PATCH, &device_endpoint(self, "cpu", None));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+}
@@ -0,0 +1,178 @@
+use super::{device_endpoint, IdeAddressSpec, NvmeAddressSpec, SataAddressSpec, ScsiAddressSpec};
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Type of the adapter a virtual disk is attached to
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HostBusAdapterType {
+    Ide,
+    Scsi,
+    Sata,
+    Nvme,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Type of the backing of a virtual disk
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackingType {
+    VmdkFile,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Existing VMDK file backing a virtual disk, e.g. "[datastore1] vm/vm.vmdk"
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BackingSpec {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    #[serde(default)]
+    pub vmdk_file: String,
+}
+
+impl BackingSpec {
+    pub fn vmdk_file(vmdk_file: impl Into<String>) -> Self {
+        BackingSpec {
+            backing_type: BackingType::VmdkFile,
+            vmdk_file: vmdk_file.into(),
+        }
+    }
+}
+
+// Storage policy of a new VMDK file
+#[derive(Serialize, Debug, Clone)]
+pub struct StoragePolicySpec {
+    pub policy: String,
+}
+
+// Specification of a new VMDK file, the capacity is given in bytes
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct VmdkCreateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_policy: Option<StoragePolicySpec>,
+}
+
+// Specification of a new virtual disk, either backed by an existing VMDK
+// file or a new one. Unset fields are chosen by the server.
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub adapter_type: Option<HostBusAdapterType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide: Option<IdeAddressSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi: Option<ScsiAddressSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata: Option<SataAddressSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nvme: Option<NvmeAddressSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_vmdk: Option<VmdkCreateSpec>,
+}
+
+impl CreateSpec {
+    // Disk backed by a new VMDK file with the given capacity in bytes
+    pub fn new_vmdk(capacity: u64) -> Self {
+        CreateSpec {
+            new_vmdk: Some(VmdkCreateSpec {
+                capacity: Some(capacity),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    // Assign a storage policy to the new VMDK file, see vcenter::storage::policies
+    pub fn storage_policy(mut self, policy: impl Into<String>) -> Self {
+        self.new_vmdk
+            .get_or_insert_with(Default::default)
+            .storage_policy = Some(StoragePolicySpec {
+            policy: policy.into(),
+        });
+        self
+    }
+}
+
+// Changes to a virtual disk, the REST API only supports replacing the backing
+// and can't change the capacity of a disk
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DiskSummary {
+    #[serde(default)]
+    pub disk: String,
+}
+
+// Virtual disk of a virtual machine, the address matching the adapter type
+// is set and the capacity is given in bytes
+#[derive(Deserialize, Debug, Clone)]
+pub struct DiskInfo {
+    #[serde(default)]
+    pub label: String,
+    #[serde(rename = "type")]
+    pub adapter_type: HostBusAdapterType,
+    pub ide: Option<IdeAddressSpec>,
+    pub scsi: Option<ScsiAddressSpec>,
+    pub sata: Option<SataAddressSpec>,
+    pub nvme: Option<NvmeAddressSpec>,
+    pub backing: BackingSpec,
+    pub capacity: Option<u64>,
+}
+
+impl Vm<'_> {
+    pub async fn disks(&self) -> Result<Vec<DiskSummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "disk", None));
+        self.session.send(request).await
+    }
+
+    pub async fn disk(&self, disk: &str) -> Result<DiskInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "disk", Some(disk)));
+        self.session.send(request).await
+    }
+
+    // Add a virtual disk and return its identifier
+    pub async fn create_disk(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "disk", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn update_disk(&self, disk: &str, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PATCH, &device_endpoint(self, "disk", Some(disk)));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Remove a virtual disk, the backing VMDK file is kept
+    pub async fn delete_disk(&self, disk: &str) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::DELETE, &device_endpoint(self, "disk", Some(disk)));
+        self.session.send_empty(request).await
+    }
+}
@@ -0,0 +1,206 @@
+use super::{device_endpoint, ConnectionState};
+use crate::cis::Error;
+use crate::id::NetworkId;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Emulated adapter type of a virtual Ethernet adapter
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EmulationType {
+    E1000,
+    E1000e,
+    Pcnet32,
+    Vmxnet,
+    Vmxnet2,
+    Vmxnet3,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// How the MAC address of a virtual Ethernet adapter is assigned
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MacAddressType {
+    Manual,
+    Generated,
+    Assigned,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Type of the network backing a virtual Ethernet adapter
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackingType {
+    StandardPortgroup,
+    HostDevice,
+    DistributedPortgroup,
+    OpaqueNetwork,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Network backing of a virtual Ethernet adapter, distributed portgroups can
+// be backed by a specific port
+#[derive(Serialize, Debug, Clone)]
+pub struct BackingSpec {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distributed_port: Option<String>,
+}
+
+impl BackingSpec {
+    pub fn new(backing_type: BackingType, network: impl Into<NetworkId>) -> Self {
+        BackingSpec {
+            backing_type,
+            network: Some(network.into()),
+            distributed_port: None,
+        }
+    }
+}
+
+// Specification of a new virtual Ethernet adapter, unset fields are chosen by the server
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub emulation_type: Option<EmulationType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upt_compatibility_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_type: Option<MacAddressType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pci_slot_number: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wake_on_lan_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Changes to a virtual Ethernet adapter, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upt_compatibility_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_type: Option<MacAddressType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wake_on_lan_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Network backing of a virtual Ethernet adapter as reported by the get call
+#[derive(Deserialize, Debug, Clone)]
+pub struct BackingInfo {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    pub network: Option<NetworkId>,
+    pub network_name: Option<String>,
+    pub host_device: Option<String>,
+    pub distributed_switch_uuid: Option<String>,
+    pub distributed_port: Option<String>,
+    pub connection_cookie: Option<i64>,
+    pub opaque_network_type: Option<String>,
+    pub opaque_network_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EthernetSummary {
+    #[serde(default)]
+    pub nic: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EthernetInfo {
+    #[serde(default)]
+    pub label: String,
+    #[serde(rename = "type")]
+    pub emulation_type: EmulationType,
+    pub upt_compatibility_enabled: Option<bool>,
+    pub mac_type: MacAddressType,
+    pub mac_address: Option<String>,
+    pub pci_slot_number: Option<u32>,
+    #[serde(default)]
+    pub wake_on_lan_enabled: bool,
+    pub backing: BackingInfo,
+    pub state: ConnectionState,
+    #[serde(default)]
+    pub start_connected: bool,
+    #[serde(default)]
+    pub allow_guest_control: bool,
+}
+
+impl Vm<'_> {
+    pub async fn nics(&self) -> Result<Vec<EthernetSummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "ethernet", None));
+        self.session.send(request).await
+    }
+
+    pub async fn nic(&self, nic: &str) -> Result<EthernetInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "ethernet", Some(nic)));
+        self.session.send(request).await
+    }
+
+    // Add a virtual Ethernet adapter and return its identifier
+    pub async fn create_nic(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "ethernet", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn update_nic(&self, nic: &str, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PATCH, &device_endpoint(self, "ethernet", Some(nic)));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn delete_nic(&self, nic: &str) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::DELETE,
+            &device_endpoint(self, "ethernet", Some(nic)),
+        );
+        self.session.send_empty(request).await
+    }
+
+    // Connect the adapter of a powered on virtual machine to its network
+    pub async fn connect_nic(&self, nic: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "ethernet", Some(nic));
+        let request = self.session.path_action(&endpoint, "connect");
+        self.session.send_empty(request).await
+    }
+
+    pub async fn disconnect_nic(&self, nic: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "ethernet", Some(nic));
+        let request = self.session.path_action(&endpoint, "disconnect");
+        self.session.send_empty(request).await
+    }
+}
@@ -0,0 +1,173 @@
+use super::{device_endpoint, ConnectionState};
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Type of the media backing a virtual floppy drive
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackingType {
+    ImageFile,
+    HostDevice,
+    ClientDevice,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Media backing of a virtual floppy drive, only the field matching the type is used
+#[derive(Serialize, Debug, Clone)]
+pub struct BackingSpec {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_device: Option<String>,
+}
+
+impl BackingSpec {
+    // Floppy image on a datastore, e.g. "[datastore1] drivers/pvscsi.flp"
+    pub fn image_file(image_file: impl Into<String>) -> Self {
+        BackingSpec {
+            backing_type: BackingType::ImageFile,
+            image_file: Some(image_file.into()),
+            host_device: None,
+        }
+    }
+}
+
+// Specification of a new virtual floppy drive, unset fields are chosen by the server
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Changes to a virtual floppy drive, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Media backing of a virtual floppy drive as reported by the get call
+#[derive(Deserialize, Debug, Clone)]
+pub struct BackingInfo {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    pub image_file: Option<String>,
+    pub host_device: Option<String>,
+    pub auto_detect: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FloppySummary {
+    #[serde(default)]
+    pub floppy: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FloppyInfo {
+    #[serde(default)]
+    pub label: String,
+    pub backing: BackingInfo,
+    pub state: ConnectionState,
+    #[serde(default)]
+    pub start_connected: bool,
+    #[serde(default)]
+    pub allow_guest_control: bool,
+}
+
+impl Vm<'_> {
+    pub async fn floppies(&self) -> Result<Vec<FloppySummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "floppy", None));
+        self.session.send(request).await
+    }
+
+    pub async fn floppy(&self, floppy: &str) -> Result<FloppyInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "floppy", Some(floppy)));
+        self.session.send(request).await
+    }
+
+    // Add a virtual floppy drive and return its identifier
+    pub async fn create_floppy(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "floppy", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Changing the backing of a connected drive swaps the inserted image
+    pub async fn update_floppy(&self, floppy: &str, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::PATCH,
+            &device_endpoint(self, "floppy", Some(floppy)),
+        );
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn delete_floppy(&self, floppy: &str) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::DELETE,
+            &device_endpoint(self, "floppy", Some(floppy)),
+        );
+        self.session.send_empty(request).await
+    }
+
+    pub async fn connect_floppy(&self, floppy: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "floppy", Some(floppy));
+        let request = self.session.path_action(&endpoint, "connect");
+        self.session.send_empty(request).await
+    }
+
+    pub async fn disconnect_floppy(&self, floppy: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "floppy", Some(floppy));
+        let request = self.session.path_action(&endpoint, "disconnect");
+        self.session.send_empty(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BackingSpec, CreateSpec, FloppyInfo};
+    use crate::vcenter::vm::hardware::ConnectionState;
+
+    #[test]
+    fn floppy_image() {
+        let spec = CreateSpec {
+            backing: Some(BackingSpec::image_file("[ds1] drivers/pvscsi.flp")),
+            start_connected: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "backing": {"type": "IMAGE_FILE", "image_file": "[ds1] drivers/pvscsi.flp"},
+                "start_connected": true,
+            })
+        );
+        let info: FloppyInfo = serde_json::from_str(
+            r#"{"label":"Floppy drive 1","backing":{"type":"IMAGE_FILE","image_file":"[ds1] drivers/pvscsi.flp"},
+            "state":"CONNECTED","start_connected":true,"allow_guest_control":true}"#,
+        )
+        .expect("deserialize");
+        assert_eq!(info.state, ConnectionState::Connected);
+    }
+}
@@ -0,0 +1,44 @@
+use super::device_endpoint;
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Changes to the memory configuration of a virtual machine, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(rename = "size_MiB", skip_serializing_if = "Option::is_none")]
+    pub size_mib: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hot_add_enabled: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MemoryInfo {
+    #[serde(default, rename = "size_MiB")]
+    pub size_mib: u64,
+    #[serde(default)]
+    pub hot_add_enabled: bool,
+    #[serde(rename = "hot_add_increment_size_MiB")]
+    pub hot_add_increment_size_mib: Option<u64>,
+    #[serde(rename = "hot_add_limit_MiB")]
+    pub hot_add_limit_mib: Option<u64>,
+}
+
+impl Vm<'_> {
+    pub async fn memory(&self) -> Result<MemoryInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "memory", None));
+        self.session.send(request).await
+    }
+
+    // Growing a powered on virtual machine requires hot add, shrinking requires power off
+    pub async fn update_memory(&self, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PATCH, &device_endpoint(self, "memory", None));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+}
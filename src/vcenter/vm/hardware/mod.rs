@@ -0,0 +1,69 @@
+// Modules for the virtual hardware of a virtual machine
+pub mod adapter;
+pub mod boot;
+pub mod cdrom;
+pub mod cpu;
+pub mod disk;
+pub mod ethernet;
+pub mod floppy;
+pub mod memory;
+pub mod parallel;
+pub mod serial;
+
+use super::Vm;
+
+// Connection state of a removable device
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ConnectionState {
+    Connected,
+    RecoverableError,
+    UnrecoverableError,
+    NotConnected,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Endpoint of a device like "disk" or "ethernet", all devices without id
+fn device_endpoint(vm: &Vm<'_>, device: &str, id: Option<&str>) -> String {
+    match id {
+        Some(id) => vm.endpoint(&format!("/hardware/{}/{}", device, id)),
+        None => vm.endpoint(&format!("/hardware/{}", device)),
+    }
+}
+
+// Address of a device on an IDE adapter, unset fields are chosen by the server
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct IdeAddressSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master: Option<bool>,
+}
+
+// Address of a device on a SCSI adapter, without unit the first free one is used
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ScsiAddressSpec {
+    #[serde(default)]
+    pub bus: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<u32>,
+}
+
+// Address of a device on a SATA adapter, without unit the first free one is used
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SataAddressSpec {
+    #[serde(default)]
+    pub bus: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<u32>,
+}
+
+// Address of a device on an NVMe adapter, without unit the first free one is used
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct NvmeAddressSpec {
+    #[serde(default)]
+    pub bus: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<u32>,
+}
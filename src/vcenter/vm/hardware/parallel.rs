@@ -0,0 +1,143 @@
+use super::{device_endpoint, ConnectionState};
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Type of the backing of a virtual parallel port
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackingType {
+    File,
+    HostDevice,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Backing of a virtual parallel port, only the field matching the type is used
+#[derive(Serialize, Debug, Clone)]
+pub struct BackingSpec {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_device: Option<String>,
+}
+
+impl BackingSpec {
+    // Output to a file on a datastore, e.g. "[datastore1] web-1/parallel.out"
+    pub fn file(file: impl Into<String>) -> Self {
+        BackingSpec {
+            backing_type: BackingType::File,
+            file: Some(file.into()),
+            host_device: None,
+        }
+    }
+}
+
+// Specification of a new virtual parallel port, unset fields are chosen by the server
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Changes to a virtual parallel port, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Backing of a virtual parallel port as reported by the get call
+#[derive(Deserialize, Debug, Clone)]
+pub struct BackingInfo {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    pub file: Option<String>,
+    pub host_device: Option<String>,
+    pub auto_detect: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ParallelSummary {
+    #[serde(default)]
+    pub port: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ParallelInfo {
+    #[serde(default)]
+    pub label: String,
+    pub backing: BackingInfo,
+    pub state: ConnectionState,
+    #[serde(default)]
+    pub start_connected: bool,
+    #[serde(default)]
+    pub allow_guest_control: bool,
+}
+
+impl Vm<'_> {
+    pub async fn parallel_ports(&self) -> Result<Vec<ParallelSummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "parallel", None));
+        self.session.send(request).await
+    }
+
+    pub async fn parallel_port(&self, port: &str) -> Result<ParallelInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "parallel", Some(port)));
+        self.session.send(request).await
+    }
+
+    // Add a virtual parallel port and return its identifier
+    pub async fn create_parallel_port(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "parallel", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn update_parallel_port(&self, port: &str, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::PATCH,
+            &device_endpoint(self, "parallel", Some(port)),
+        );
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn delete_parallel_port(&self, port: &str) -> Result<(), Error> {
+        let request = self.session.request(
+            Method::DELETE,
+            &device_endpoint(self, "parallel", Some(port)),
+        );
+        self.session.send_empty(request).await
+    }
+
+    pub async fn connect_parallel_port(&self, port: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "parallel", Some(port));
+        let request = self.session.path_action(&endpoint, "connect");
+        self.session.send_empty(request).await
+    }
+
+    pub async fn disconnect_parallel_port(&self, port: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "parallel", Some(port));
+        let request = self.session.path_action(&endpoint, "disconnect");
+        self.session.send_empty(request).await
+    }
+}
@@ -0,0 +1,224 @@
+use super::{device_endpoint, ConnectionState};
+use crate::cis::Error;
+use crate::vcenter::vm::Vm;
+use reqwest::Method;
+
+// Type of the backing of a virtual serial port
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackingType {
+    File,
+    HostDevice,
+    PipeServer,
+    PipeClient,
+    NetworkServer,
+    NetworkClient,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Backing of a virtual serial port, only the field matching the type is used.
+// Network locations are URIs like "telnet://:23".
+#[derive(Serialize, Debug, Clone)]
+pub struct BackingSpec {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_device: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pipe: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_rx_loss: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+}
+
+impl BackingSpec {
+    fn new(backing_type: BackingType) -> Self {
+        BackingSpec {
+            backing_type,
+            file: None,
+            host_device: None,
+            pipe: None,
+            no_rx_loss: None,
+            network_location: None,
+            proxy: None,
+        }
+    }
+
+    // Output to a file on a datastore, e.g. "[datastore1] web-1/serial.log"
+    pub fn file(file: impl Into<String>) -> Self {
+        BackingSpec {
+            file: Some(file.into()),
+            ..BackingSpec::new(BackingType::File)
+        }
+    }
+
+    // The virtual machine listens on the URI, e.g. "telnet://:7001" for a
+    // console reachable with telnet on port 7001 of the host
+    pub fn network_server(network_location: impl Into<String>) -> Self {
+        BackingSpec {
+            network_location: Some(network_location.into()),
+            ..BackingSpec::new(BackingType::NetworkServer)
+        }
+    }
+
+    // The virtual machine connects to the URI, e.g. "telnet://console.example.com:7001"
+    pub fn network_client(network_location: impl Into<String>) -> Self {
+        BackingSpec {
+            network_location: Some(network_location.into()),
+            ..BackingSpec::new(BackingType::NetworkClient)
+        }
+    }
+}
+
+// Specification of a new virtual serial port, unset fields are chosen by the server
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CreateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yield_on_poll: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Changes to a virtual serial port, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yield_on_poll: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing: Option<BackingSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_connected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_guest_control: Option<bool>,
+}
+
+// Backing of a virtual serial port as reported by the get call
+#[derive(Deserialize, Debug, Clone)]
+pub struct BackingInfo {
+    #[serde(rename = "type")]
+    pub backing_type: BackingType,
+    pub file: Option<String>,
+    pub host_device: Option<String>,
+    pub auto_detect: Option<bool>,
+    pub pipe: Option<String>,
+    pub no_rx_loss: Option<bool>,
+    pub network_location: Option<String>,
+    pub proxy: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SerialSummary {
+    #[serde(default)]
+    pub port: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SerialInfo {
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub yield_on_poll: bool,
+    pub backing: BackingInfo,
+    pub state: ConnectionState,
+    #[serde(default)]
+    pub start_connected: bool,
+    #[serde(default)]
+    pub allow_guest_control: bool,
+}
+
+impl Vm<'_> {
+    pub async fn serial_ports(&self) -> Result<Vec<SerialSummary>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "serial", None));
+        self.session.send(request).await
+    }
+
+    pub async fn serial_port(&self, port: &str) -> Result<SerialInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &device_endpoint(self, "serial", Some(port)));
+        self.session.send(request).await
+    }
+
+    // Add a virtual serial port and return its identifier
+    pub async fn create_serial_port(&self, spec: &CreateSpec) -> Result<String, Error> {
+        let request = self
+            .session
+            .request(Method::POST, &device_endpoint(self, "serial", None));
+        self.session
+            .send(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn update_serial_port(&self, port: &str, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PATCH, &device_endpoint(self, "serial", Some(port)));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    pub async fn delete_serial_port(&self, port: &str) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::DELETE, &device_endpoint(self, "serial", Some(port)));
+        self.session.send_empty(request).await
+    }
+
+    pub async fn connect_serial_port(&self, port: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "serial", Some(port));
+        let request = self.session.path_action(&endpoint, "connect");
+        self.session.send_empty(request).await
+    }
+
+    pub async fn disconnect_serial_port(&self, port: &str) -> Result<(), Error> {
+        let endpoint = device_endpoint(self, "serial", Some(port));
+        let request = self.session.path_action(&endpoint, "disconnect");
+        self.session.send_empty(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BackingSpec, BackingType, CreateSpec, SerialInfo};
+
+    #[test]
+    fn telnet_serial_port() {
+        let spec = CreateSpec {
+            backing: Some(BackingSpec::network_server("telnet://:7001")),
+            start_connected: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "backing": {"type": "NETWORK_SERVER", "network_location": "telnet://:7001"},
+                "start_connected": true,
+            })
+        );
+        let info: SerialInfo = serde_json::from_str(
+            r#"{"label":"Serial port 1","yield_on_poll":true,"backing":{"type":"NETWORK_SERVER",
+            "network_location":"telnet://:7001","no_rx_loss":false},"state":"NOT_CONNECTED",
+            "start_connected":true,"allow_guest_control":false}"#,
+        )
+        .expect("deserialize");
+        assert_eq!(info.backing.backing_type, BackingType::NetworkServer);
+        assert_eq!(
+            info.backing.network_location.as_deref(),
+            Some("telnet://:7001")
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use super::hardware::{ethernet, parallel, serial};
+use super::Vm;
+use crate::cis::{Error, Session};
+use crate::common::serialize_map;
+use crate::id::{DatastoreId, FolderId, ResourcePoolId, VmId};
+use reqwest::Method;
+use std::collections::HashMap;
+
+// Placement of an instant clone, it always runs on the host of its source
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct InstantClonePlacementSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<FolderId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pool: Option<ResourcePoolId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore: Option<DatastoreId>,
+}
+
+// Instant clone of a powered on virtual machine which shares the memory of
+// its source. Devices are keyed by their identifiers in the source.
+#[derive(Serialize, Debug, Clone)]
+pub struct InstantCloneSpec {
+    pub source: VmId,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<InstantClonePlacementSpec>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub nics_to_update: HashMap<String, ethernet::UpdateSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disconnect_all_nics: Option<bool>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub parallel_ports_to_update: HashMap<String, parallel::UpdateSpec>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub serial_ports_to_update: HashMap<String, serial::UpdateSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bios_uuid: Option<String>,
+}
+
+impl InstantCloneSpec {
+    pub fn new(source: impl Into<VmId>, name: impl Into<String>) -> Self {
+        InstantCloneSpec {
+            source: source.into(),
+            name: name.into(),
+            placement: None,
+            nics_to_update: HashMap::new(),
+            disconnect_all_nics: None,
+            parallel_ports_to_update: HashMap::new(),
+            serial_ports_to_update: HashMap::new(),
+            bios_uuid: None,
+        }
+    }
+}
+
+impl<'a> Vm<'a> {
+    // Create an instant clone (vSphere 7.0 or later) and return a handle to it,
+    // the clone is powered on
+    pub async fn instant_clone(
+        session: &'a Session,
+        spec: &InstantCloneSpec,
+    ) -> Result<Vm<'a>, Error> {
+        let request = session.action(Method::POST, "/vcenter/vm", "instant-clone");
+        let id: VmId = session.send(session.body(request, "spec", spec)).await?;
+        Ok(Vm::new(session, id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InstantCloneSpec;
+    use crate::cis::ApiFlavor;
+    use crate::common::WithFlavor;
+    use crate::vcenter::vm::hardware::ethernet;
+
+    #[test]
+    fn nics_per_flavor() {
+        let mut spec = InstantCloneSpec::new("vm-10", "test-1");
+        spec.nics_to_update.insert(
+            "4000".into(),
+            ethernet::UpdateSpec {
+                start_connected: Some(false),
+                ..Default::default()
+            },
+        );
+        let rest = serde_json::to_value(WithFlavor(ApiFlavor::Rest, &spec)).expect("serialize");
+        assert_eq!(
+            rest["nics_to_update"],
+            serde_json::json!([{"key": "4000", "value": {"start_connected": false}}])
+        );
+        assert!(rest.get("serial_ports_to_update").is_none());
+    }
+}
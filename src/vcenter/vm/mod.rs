@@ -0,0 +1,800 @@
+// Cloning of a virtual machine
+pub mod clone;
+// Console tickets of a virtual machine
+pub mod console;
+// Guest operating system of a virtual machine
+pub mod guest;
+// Virtual hardware of a virtual machine
+pub mod hardware;
+// Instant clones of a running virtual machine
+pub mod instant_clone;
+// PCI passthrough devices and vGPUs of a virtual machine
+#[cfg(feature = "vim25")]
+pub mod pci;
+#[cfg(feature = "vim25")]
+mod reconfig;
+// Relocation of a virtual machine to another host or datastore
+pub mod relocate;
+// Virtual TPM, secure boot and VBS of a virtual machine
+#[cfg(feature = "vim25")]
+pub mod security;
+// Snapshots of a virtual machine
+#[cfg(feature = "vim25")]
+pub mod snapshot;
+// Storage policies of a virtual machine and its disks
+pub mod storage_policy;
+// VMware Tools of a virtual machine
+pub mod tools;
+
+use crate::cis::{Error, ErrorType, Session};
+use crate::common::{deserialize_map, push_query, Query};
+use crate::id::{ClusterId, DatacenterId, DatastoreId, FolderId, HostId, ResourcePoolId, VmId};
+use crate::paging::{Page, PagedStream};
+use crate::vcenter::host;
+use hardware::{adapter, boot, cdrom, cpu, disk, ethernet, floppy, memory};
+use reqwest::Method;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::{delay_for, Instant};
+
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Power state of a virtual machine
+#[derive(Deserialize, Serialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PowerState {
+    #[display(fmt = "POWERED_ON")]
+    PoweredOn,
+    #[display(fmt = "POWERED_OFF")]
+    PoweredOff,
+    #[display(fmt = "SUSPENDED")]
+    Suspended,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    #[display(fmt = "UNKNOWN")]
+    Unknown,
+}
+
+// Summary of a virtual machine as returned by the list call
+#[derive(Deserialize, Debug, Clone)]
+pub struct VmSummary {
+    pub vm: VmId,
+    pub name: String,
+    pub power_state: PowerState,
+    pub cpu_count: Option<u32>,
+    #[serde(rename = "memory_size_MiB")]
+    pub memory_size_mib: Option<u64>,
+}
+
+// Filter for the list call, empty fields match every virtual machine
+#[derive(Debug, Default, Clone)]
+pub struct FilterSpec {
+    pub vms: Vec<VmId>,
+    pub names: Vec<String>,
+    pub folders: Vec<FolderId>,
+    pub datacenters: Vec<DatacenterId>,
+    pub hosts: Vec<HostId>,
+    pub clusters: Vec<ClusterId>,
+    pub resource_pools: Vec<ResourcePoolId>,
+    pub power_states: Vec<PowerState>,
+}
+
+impl FilterSpec {
+    fn query(&self) -> Query {
+        let mut query = Query::new();
+        push_query(&mut query, "vms", &self.vms);
+        push_query(&mut query, "names", &self.names);
+        push_query(&mut query, "folders", &self.folders);
+        push_query(&mut query, "datacenters", &self.datacenters);
+        push_query(&mut query, "hosts", &self.hosts);
+        push_query(&mut query, "clusters", &self.clusters);
+        push_query(&mut query, "resource_pools", &self.resource_pools);
+        push_query(&mut query, "power_states", &self.power_states);
+        query
+    }
+}
+
+// Power state information of a virtual machine
+#[derive(Deserialize, Debug, Clone)]
+pub struct PowerInfo {
+    pub state: PowerState,
+    pub clean_power_off: Option<bool>,
+}
+
+// How a virtual machine was brought down by shutdown_with_timeout_then_poweroff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownOutcome {
+    AlreadyPoweredOff,
+    GuestShutdown,
+    PoweredOff,
+}
+
+// Virtual hardware version and its upgrade settings, e.g. version "VMX_14"
+#[derive(Deserialize, Debug, Clone)]
+pub struct HardwareInfo {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub upgrade_policy: String,
+    pub upgrade_version: Option<String>,
+    #[serde(default)]
+    pub upgrade_status: String,
+}
+
+// Configuration of a virtual machine, devices are keyed by their identifier
+#[derive(Deserialize, Debug, Clone)]
+pub struct VmInfo {
+    pub name: String,
+    #[serde(default, rename = "guest_OS")]
+    pub guest_os: String,
+    pub power_state: PowerState,
+    pub hardware: HardwareInfo,
+    pub boot: boot::BootInfo,
+    #[serde(default)]
+    pub boot_devices: Vec<boot::device::Entry>,
+    pub cpu: cpu::CpuInfo,
+    pub memory: memory::MemoryInfo,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub disks: HashMap<String, disk::DiskInfo>,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub nics: HashMap<String, ethernet::EthernetInfo>,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub cdroms: HashMap<String, cdrom::CdromInfo>,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub floppies: HashMap<String, floppy::FloppyInfo>,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub scsi_adapters: HashMap<String, adapter::scsi::ScsiInfo>,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub sata_adapters: HashMap<String, adapter::sata::SataInfo>,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub nvme_adapters: HashMap<String, adapter::nvme::NvmeInfo>,
+}
+
+// Placement of a virtual machine in the inventory, a new virtual machine
+// requires at least a folder and either a host, cluster or resource pool
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct PlacementSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<FolderId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pool: Option<ResourcePoolId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<HostId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<ClusterId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore: Option<DatastoreId>,
+}
+
+// Specification of a new virtual machine, use VmCreateSpecBuilder to build it
+#[derive(Serialize, Debug, Clone)]
+pub struct VmCreateSpec {
+    #[serde(rename = "guest_OS")]
+    pub guest_os: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub placement: PlacementSpec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hardware_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boot: Option<boot::CreateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub boot_devices: Vec<boot::device::EntryCreateSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<cpu::UpdateSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<memory::UpdateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disks: Vec<disk::CreateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nics: Vec<ethernet::CreateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cdroms: Vec<cdrom::CreateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub floppies: Vec<floppy::CreateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scsi_adapters: Vec<adapter::scsi::CreateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sata_adapters: Vec<adapter::sata::CreateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nvme_adapters: Vec<adapter::nvme::CreateSpec>,
+}
+
+// Registration of an existing virtual machine, its configuration file is
+// given either as datastore path like "[ds1] web-1/web-1.vmx" or as
+// datastore and path relative to it
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct RegisterSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore: Option<DatastoreId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<PlacementSpec>,
+}
+
+impl RegisterSpec {
+    pub fn new(datastore_path: impl Into<String>, placement: PlacementSpec) -> Self {
+        RegisterSpec {
+            datastore_path: Some(datastore_path.into()),
+            placement: Some(placement),
+            ..Default::default()
+        }
+    }
+}
+
+// Builder for the specification of a new virtual machine
+#[derive(Debug, Clone)]
+pub struct VmCreateSpecBuilder {
+    spec: VmCreateSpec,
+}
+
+impl VmCreateSpecBuilder {
+    // The guest OS is given as vSphere identifier, e.g. "RHEL_8_64" or "WINDOWS_9_64"
+    pub fn new(guest_os: impl Into<String>) -> Self {
+        VmCreateSpecBuilder {
+            spec: VmCreateSpec {
+                guest_os: guest_os.into(),
+                name: None,
+                placement: PlacementSpec::default(),
+                hardware_version: None,
+                boot: None,
+                boot_devices: Vec::new(),
+                cpu: None,
+                memory: None,
+                disks: Vec::new(),
+                nics: Vec::new(),
+                cdroms: Vec::new(),
+                floppies: Vec::new(),
+                scsi_adapters: Vec::new(),
+                sata_adapters: Vec::new(),
+                nvme_adapters: Vec::new(),
+            },
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.spec.name = Some(name.into());
+        self
+    }
+
+    pub fn folder(mut self, folder: impl Into<FolderId>) -> Self {
+        self.spec.placement.folder = Some(folder.into());
+        self
+    }
+
+    pub fn resource_pool(mut self, resource_pool: impl Into<ResourcePoolId>) -> Self {
+        self.spec.placement.resource_pool = Some(resource_pool.into());
+        self
+    }
+
+    pub fn host(mut self, host: impl Into<HostId>) -> Self {
+        self.spec.placement.host = Some(host.into());
+        self
+    }
+
+    pub fn cluster(mut self, cluster: impl Into<ClusterId>) -> Self {
+        self.spec.placement.cluster = Some(cluster.into());
+        self
+    }
+
+    pub fn datastore(mut self, datastore: impl Into<DatastoreId>) -> Self {
+        self.spec.placement.datastore = Some(datastore.into());
+        self
+    }
+
+    // Virtual hardware version, e.g. "VMX_14"
+    pub fn hardware_version(mut self, version: impl Into<String>) -> Self {
+        self.spec.hardware_version = Some(version.into());
+        self
+    }
+
+    pub fn cpu_count(mut self, count: u32) -> Self {
+        self.spec.cpu.get_or_insert_with(Default::default).count = Some(count);
+        self
+    }
+
+    pub fn cores_per_socket(mut self, cores: u32) -> Self {
+        self.spec
+            .cpu
+            .get_or_insert_with(Default::default)
+            .cores_per_socket = Some(cores);
+        self
+    }
+
+    pub fn cpu(mut self, cpu: cpu::UpdateSpec) -> Self {
+        self.spec.cpu = Some(cpu);
+        self
+    }
+
+    pub fn memory_mib(mut self, size: u64) -> Self {
+        self.spec
+            .memory
+            .get_or_insert_with(Default::default)
+            .size_mib = Some(size);
+        self
+    }
+
+    pub fn memory(mut self, memory: memory::UpdateSpec) -> Self {
+        self.spec.memory = Some(memory);
+        self
+    }
+
+    pub fn disk(mut self, disk: disk::CreateSpec) -> Self {
+        self.spec.disks.push(disk);
+        self
+    }
+
+    pub fn nic(mut self, nic: ethernet::CreateSpec) -> Self {
+        self.spec.nics.push(nic);
+        self
+    }
+
+    pub fn cdrom(mut self, cdrom: cdrom::CreateSpec) -> Self {
+        self.spec.cdroms.push(cdrom);
+        self
+    }
+
+    pub fn floppy(mut self, floppy: floppy::CreateSpec) -> Self {
+        self.spec.floppies.push(floppy);
+        self
+    }
+
+    // Disks and CD-ROM devices refer to the adapters by their bus number
+    pub fn scsi_adapter(mut self, adapter: adapter::scsi::CreateSpec) -> Self {
+        self.spec.scsi_adapters.push(adapter);
+        self
+    }
+
+    pub fn sata_adapter(mut self, adapter: adapter::sata::CreateSpec) -> Self {
+        self.spec.sata_adapters.push(adapter);
+        self
+    }
+
+    pub fn nvme_adapter(mut self, adapter: adapter::nvme::CreateSpec) -> Self {
+        self.spec.nvme_adapters.push(adapter);
+        self
+    }
+
+    pub fn boot(mut self, boot: boot::CreateSpec) -> Self {
+        self.spec.boot = Some(boot);
+        self
+    }
+
+    // Append a device type to the boot order
+    pub fn boot_device(mut self, device_type: boot::device::DeviceType) -> Self {
+        self.spec
+            .boot_devices
+            .push(boot::device::EntryCreateSpec { device_type });
+        self
+    }
+
+    pub fn build(self) -> VmCreateSpec {
+        self.spec
+    }
+}
+
+// Handle to a single virtual machine of a session
+pub struct Vm<'a> {
+    session: &'a Session,
+    id: VmId,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(session: &'a Session, id: impl Into<VmId>) -> Self {
+        Vm {
+            session,
+            id: id.into(),
+        }
+    }
+
+    pub fn id(&self) -> &VmId {
+        &self.id
+    }
+
+    // Endpoint of the virtual machine, the path is appended to it
+    fn endpoint(&self, path: &str) -> String {
+        format!("/vcenter/vm/{}{}", self.id, path)
+    }
+
+    // Create a virtual machine and return a handle to it
+    pub async fn create(session: &'a Session, spec: &VmCreateSpec) -> Result<Vm<'a>, Error> {
+        let request = session.body(session.request(Method::POST, "/vcenter/vm"), "spec", spec);
+        let id: VmId = session.send(request).await?;
+        Ok(Vm::new(session, id))
+    }
+
+    pub async fn info(&self) -> Result<VmInfo, Error> {
+        let request = self.session.request(Method::GET, &self.endpoint(""));
+        self.session.send(request).await
+    }
+
+    // Delete the virtual machine including its files, fails with
+    // ErrorType::NotAllowedInCurrentState if it is powered on
+    pub async fn delete(&self) -> Result<(), Error> {
+        let request = self.session.request(Method::DELETE, &self.endpoint(""));
+        self.session.send_empty(request).await
+    }
+
+    // Register a virtual machine from its configuration file and return a handle to it
+    pub async fn register(session: &'a Session, spec: &RegisterSpec) -> Result<Vm<'a>, Error> {
+        let request = session.action(Method::POST, "/vcenter/vm", "register");
+        let id: VmId = session.send(session.body(request, "spec", spec)).await?;
+        Ok(Vm::new(session, id))
+    }
+
+    // Remove the virtual machine from the inventory but keep its files, fails
+    // with ErrorType::NotAllowedInCurrentState if it is powered on
+    pub async fn unregister(&self) -> Result<(), Error> {
+        let request = self
+            .session
+            .action(Method::POST, &self.endpoint(""), "unregister");
+        self.session.send_empty(request).await
+    }
+
+    pub async fn power(&self) -> Result<PowerInfo, Error> {
+        let request = self.session.request(Method::GET, &self.endpoint("/power"));
+        self.session.send(request).await
+    }
+
+    // Fails with ErrorType::AlreadyInDesiredState if the virtual machine is already powered on
+    pub async fn power_on(&self) -> Result<(), Error> {
+        self.power_action("start").await
+    }
+
+    // Fails with ErrorType::AlreadyInDesiredState if the virtual machine is already powered off
+    pub async fn power_off(&self) -> Result<(), Error> {
+        self.power_action("stop").await
+    }
+
+    // Fails with ErrorType::AlreadyInDesiredState if the virtual machine is already suspended
+    pub async fn suspend(&self) -> Result<(), Error> {
+        self.power_action("suspend").await
+    }
+
+    pub async fn reset(&self) -> Result<(), Error> {
+        self.power_action("reset").await
+    }
+
+    // Ask the guest operating system to shut down, requires VMware Tools. The
+    // call returns once the request is issued, not when the guest is down.
+    pub async fn shutdown_guest(&self) -> Result<(), Error> {
+        self.guest_power_action("shutdown").await
+    }
+
+    // Ask the guest operating system to reboot, requires VMware Tools
+    pub async fn reboot_guest(&self) -> Result<(), Error> {
+        self.guest_power_action("reboot").await
+    }
+
+    // Shut down the guest and wait up to the timeout for the virtual machine to
+    // be powered off, then power it off. A guest without running VMware
+    // Tools or a suspended virtual machine is powered off right away.
+    pub async fn shutdown_with_timeout_then_poweroff(
+        &self,
+        timeout: Duration,
+    ) -> Result<ShutdownOutcome, Error> {
+        let deadline = Instant::now() + timeout;
+        match self.power().await?.state {
+            PowerState::PoweredOff => return Ok(ShutdownOutcome::AlreadyPoweredOff),
+            PowerState::Suspended => return self.hard_power_off().await,
+            PowerState::PoweredOn | PowerState::Unknown => {}
+        }
+        match self.shutdown_guest().await {
+            Ok(()) => {}
+            Err(err)
+                if err.error_type() == Some(&ErrorType::ServiceUnavailable)
+                    || err.error_type() == Some(&ErrorType::NotAllowedInCurrentState) =>
+            {
+                return self.hard_power_off().await
+            }
+            Err(err) => return Err(err),
+        }
+        loop {
+            if self.power().await?.state == PowerState::PoweredOff {
+                return Ok(ShutdownOutcome::GuestShutdown);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return self.hard_power_off().await;
+            }
+            delay_for(SHUTDOWN_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    // Power off, the guest may have finished its shutdown in the meantime
+    async fn hard_power_off(&self) -> Result<ShutdownOutcome, Error> {
+        match self.power_off().await {
+            Ok(()) => Ok(ShutdownOutcome::PoweredOff),
+            Err(err) if err.error_type() == Some(&ErrorType::AlreadyInDesiredState) => {
+                Ok(ShutdownOutcome::GuestShutdown)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn guest_power_action(&self, action: &str) -> Result<(), Error> {
+        let request = self
+            .session
+            .action(Method::POST, &self.endpoint("/guest/power"), action);
+        self.session.send_empty(request).await
+    }
+
+    async fn power_action(&self, action: &str) -> Result<(), Error> {
+        let request = self.session.path_action(&self.endpoint("/power"), action);
+        self.session.send_empty(request).await
+    }
+}
+
+// List the virtual machines matching the filter, fails with
+// ErrorType::UnableToAllocateResource if more than 4000 match, see list_stream
+pub async fn list(session: &Session, filter: &FilterSpec) -> Result<Vec<VmSummary>, Error> {
+    let request = session.list("/vcenter/vm", &filter.query());
+    session.send(request).await
+}
+
+// Cursor of list_stream, the virtual machines are listed per host if there
+// are too many for a single list call
+enum ListCursor {
+    All,
+    Hosts(Vec<HostId>),
+}
+
+// Stream the virtual machines matching the filter without the limit of the
+// list call. If more than 4000 match, they are listed host by host.
+pub fn list_stream<'a>(session: &'a Session, filter: &FilterSpec) -> PagedStream<'a, VmSummary> {
+    let filter = filter.clone();
+    PagedStream::new(ListCursor::All, move |cursor| {
+        let filter = filter.clone();
+        async move {
+            match cursor {
+                ListCursor::All => match list(session, &filter).await {
+                    Ok(items) => Ok(Page { items, next: None }),
+                    Err(err) if err.error_type() == Some(&ErrorType::UnableToAllocateResource) => {
+                        let hosts = if filter.hosts.is_empty() {
+                            host::list(session, &Default::default())
+                                .await?
+                                .into_iter()
+                                .map(|summary| summary.host)
+                                .collect()
+                        } else {
+                            filter.hosts
+                        };
+                        Ok(Page {
+                            items: Vec::new(),
+                            next: Some(ListCursor::Hosts(hosts)),
+                        })
+                    }
+                    Err(err) => Err(err),
+                },
+                ListCursor::Hosts(mut hosts) => {
+                    let host = match hosts.pop() {
+                        Some(host) => host,
+                        None => {
+                            return Ok(Page {
+                                items: Vec::new(),
+                                next: None,
+                            })
+                        }
+                    };
+                    let filter = FilterSpec {
+                        hosts: vec![host],
+                        ..filter
+                    };
+                    let items = list(session, &filter).await?;
+                    let next = if hosts.is_empty() {
+                        None
+                    } else {
+                        Some(ListCursor::Hosts(hosts))
+                    };
+                    Ok(Page { items, next })
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hardware::{disk, ethernet};
+    use super::{
+        FilterSpec, HardwareInfo, PlacementSpec, PowerState, RegisterSpec, ShutdownOutcome, Vm,
+        VmCreateSpecBuilder, VmInfo, VmSummary,
+    };
+    use crate::cis::transport::MockTransport;
+    use crate::cis::Session;
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn filter_query() {
+        let filter = FilterSpec {
+            names: vec!["web-1".into(), "web-2".into()],
+            power_states: vec![PowerState::PoweredOn],
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.query(),
+            vec![
+                ("names", "web-1".to_string()),
+                ("names", "web-2".to_string()),
+                ("power_states", "POWERED_ON".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn deserialize_summary() {
+        let summary: VmSummary = serde_json::from_str(
+            r#"{"vm":"vm-42","name":"web-1","power_state":"POWERED_OFF","cpu_count":2,"memory_size_MiB":4096}"#,
+        )
+        .expect("deserialize");
+        assert_eq!(summary.vm, "vm-42");
+        assert_eq!(summary.power_state, PowerState::PoweredOff);
+        assert_eq!(summary.memory_size_mib, Some(4096));
+    }
+
+    #[cfg(not(feature = "strict"))]
+    #[test]
+    fn deserialize_unknown_power_state() {
+        let summary: VmSummary =
+            serde_json::from_str(r#"{"vm":"vm-42","name":"web-1","power_state":"HIBERNATED"}"#)
+                .expect("deserialize");
+        assert_eq!(summary.power_state, PowerState::Unknown);
+        assert_eq!(summary.power_state.to_string(), "UNKNOWN");
+    }
+
+    #[test]
+    fn deserialize_missing_fields() {
+        let hardware: HardwareInfo =
+            serde_json::from_str(r#"{"version":"VMX_19"}"#).expect("deserialize");
+        assert_eq!(hardware.version, "VMX_19");
+        assert_eq!(hardware.upgrade_status, "");
+    }
+
+    #[test]
+    fn deserialize_info_with_device_pairs() {
+        let info: VmInfo = serde_json::from_str(
+            r#"{
+                "name": "web-1", "guest_OS": "OTHER_LINUX_64", "power_state": "POWERED_ON",
+                "hardware": {"version": "VMX_14", "upgrade_policy": "NEVER", "upgrade_status": "NONE"},
+                "boot": {"type": "BIOS", "delay": 0, "retry": false, "retry_delay": 10000, "enter_setup_mode": false},
+                "boot_devices": [],
+                "cpu": {"count": 2, "cores_per_socket": 1, "hot_add_enabled": false, "hot_remove_enabled": false},
+                "memory": {"size_MiB": 2048, "hot_add_enabled": false},
+                "disks": [{"key": "2000", "value": {
+                    "label": "Hard disk 1", "type": "SCSI", "scsi": {"bus": 0, "unit": 0},
+                    "backing": {"type": "VMDK_FILE", "vmdk_file": "[ds1] web-1/web-1.vmdk"},
+                    "capacity": 17179869184
+                }}],
+                "nics": [],
+                "cdroms": []
+            }"#,
+        )
+        .expect("deserialize");
+        assert_eq!(info.cpu.count, 2);
+        assert_eq!(info.disks["2000"].capacity, Some(17_179_869_184));
+        assert!(info.nics.is_empty());
+    }
+
+    #[test]
+    fn create_spec() {
+        let spec = VmCreateSpecBuilder::new("OTHER_LINUX_64")
+            .name("web-1")
+            .folder("group-v3")
+            .cluster("domain-c7")
+            .cpu_count(2)
+            .memory_mib(2048)
+            .disk(disk::CreateSpec::new_vmdk(16 * 1024 * 1024 * 1024))
+            .nic(ethernet::CreateSpec {
+                emulation_type: Some(ethernet::EmulationType::Vmxnet3),
+                backing: Some(ethernet::BackingSpec::new(
+                    ethernet::BackingType::StandardPortgroup,
+                    "network-11",
+                )),
+                ..Default::default()
+            })
+            .build();
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "guest_OS": "OTHER_LINUX_64",
+                "name": "web-1",
+                "placement": {"folder": "group-v3", "cluster": "domain-c7"},
+                "cpu": {"count": 2},
+                "memory": {"size_MiB": 2048},
+                "disks": [{"new_vmdk": {"capacity": 17179869184u64}}],
+                "nics": [{
+                    "type": "VMXNET3",
+                    "backing": {"type": "STANDARD_PORTGROUP", "network": "network-11"}
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn register_spec() {
+        let placement = PlacementSpec {
+            folder: Some("group-v3".into()),
+            cluster: Some("domain-c8".into()),
+            ..Default::default()
+        };
+        let spec = RegisterSpec::new("[ds1] web-1/web-1.vmx", placement);
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "datastore_path": "[ds1] web-1/web-1.vmx",
+                "placement": {"folder": "group-v3", "cluster": "domain-c8"}
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn register_returns_vm() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::POST,
+            "/rest/vcenter/vm?action=register",
+            StatusCode::OK,
+            r#"{"value":"vm-77"}"#,
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock)
+            .build()
+            .expect("build");
+        let spec = RegisterSpec::new("[ds1] web-1/web-1.vmx", PlacementSpec::default());
+        let vm = Vm::register(&session, &spec).await.expect("register");
+        assert_eq!(vm.id(), "vm-77");
+    }
+
+    #[tokio::test]
+    async fn shutdown_falls_back_to_power_off() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/vm/vm-42/power",
+            StatusCode::OK,
+            r#"{"value":{"state":"POWERED_ON"}}"#,
+        );
+        mock.on(
+            Method::POST,
+            "/rest/vcenter/vm/vm-42/guest/power?action=shutdown",
+            StatusCode::OK,
+            "",
+        );
+        mock.on(
+            Method::POST,
+            "/rest/vcenter/vm/vm-42/power/stop",
+            StatusCode::OK,
+            "",
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        let outcome = Vm::new(&session, "vm-42")
+            .shutdown_with_timeout_then_poweroff(Duration::from_millis(0))
+            .await
+            .expect("shutdown");
+        assert_eq!(outcome, ShutdownOutcome::PoweredOff);
+        let paths: Vec<_> = mock
+            .requests()
+            .iter()
+            .map(|request| request.url.path().to_owned())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/rest/vcenter/vm/vm-42/power",
+                "/rest/vcenter/vm/vm-42/guest/power",
+                "/rest/vcenter/vm/vm-42/power",
+                "/rest/vcenter/vm/vm-42/power/stop",
+            ]
+        );
+    }
+}
@@ -0,0 +1,318 @@
+// PCI passthrough devices and vGPUs of a virtual machine. The REST API has
+// no PCI devices, they are changed through ReconfigVM_Task of the vim25 SOAP
+// API. The virtual machine has to be powered off.
+use super::reconfig::{reconfigure, Operation};
+use super::snapshot::vm_reference;
+use crate::id::VmId;
+use crate::vim25::{Client, Error};
+
+// Key of a new device, vCenter assigns the real one
+const NEW_DEVICE_KEY: i32 = -100;
+
+// Backing of a PCI passthrough device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PciBacking {
+    // vGPU with a profile of the host, e.g. "grid_t4-8q"
+    Vgpu(String),
+    // Dynamic DirectPath to any host device with the vendor and device id,
+    // the virtual machine is placed on a host with a free one
+    Dynamic {
+        vendor_id: u16,
+        device_id: u16,
+        custom_label: Option<String>,
+    },
+    // DirectPath to a device of a host by its PCI address, the device id is
+    // hexadecimal like "1eb8" and the system id the one the config target of
+    // the host reports for the device
+    DirectPath {
+        id: String,
+        device_name: String,
+        device_id: String,
+        system_id: String,
+        vendor_id: u16,
+    },
+    // Other backings with their vim25 type
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PciDevice {
+    pub key: i32,
+    pub label: String,
+    pub backing: PciBacking,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct AllowedDevice {
+    vendor_id: i32,
+    device_id: i32,
+}
+
+// Polymorphic wire format of the backings, the SOAP API requires the fields
+// in this order
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RawBacking {
+    #[serde(rename(serialize = "@xsi:type", deserialize = "@type"))]
+    backing_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vgpu: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vendor_id: Option<i16>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allowed_device: Vec<AllowedDevice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    custom_label: Option<String>,
+}
+
+impl From<&PciBacking> for RawBacking {
+    fn from(backing: &PciBacking) -> Self {
+        let mut raw = RawBacking {
+            backing_type: String::new(),
+            device_name: None,
+            vgpu: None,
+            id: None,
+            device_id: None,
+            system_id: None,
+            vendor_id: None,
+            allowed_device: Vec::new(),
+            custom_label: None,
+        };
+        match backing {
+            PciBacking::Vgpu(profile) => {
+                raw.backing_type = "VirtualPCIPassthroughVmiopBackingInfo".to_owned();
+                raw.vgpu = Some(profile.clone());
+            }
+            PciBacking::Dynamic {
+                vendor_id,
+                device_id,
+                custom_label,
+            } => {
+                raw.backing_type = "VirtualPCIPassthroughDynamicBackingInfo".to_owned();
+                raw.device_name = Some(String::new());
+                raw.allowed_device = vec![AllowedDevice {
+                    vendor_id: i32::from(*vendor_id),
+                    device_id: i32::from(*device_id),
+                }];
+                raw.custom_label = custom_label.clone();
+            }
+            PciBacking::DirectPath {
+                id,
+                device_name,
+                device_id,
+                system_id,
+                vendor_id,
+            } => {
+                raw.backing_type = "VirtualPCIPassthroughDeviceBackingInfo".to_owned();
+                raw.device_name = Some(device_name.clone());
+                raw.id = Some(id.clone());
+                raw.device_id = Some(device_id.clone());
+                raw.system_id = Some(system_id.clone());
+                // xsd:short on the wire
+                raw.vendor_id = Some(*vendor_id as i16);
+            }
+            PciBacking::Other(backing_type) => raw.backing_type = backing_type.clone(),
+        }
+        raw
+    }
+}
+
+impl From<RawBacking> for PciBacking {
+    fn from(raw: RawBacking) -> Self {
+        match raw.backing_type.as_str() {
+            "VirtualPCIPassthroughVmiopBackingInfo" => {
+                PciBacking::Vgpu(raw.vgpu.unwrap_or_default())
+            }
+            "VirtualPCIPassthroughDynamicBackingInfo" => {
+                let allowed = raw.allowed_device.into_iter().next().unwrap_or_default();
+                PciBacking::Dynamic {
+                    vendor_id: allowed.vendor_id as u16,
+                    device_id: allowed.device_id as u16,
+                    custom_label: raw.custom_label,
+                }
+            }
+            "VirtualPCIPassthroughDeviceBackingInfo" => PciBacking::DirectPath {
+                id: raw.id.unwrap_or_default(),
+                device_name: raw.device_name.unwrap_or_default(),
+                device_id: raw.device_id.unwrap_or_default(),
+                system_id: raw.system_id.unwrap_or_default(),
+                vendor_id: raw.vendor_id.unwrap_or_default() as u16,
+            },
+            other => PciBacking::Other(other.to_owned()),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Description {
+    label: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RawDevice {
+    #[serde(rename(serialize = "@xsi:type", deserialize = "@type"))]
+    device_type: String,
+    key: i32,
+    #[serde(skip_serializing)]
+    device_info: Option<Description>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backing: Option<RawBacking>,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfVirtualDevice {
+    #[serde(default, rename = "VirtualDevice")]
+    devices: Vec<RawDevice>,
+}
+
+#[derive(Serialize)]
+struct DeviceChange {
+    operation: Operation,
+    device: RawDevice,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigSpec {
+    device_change: DeviceChange,
+    // Passthrough requires all memory to be reserved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_reservation_locked_to_max: Option<bool>,
+}
+
+// PCI passthrough devices and vGPUs of the virtual machine
+pub async fn list(client: &Client<'_>, vm: &VmId) -> Result<Vec<PciDevice>, Error> {
+    let devices: Option<ArrayOfVirtualDevice> = client
+        .retrieve_property(&vm_reference(vm), "config.hardware.device")
+        .await?;
+    Ok(pci_devices(devices.unwrap_or_default()))
+}
+
+// Add a vGPU or passthrough device and lock the memory reservation to the
+// memory size, which passthrough requires
+pub async fn add(client: &Client<'_>, vm: &VmId, backing: &PciBacking) -> Result<(), Error> {
+    let spec = ConfigSpec {
+        device_change: DeviceChange {
+            operation: Operation::Add,
+            device: RawDevice {
+                device_type: "VirtualPCIPassthrough".to_owned(),
+                key: NEW_DEVICE_KEY,
+                device_info: None,
+                backing: Some(backing.into()),
+            },
+        },
+        memory_reservation_locked_to_max: Some(true),
+    };
+    reconfigure(client, vm, &spec).await
+}
+
+// Remove a device by its key, the memory reservation is left unchanged
+pub async fn remove(client: &Client<'_>, vm: &VmId, key: i32) -> Result<(), Error> {
+    let spec = ConfigSpec {
+        device_change: DeviceChange {
+            operation: Operation::Remove,
+            device: RawDevice {
+                device_type: "VirtualPCIPassthrough".to_owned(),
+                key,
+                device_info: None,
+                backing: None,
+            },
+        },
+        memory_reservation_locked_to_max: None,
+    };
+    reconfigure(client, vm, &spec).await
+}
+
+fn pci_devices(devices: ArrayOfVirtualDevice) -> Vec<PciDevice> {
+    devices
+        .devices
+        .into_iter()
+        .filter(|device| device.device_type == "VirtualPCIPassthrough")
+        .map(|device| PciDevice {
+            key: device.key,
+            label: device
+                .device_info
+                .map(|info| info.label)
+                .unwrap_or_default(),
+            backing: device
+                .backing
+                .map(PciBacking::from)
+                .unwrap_or_else(|| PciBacking::Other(String::new())),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reconfig::ReconfigRequest;
+    use super::*;
+    use crate::vim25::envelope;
+
+    #[test]
+    fn list_pci_devices() {
+        let xml = r#"<val xsi:type="ArrayOfVirtualDevice">
+            <VirtualDevice xsi:type="VirtualIDEController"><key>200</key><deviceInfo><label>IDE 0</label>
+            <summary>IDE 0</summary></deviceInfo><busNumber>0</busNumber></VirtualDevice>
+            <VirtualDevice xsi:type="VirtualPCIPassthrough"><key>13000</key><deviceInfo><label>PCI device 0</label>
+            <summary>NVIDIA GRID vGPU grid_t4-8q</summary></deviceInfo>
+            <backing xsi:type="VirtualPCIPassthroughVmiopBackingInfo"><vgpu>grid_t4-8q</vgpu></backing>
+            <controllerKey>100</controllerKey><unitNumber>18</unitNumber></VirtualDevice>
+            <VirtualDevice xsi:type="VirtualPCIPassthrough"><key>13001</key><deviceInfo><label>PCI device 1</label>
+            <summary>Dynamic DirectPath</summary></deviceInfo>
+            <backing xsi:type="VirtualPCIPassthroughDynamicBackingInfo"><deviceName></deviceName>
+            <allowedDevice><vendorId>4318</vendorId><deviceId>7864</deviceId></allowedDevice>
+            <customLabel>t4</customLabel></backing></VirtualDevice></val>"#;
+        let devices = pci_devices(quick_xml::de::from_str(xml).unwrap());
+        assert_eq!(devices.len(), 2);
+        assert_eq!(
+            devices[0].backing,
+            PciBacking::Vgpu("grid_t4-8q".to_owned())
+        );
+        assert_eq!(
+            devices[1].backing,
+            PciBacking::Dynamic {
+                vendor_id: 0x10de,
+                device_id: 0x1eb8,
+                custom_label: Some("t4".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn add_vgpu_envelope() {
+        let vm = vm_reference(&VmId::new("vm-42"));
+        let spec = ConfigSpec {
+            device_change: DeviceChange {
+                operation: Operation::Add,
+                device: RawDevice {
+                    device_type: "VirtualPCIPassthrough".to_owned(),
+                    key: NEW_DEVICE_KEY,
+                    device_info: None,
+                    backing: Some((&PciBacking::Vgpu("grid_t4-8q".to_owned())).into()),
+                },
+            },
+            memory_reservation_locked_to_max: Some(true),
+        };
+        let request = ReconfigRequest {
+            this: &vm,
+            spec: &spec,
+        };
+        let xml = envelope("ReconfigVM_Task", &request).unwrap();
+        assert!(xml.contains(
+            "<spec><deviceChange><operation>add</operation>\
+             <device xsi:type=\"VirtualPCIPassthrough\"><key>-100</key>\
+             <backing xsi:type=\"VirtualPCIPassthroughVmiopBackingInfo\"><vgpu>grid_t4-8q</vgpu></backing>\
+             </device></deviceChange><memoryReservationLockedToMax>true</memoryReservationLockedToMax></spec>"
+        ));
+    }
+}
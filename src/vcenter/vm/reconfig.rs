@@ -0,0 +1,42 @@
+// Reconfiguration of a virtual machine through ReconfigVM_Task of the vim25
+// SOAP API, for settings missing in the REST API
+use super::snapshot::vm_reference;
+use crate::id::VmId;
+use crate::vim25::{Client, Error, ManagedObjectReference};
+use serde::de::IgnoredAny;
+use serde::Serialize;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Operation of a device change
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub(super) enum Operation {
+    Add,
+    Remove,
+}
+
+// The spec is a VirtualMachineConfigSpec, whose fields have to be in the
+// order of the SOAP API
+#[derive(Serialize)]
+pub(super) struct ReconfigRequest<'a, T> {
+    #[serde(rename = "_this")]
+    pub(super) this: &'a ManagedObjectReference,
+    pub(super) spec: &'a T,
+}
+
+// Reconfigure the virtual machine and wait until it is done
+pub(super) async fn reconfigure<T: Serialize>(
+    client: &Client<'_>,
+    vm: &VmId,
+    spec: &T,
+) -> Result<(), Error> {
+    let vm = vm_reference(vm);
+    let request = ReconfigRequest { this: &vm, spec };
+    let task = client.call("ReconfigVM_Task", &request).await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
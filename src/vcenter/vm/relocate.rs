@@ -0,0 +1,92 @@
+use super::{PlacementSpec, Vm};
+use crate::cis::Error;
+use crate::common::serialize_map;
+use crate::id::{DatastoreId, HostId};
+use reqwest::Method;
+use std::collections::HashMap;
+
+// Target datastore of a disk of the relocated virtual machine
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct DiskRelocateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore: Option<DatastoreId>,
+}
+
+// Target of a relocation, unset placement fields keep the current placement.
+// Disks are keyed by their identifiers and default to the datastore of the
+// placement.
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct RelocateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<PlacementSpec>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub disks: HashMap<String, DiskRelocateSpec>,
+}
+
+impl RelocateSpec {
+    // Move the virtual machine to another host (vMotion)
+    pub fn host(host: impl Into<HostId>) -> Self {
+        RelocateSpec {
+            placement: Some(PlacementSpec {
+                host: Some(host.into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    // Move all files of the virtual machine to another datastore (storage vMotion)
+    pub fn datastore(datastore: impl Into<DatastoreId>) -> Self {
+        RelocateSpec {
+            placement: Some(PlacementSpec {
+                datastore: Some(datastore.into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    // Move a single disk to another datastore
+    pub fn disk(mut self, disk: impl Into<String>, datastore: impl Into<DatastoreId>) -> Self {
+        self.disks.insert(
+            disk.into(),
+            DiskRelocateSpec {
+                datastore: Some(datastore.into()),
+            },
+        );
+        self
+    }
+}
+
+impl Vm<'_> {
+    // Relocate the virtual machine to another host, cluster, resource pool or
+    // datastore (vSphere 7.0 or later), it returns once the relocation finished
+    pub async fn relocate(&self, spec: &RelocateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .action(Method::POST, &self.endpoint(""), "relocate");
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RelocateSpec;
+
+    #[test]
+    fn relocate_spec() {
+        let spec = RelocateSpec::host("host-21").disk("2001", "datastore-12");
+        assert_eq!(
+            serde_json::to_value(&spec).expect("serialize"),
+            serde_json::json!({
+                "placement": {"host": "host-21"},
+                "disks": {"2001": {"datastore": "datastore-12"}}
+            })
+        );
+    }
+}
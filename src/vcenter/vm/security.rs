@@ -0,0 +1,257 @@
+// Virtual TPM, secure boot and virtualization based security (VBS) of a
+// virtual machine, e.g. for Windows 11 guests which need EFI firmware with
+// secure boot and a TPM. The virtual machine has to be powered off and a
+// virtual TPM requires a key provider in vCenter.
+use super::reconfig::{reconfigure, Operation};
+use super::snapshot::vm_reference;
+use crate::id::VmId;
+use crate::vim25::{Client, Error};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Firmware {
+    Bios,
+    Efi,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecuritySettings {
+    pub firmware: Firmware,
+    pub secure_boot: bool,
+    pub vbs: bool,
+    // Virtual IOMMU (Intel VT-d)
+    pub iommu: bool,
+    // Hardware virtualization exposed to the guest
+    pub nested_hv: bool,
+    pub tpm: bool,
+}
+
+// Changes to the settings, unset fields are left unchanged
+#[derive(Debug, Default, Clone)]
+pub struct UpdateSpec {
+    pub firmware: Option<Firmware>,
+    pub secure_boot: Option<bool>,
+    pub vbs: Option<bool>,
+    pub iommu: Option<bool>,
+    pub nested_hv: Option<bool>,
+}
+
+impl UpdateSpec {
+    // EFI firmware with secure boot
+    pub fn secure_boot() -> Self {
+        UpdateSpec {
+            firmware: Some(Firmware::Efi),
+            secure_boot: Some(true),
+            ..Default::default()
+        }
+    }
+
+    // VBS with the settings it requires
+    pub fn vbs() -> Self {
+        UpdateSpec {
+            vbs: Some(true),
+            iommu: Some(true),
+            nested_hv: Some(true),
+            ..UpdateSpec::secure_boot()
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct BootOptions {
+    efi_secure_boot_enabled: Option<bool>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct FlagInfo {
+    vvtd_enabled: Option<bool>,
+    vbs_enabled: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Device {
+    #[serde(rename(serialize = "@xsi:type", deserialize = "@type"))]
+    device_type: String,
+    key: i32,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfVirtualDevice {
+    #[serde(default, rename = "VirtualDevice")]
+    devices: Vec<Device>,
+}
+
+#[derive(Serialize)]
+struct DeviceChange {
+    operation: Operation,
+    device: Device,
+}
+
+// Wire formats of the specs, the SOAP API requires the fields in this order
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlagSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vvtd_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vbs_enabled: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BootOptionsSpec {
+    efi_secure_boot_enabled: bool,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ConfigSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flags: Option<FlagSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_change: Option<DeviceChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    boot_options: Option<BootOptionsSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    firmware: Option<Firmware>,
+    #[serde(rename = "nestedHVEnabled", skip_serializing_if = "Option::is_none")]
+    nested_hv_enabled: Option<bool>,
+}
+
+impl From<&UpdateSpec> for ConfigSpec {
+    fn from(spec: &UpdateSpec) -> Self {
+        let flags = if spec.iommu.is_some() || spec.vbs.is_some() {
+            Some(FlagSpec {
+                vvtd_enabled: spec.iommu,
+                vbs_enabled: spec.vbs,
+            })
+        } else {
+            None
+        };
+        ConfigSpec {
+            flags,
+            device_change: None,
+            boot_options: spec
+                .secure_boot
+                .map(|efi_secure_boot_enabled| BootOptionsSpec {
+                    efi_secure_boot_enabled,
+                }),
+            firmware: spec.firmware,
+            nested_hv_enabled: spec.nested_hv,
+        }
+    }
+}
+
+const TPM_TYPE: &str = "VirtualTPM";
+
+pub async fn settings(client: &Client<'_>, vm: &VmId) -> Result<SecuritySettings, Error> {
+    let vm = vm_reference(vm);
+    let firmware: Option<Firmware> = client.retrieve_property(&vm, "config.firmware").await?;
+    let boot_options: Option<BootOptions> =
+        client.retrieve_property(&vm, "config.bootOptions").await?;
+    let flags: Option<FlagInfo> = client.retrieve_property(&vm, "config.flags").await?;
+    let nested_hv: Option<bool> = client
+        .retrieve_property(&vm, "config.nestedHVEnabled")
+        .await?;
+    let devices: Option<ArrayOfVirtualDevice> = client
+        .retrieve_property(&vm, "config.hardware.device")
+        .await?;
+    let flags = flags.unwrap_or_default();
+    Ok(SecuritySettings {
+        firmware: firmware.unwrap_or(Firmware::Bios),
+        secure_boot: boot_options
+            .unwrap_or_default()
+            .efi_secure_boot_enabled
+            .unwrap_or(false),
+        vbs: flags.vbs_enabled.unwrap_or(false),
+        iommu: flags.vvtd_enabled.unwrap_or(false),
+        nested_hv: nested_hv.unwrap_or(false),
+        tpm: tpm_key(devices.unwrap_or_default()).is_some(),
+    })
+}
+
+pub async fn update(client: &Client<'_>, vm: &VmId, spec: &UpdateSpec) -> Result<(), Error> {
+    reconfigure(client, vm, &ConfigSpec::from(spec)).await
+}
+
+// Add a virtual TPM, the virtual machine needs EFI firmware and its files
+// are encrypted with the key provider
+pub async fn add_tpm(client: &Client<'_>, vm: &VmId) -> Result<(), Error> {
+    tpm_change(client, vm, Operation::Add, -1).await
+}
+
+// Remove the virtual TPM, does nothing if there is none
+pub async fn remove_tpm(client: &Client<'_>, vm: &VmId) -> Result<(), Error> {
+    let devices: Option<ArrayOfVirtualDevice> = client
+        .retrieve_property(&vm_reference(vm), "config.hardware.device")
+        .await?;
+    match tpm_key(devices.unwrap_or_default()) {
+        Some(key) => tpm_change(client, vm, Operation::Remove, key).await,
+        None => Ok(()),
+    }
+}
+
+async fn tpm_change(
+    client: &Client<'_>,
+    vm: &VmId,
+    operation: Operation,
+    key: i32,
+) -> Result<(), Error> {
+    let spec = ConfigSpec {
+        device_change: Some(DeviceChange {
+            operation,
+            device: Device {
+                device_type: TPM_TYPE.to_owned(),
+                key,
+            },
+        }),
+        ..Default::default()
+    };
+    reconfigure(client, vm, &spec).await
+}
+
+fn tpm_key(devices: ArrayOfVirtualDevice) -> Option<i32> {
+    devices
+        .devices
+        .into_iter()
+        .find(|device| device.device_type == TPM_TYPE)
+        .map(|device| device.key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reconfig::ReconfigRequest;
+    use super::*;
+    use crate::vim25::envelope;
+
+    #[test]
+    fn vbs_envelope() {
+        let vm = vm_reference(&VmId::new("vm-42"));
+        let spec = ConfigSpec::from(&UpdateSpec::vbs());
+        let request = ReconfigRequest {
+            this: &vm,
+            spec: &spec,
+        };
+        let xml = envelope("ReconfigVM_Task", &request).unwrap();
+        assert!(xml.contains(
+            "<spec><flags><vvtdEnabled>true</vvtdEnabled><vbsEnabled>true</vbsEnabled></flags>\
+             <bootOptions><efiSecureBootEnabled>true</efiSecureBootEnabled></bootOptions>\
+             <firmware>efi</firmware><nestedHVEnabled>true</nestedHVEnabled></spec>"
+        ));
+    }
+
+    #[test]
+    fn find_tpm() {
+        let xml = r#"<val xsi:type="ArrayOfVirtualDevice">
+            <VirtualDevice xsi:type="VirtualPCIController"><key>100</key><deviceInfo><label>PCI controller 0</label>
+            <summary>PCI controller 0</summary></deviceInfo><busNumber>0</busNumber><device>500</device></VirtualDevice>
+            <VirtualDevice xsi:type="VirtualTPM"><key>11000</key><deviceInfo><label>TPM</label><summary>TPM</summary>
+            </deviceInfo><controllerKey>100</controllerKey><unitNumber>20</unitNumber></VirtualDevice></val>"#;
+        assert_eq!(tpm_key(quick_xml::de::from_str(xml).unwrap()), Some(11000));
+    }
+}
@@ -0,0 +1,192 @@
+// Snapshots through the vim25 SOAP API, the REST API of vSphere 7 has no
+// snapshot service. Virtual machine identifiers of the REST API are the
+// values of their managed object references, e.g. "vm-42".
+use crate::id::VmId;
+use crate::vim25::{Client, Error, ManagedObjectReference, This};
+use chrono::prelude::*;
+use serde::de::IgnoredAny;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotPowerState {
+    PoweredOff,
+    PoweredOn,
+    Suspended,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Snapshot and its children, which are based on it
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotTree {
+    pub snapshot: ManagedObjectReference,
+    pub name: String,
+    pub description: Option<String>,
+    pub id: i32,
+    pub create_time: DateTime<Utc>,
+    pub state: SnapshotPowerState,
+    #[serde(default)]
+    pub quiesced: bool,
+    #[serde(default = "Vec::new", rename = "childSnapshotList")]
+    pub children: Vec<SnapshotTree>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub current_snapshot: Option<ManagedObjectReference>,
+    #[serde(default = "Vec::new", rename = "rootSnapshotList")]
+    pub root_snapshots: Vec<SnapshotTree>,
+}
+
+// Snapshot to create, memory includes the memory of a powered on machine and
+// quiesce flushes the guest file systems through VMware Tools before
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub memory: bool,
+    pub quiesce: bool,
+}
+
+impl CreateSpec {
+    pub fn new(name: impl Into<String>) -> Self {
+        CreateSpec {
+            name: name.into(),
+            description: None,
+            memory: false,
+            quiesce: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CreateRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    #[serde(flatten)]
+    spec: &'a CreateSpec,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoveRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    remove_children: bool,
+}
+
+pub(super) fn vm_reference(vm: &VmId) -> ManagedObjectReference {
+    ManagedObjectReference::new(vm.object_type(), vm.as_str())
+}
+
+// Snapshot tree of a virtual machine, None if it has no snapshots
+pub async fn list(client: &Client<'_>, vm: &VmId) -> Result<Option<SnapshotInfo>, Error> {
+    client
+        .retrieve_property(&vm_reference(vm), "snapshot")
+        .await
+}
+
+// Create a snapshot and wait until it is taken
+pub async fn create(
+    client: &Client<'_>,
+    vm: &VmId,
+    spec: &CreateSpec,
+) -> Result<ManagedObjectReference, Error> {
+    let vm = vm_reference(vm);
+    let request = CreateRequest { this: &vm, spec };
+    let task = client.call("CreateSnapshot_Task", &request).await?;
+    client
+        .wait_for_task(&task, POLL_INTERVAL)
+        .await?
+        .ok_or_else(|| Error::TaskFailed("no snapshot created".to_owned()))
+}
+
+// Revert the virtual machine to the snapshot and wait until it is done
+pub async fn revert(client: &Client<'_>, snapshot: &ManagedObjectReference) -> Result<(), Error> {
+    let task = client
+        .call("RevertToSnapshot_Task", &This { this: snapshot })
+        .await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+// Delete the snapshot, optionally with its children, and wait until the
+// disks are consolidated
+pub async fn delete(
+    client: &Client<'_>,
+    snapshot: &ManagedObjectReference,
+    remove_children: bool,
+) -> Result<(), Error> {
+    let request = RemoveRequest {
+        this: snapshot,
+        remove_children,
+    };
+    let task = client.call("RemoveSnapshot_Task", &request).await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_all(client: &Client<'_>, vm: &VmId) -> Result<(), Error> {
+    let vm = vm_reference(vm);
+    let task = client
+        .call("RemoveAllSnapshots_Task", &This { this: &vm })
+        .await?;
+    client
+        .wait_for_task::<IgnoredAny>(&task, POLL_INTERVAL)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vim25::envelope;
+
+    #[test]
+    fn create_envelope() {
+        let vm = vm_reference(&VmId::new("vm-42"));
+        let spec = CreateSpec {
+            memory: true,
+            ..CreateSpec::new("before-upgrade")
+        };
+        let request = CreateRequest {
+            this: &vm,
+            spec: &spec,
+        };
+        let envelope = envelope("CreateSnapshot_Task", &request).unwrap();
+        assert!(envelope.contains(
+            "<_this type=\"VirtualMachine\">vm-42</_this><name>before-upgrade</name>\
+             <memory>true</memory><quiesce>false</quiesce>"
+        ));
+    }
+
+    #[test]
+    fn deserialize_snapshot_info() {
+        let xml = r#"<val xsi:type="VirtualMachineSnapshotInfo">
+            <currentSnapshot type="VirtualMachineSnapshot">snapshot-2</currentSnapshot>
+            <rootSnapshotList><snapshot type="VirtualMachineSnapshot">snapshot-1</snapshot>
+            <vm type="VirtualMachine">vm-42</vm><name>base</name><description></description>
+            <id>1</id><createTime>2021-03-01T10:00:00.123Z</createTime><state>poweredOff</state>
+            <quiesced>false</quiesced><childSnapshotList>
+            <snapshot type="VirtualMachineSnapshot">snapshot-2</snapshot>
+            <vm type="VirtualMachine">vm-42</vm><name>child</name><id>2</id>
+            <createTime>2021-03-02T10:00:00Z</createTime><state>poweredOn</state>
+            <quiesced>true</quiesced></childSnapshotList></rootSnapshotList></val>"#;
+        let info: SnapshotInfo = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(info.root_snapshots.len(), 1);
+        let child = &info.root_snapshots[0].children[0];
+        assert_eq!(child.name, "child");
+        assert_eq!(child.state, SnapshotPowerState::PoweredOn);
+        assert_eq!(info.current_snapshot, Some(child.snapshot.clone()));
+    }
+}
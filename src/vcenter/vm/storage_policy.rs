@@ -0,0 +1,250 @@
+use super::Vm;
+use crate::cis::{Error, LocalizableMessage};
+use crate::common::{deserialize_map, serialize_map};
+use crate::vcenter::storage::policies::ComplianceStatus;
+use chrono::prelude::*;
+use reqwest::Method;
+use std::collections::HashMap;
+
+// Storage policies of the home directory and the disks keyed by their
+// identifiers, unset for storage without policy
+#[derive(Deserialize, Debug, Clone)]
+pub struct StoragePolicyInfo {
+    pub vm_home: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub disks: HashMap<String, String>,
+}
+
+// Whether a storage policy is given or the default policy of the datastore applies
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PolicyType {
+    UseSpecifiedPolicy,
+    UseDefaultPolicy,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PolicySpec {
+    #[serde(rename = "type")]
+    pub policy_type: PolicyType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+}
+
+impl PolicySpec {
+    pub fn specified(policy: impl Into<String>) -> Self {
+        PolicySpec {
+            policy_type: PolicyType::UseSpecifiedPolicy,
+            policy: Some(policy.into()),
+        }
+    }
+
+    pub fn default_policy() -> Self {
+        PolicySpec {
+            policy_type: PolicyType::UseDefaultPolicy,
+            policy: None,
+        }
+    }
+}
+
+// Changes to the storage policies, unset fields and disks missing from the
+// map are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_home: Option<PolicySpec>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub disks: HashMap<String, PolicySpec>,
+}
+
+impl UpdateSpec {
+    pub fn is_empty(&self) -> bool {
+        self.vm_home.is_none() && self.disks.is_empty()
+    }
+}
+
+// Policies the storage of a virtual machine should have, an unset home
+// directory and disks missing from the map are not checked
+#[derive(Debug, Default, Clone)]
+pub struct IntendedPolicies {
+    pub vm_home: Option<String>,
+    pub disks: HashMap<String, String>,
+}
+
+impl IntendedPolicies {
+    // Changes which fix the drift from the intended policies. Storage with
+    // another policy gets the intended one and storage which has it but
+    // isn't compliant (anymore) gets it applied again.
+    pub fn drift(
+        &self,
+        info: &StoragePolicyInfo,
+        compliance: Option<&ComplianceInfo>,
+    ) -> UpdateSpec {
+        let drifted = |intended: &str,
+                       current: Option<&String>,
+                       compliance: Option<&EntityComplianceInfo>| {
+            current.map(String::as_str) != Some(intended)
+                || compliance.is_some_and(|compliance| {
+                    matches!(
+                        compliance.status,
+                        ComplianceStatus::NonCompliant | ComplianceStatus::OutOfDate
+                    )
+                })
+        };
+        let vm_home = self.vm_home.as_deref().filter(|intended| {
+            drifted(
+                intended,
+                info.vm_home.as_ref(),
+                compliance.and_then(|compliance| compliance.vm_home.as_ref()),
+            )
+        });
+        let disks = self
+            .disks
+            .iter()
+            .filter(|(disk, intended)| {
+                drifted(
+                    intended,
+                    info.disks.get(*disk),
+                    compliance.and_then(|compliance| compliance.disks.get(*disk)),
+                )
+            })
+            .map(|(disk, intended)| (disk.clone(), PolicySpec::specified(intended.as_str())))
+            .collect();
+        UpdateSpec {
+            vm_home: vm_home.map(PolicySpec::specified),
+            disks,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EntityComplianceInfo {
+    pub status: ComplianceStatus,
+    pub check_time: DateTime<Utc>,
+    pub policy: Option<String>,
+    #[serde(default)]
+    pub failure_cause: Vec<LocalizableMessage>,
+}
+
+// Compliance of the home directory and the disks keyed by their identifiers
+#[derive(Deserialize, Debug, Clone)]
+pub struct ComplianceInfo {
+    pub overall_compliance: ComplianceStatus,
+    pub vm_home: Option<EntityComplianceInfo>,
+    #[serde(default, deserialize_with = "deserialize_map")]
+    pub disks: HashMap<String, EntityComplianceInfo>,
+}
+
+impl Vm<'_> {
+    pub async fn storage_policy(&self) -> Result<StoragePolicyInfo, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &self.endpoint("/storage/policy"));
+        self.session.send(request).await
+    }
+
+    pub async fn update_storage_policy(&self, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self
+            .session
+            .request(Method::PATCH, &self.endpoint("/storage/policy"));
+        self.session
+            .send_empty(self.session.body(request, "spec", spec))
+            .await
+    }
+
+    // Result of the last compliance check, None if it was never checked
+    pub async fn storage_policy_compliance(&self) -> Result<Option<ComplianceInfo>, Error> {
+        let request = self
+            .session
+            .request(Method::GET, &self.endpoint("/storage/policy/compliance"));
+        self.session.send(request).await
+    }
+
+    // Check the compliance now, None if no storage has a policy
+    pub async fn check_storage_policy_compliance(&self) -> Result<Option<ComplianceInfo>, Error> {
+        let request = self.session.action(
+            Method::POST,
+            &self.endpoint("/storage/policy/compliance"),
+            "check",
+        );
+        self.session.send(request).await
+    }
+
+    // Policy of a disk, None if it has none
+    pub async fn disk_storage_policy(&self, disk: &str) -> Result<Option<String>, Error> {
+        Ok(self.storage_policy().await?.disks.remove(disk))
+    }
+
+    // Check the compliance now and fix the drift from the intended policies,
+    // returns the applied changes which are empty if nothing drifted
+    pub async fn reconcile_storage_policy(
+        &self,
+        intended: &IntendedPolicies,
+    ) -> Result<UpdateSpec, Error> {
+        let info = self.storage_policy().await?;
+        let compliance = self.check_storage_policy_compliance().await?;
+        let spec = intended.drift(&info, compliance.as_ref());
+        if !spec.is_empty() {
+            self.update_storage_policy(&spec).await?;
+        }
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComplianceInfo, ComplianceStatus, IntendedPolicies, StoragePolicyInfo};
+
+    #[test]
+    fn deserialize_compliance() {
+        let info: ComplianceInfo = serde_json::from_str(
+            r#"{
+                "overall_compliance": "NON_COMPLIANT",
+                "vm_home": {"status": "COMPLIANT", "check_time": "2021-03-01T10:00:00.000Z", "policy": "aa6d5a82-1c88-45da-85d3-3d74b91a5bad"},
+                "disks": [{"key": "2000", "value": {
+                    "status": "NON_COMPLIANT", "check_time": "2021-03-01T10:00:00.000Z",
+                    "failure_cause": [{"id": "vsan.capacity", "default_message": "Not enough capacity"}]
+                }}]
+            }"#,
+        )
+        .expect("deserialize");
+        assert_eq!(info.overall_compliance, ComplianceStatus::NonCompliant);
+        assert_eq!(info.disks["2000"].failure_cause.len(), 1);
+    }
+
+    #[test]
+    fn drift_from_intended_policies() {
+        let info: StoragePolicyInfo = serde_json::from_str(
+            r#"{"vm_home": "gold", "disks": [{"key": "2000", "value": "gold"}, {"key": "2001", "value": "silver"}]}"#,
+        )
+        .expect("deserialize");
+        let compliance: ComplianceInfo = serde_json::from_str(
+            r#"{
+                "overall_compliance": "NON_COMPLIANT",
+                "vm_home": {"status": "COMPLIANT", "check_time": "2021-03-01T10:00:00.000Z", "policy": "gold"},
+                "disks": [{"key": "2000", "value": {"status": "OUT_OF_DATE", "check_time": "2021-03-01T10:00:00.000Z", "policy": "gold"}}]
+            }"#,
+        )
+        .expect("deserialize");
+        let intended = IntendedPolicies {
+            vm_home: Some("gold".to_owned()),
+            disks: vec![
+                ("2000".to_owned(), "gold".to_owned()),
+                ("2001".to_owned(), "gold".to_owned()),
+                ("2002".to_owned(), "gold".to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let spec = intended.drift(&info, Some(&compliance));
+        assert!(spec.vm_home.is_none());
+        let mut disks: Vec<_> = spec.disks.keys().map(String::as_str).collect();
+        disks.sort_unstable();
+        assert_eq!(disks, vec!["2000", "2001", "2002"]);
+        assert!(intended.drift(&info, None).disks.contains_key("2001"));
+        assert!(!intended.drift(&info, None).disks.contains_key("2000"));
+    }
+}
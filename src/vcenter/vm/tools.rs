@@ -0,0 +1,162 @@
+use super::Vm;
+use crate::cis::Error;
+use reqwest::Method;
+use std::time::Duration;
+use tokio::time::{delay_for, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// When VMware Tools are upgraded
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UpgradePolicy {
+    Manual,
+    UpgradeAtPowerCycle,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Run state of VMware Tools in the guest
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RunState {
+    NotRunning,
+    Running,
+    ExecutingScripts,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Version of the installed VMware Tools compared to the host
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VersionStatus {
+    NotInstalled,
+    Current,
+    Unmanaged,
+    TooOldUnsupported,
+    SupportedOld,
+    SupportedNew,
+    TooNew,
+    Blacklisted,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// How VMware Tools were installed in the guest
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InstallType {
+    OpenVmTools,
+    Msi,
+    Tar,
+    Osp,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// State of VMware Tools in a virtual machine
+#[derive(Deserialize, Debug, Clone)]
+pub struct ToolsInfo {
+    #[serde(default)]
+    pub auto_update_supported: bool,
+    pub install_attempt_count: Option<u32>,
+    pub version_number: Option<i64>,
+    pub version: Option<String>,
+    pub upgrade_policy: UpgradePolicy,
+    pub version_status: Option<VersionStatus>,
+    pub install_type: Option<InstallType>,
+    pub run_state: RunState,
+}
+
+// Changes to the VMware Tools configuration, unset fields are left unchanged
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct UpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upgrade_policy: Option<UpgradePolicy>,
+}
+
+#[derive(Serialize)]
+struct UpgradeParams<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command_line_options: Option<&'a str>,
+}
+
+impl Vm<'_> {
+    pub async fn tools(&self) -> Result<ToolsInfo, Error> {
+        let request = self.session.request(Method::GET, &self.endpoint("/tools"));
+        self.session.send(request).await
+    }
+
+    pub async fn update_tools(&self, spec: &UpdateSpec) -> Result<(), Error> {
+        let request = self.session.body(
+            self.session
+                .request(Method::PATCH, &self.endpoint("/tools")),
+            "spec",
+            spec,
+        );
+        self.session.send_empty(request).await
+    }
+
+    // Start an upgrade of VMware Tools, fails with ErrorType::NotAllowedInCurrentState
+    // if the virtual machine is not running or the upgrade is not supported
+    pub async fn upgrade_tools(&self, command_line_options: Option<&str>) -> Result<(), Error> {
+        let request = self
+            .session
+            .action(Method::POST, &self.endpoint("/tools"), "upgrade");
+        let request = self.session.json(
+            request,
+            &UpgradeParams {
+                command_line_options,
+            },
+        );
+        self.session.send_empty(request).await
+    }
+
+    // Poll until VMware Tools are running in the guest, fails with
+    // Error::Timeout if they didn't start in time. Like wait_for_guest_ip the
+    // future can be dropped at any time.
+    pub async fn wait_for_tools_running(&self, timeout: Duration) -> Result<ToolsInfo, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let info = self.tools().await?;
+            if let RunState::Running | RunState::ExecutingScripts = info.run_state {
+                return Ok(info);
+            }
+            if Instant::now() + POLL_INTERVAL > deadline {
+                return Err(Error::Timeout);
+            }
+            delay_for(POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "strict")))]
+mod tests {
+    use super::super::Vm;
+    use crate::cis::transport::MockTransport;
+    use crate::cis::{Error, Session};
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn unknown_run_state_is_not_running() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            Method::GET,
+            "/rest/vcenter/vm/vm-42/tools",
+            StatusCode::OK,
+            r#"{"value":{"upgrade_policy":"MANUAL","run_state":"STARTING"}}"#,
+        );
+        let session = Session::builder("vcenter.test")
+            .transport(mock)
+            .build()
+            .expect("build");
+        let result = Vm::new(&session, "vm-42")
+            .wait_for_tools_running(Duration::from_millis(0))
+            .await;
+        assert!(matches!(result, Err(Error::Timeout)));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::cis::{Error, Session};
+use crate::common::serialize_map;
+use crate::id::{
+    ClusterId, DatastoreId, FolderId, HostId, LibraryItemId, NetworkId, ResourcePoolId, VmId,
+};
+use reqwest::Method;
+use std::collections::HashMap;
+
+// Which storage policy applies to the storage of a deployed virtual machine
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StoragePolicyType {
+    UseSpecifiedPolicy,
+    UseSourcePolicy,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StoragePolicySpec {
+    #[serde(rename = "type")]
+    pub policy_type: StoragePolicyType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+}
+
+// Storage of the home directory or the disks of a deployed virtual machine,
+// unset fields are taken from the template
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct StorageSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore: Option<DatastoreId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_policy: Option<StoragePolicySpec>,
+}
+
+// Placement of a deployed virtual machine, one of host, cluster or resource
+// pool is required if the template has no placement of its own
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct PlacementSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<FolderId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pool: Option<ResourcePoolId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<HostId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<ClusterId>,
+}
+
+// Guest customization applied by name of a specification stored in
+// vcenter::guest::customization_specs
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct GuestCustomizationSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct EthernetUpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkId>,
+}
+
+// New capacity of a disk in bytes
+#[derive(Serialize, Debug, Clone)]
+pub struct DiskUpdateSpec {
+    pub capacity: u64,
+}
+
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct CpuUpdateSpec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_cpus: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_cores_per_socket: Option<u32>,
+}
+
+// New memory size in MiB
+#[derive(Serialize, Debug, Clone)]
+pub struct MemoryUpdateSpec {
+    pub memory: u64,
+}
+
+// Changes to the hardware of the template, NICs and disks are keyed by the
+// identifiers of the devices in the template
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct HardwareCustomizationSpec {
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub nics: HashMap<String, EthernetUpdateSpec>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disks_to_remove: Vec<String>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub disks_to_update: HashMap<String, DiskUpdateSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_update: Option<CpuUpdateSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_update: Option<MemoryUpdateSpec>,
+}
+
+// Deployment of a virtual machine from a VM template library item, the disk
+// storage overrides are keyed by the disk identifiers of the template
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct DeploySpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_home_storage: Option<StorageSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_storage: Option<StorageSpec>,
+    #[serde(
+        serialize_with = "serialize_map",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub disk_storage_overrides: HashMap<String, StorageSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<PlacementSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub powered_on: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guest_customization: Option<GuestCustomizationSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hardware_customization: Option<HardwareCustomizationSpec>,
+}
+
+// Deploy a virtual machine from a VM template library item and return its identifier
+pub async fn deploy(
+    session: &Session,
+    template_library_item: &LibraryItemId,
+    spec: &DeploySpec,
+) -> Result<VmId, Error> {
+    let request = session.action(
+        Method::POST,
+        &format!(
+            "/vcenter/vm-template/library-items/{}",
+            template_library_item
+        ),
+        "deploy",
+    );
+    session.send(session.body(request, "spec", spec)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeploySpec, DiskUpdateSpec, HardwareCustomizationSpec};
+    use crate::cis::ApiFlavor;
+    use crate::common::WithFlavor;
+
+    #[test]
+    fn nested_maps_per_flavor() {
+        let mut hardware = HardwareCustomizationSpec::default();
+        hardware
+            .disks_to_update
+            .insert("2000".into(), DiskUpdateSpec { capacity: 1024 });
+        let spec = DeploySpec {
+            name: "web-1".into(),
+            hardware_customization: Some(hardware),
+            ..Default::default()
+        };
+        let rest = serde_json::to_value(WithFlavor(ApiFlavor::Rest, &spec)).expect("serialize");
+        assert_eq!(
+            rest["hardware_customization"]["disks_to_update"],
+            serde_json::json!([{"key": "2000", "value": {"capacity": 1024}}])
+        );
+        let api = serde_json::to_value(WithFlavor(ApiFlavor::Api, &spec)).expect("serialize");
+        assert_eq!(
+            api["hardware_customization"]["disks_to_update"],
+            serde_json::json!({"2000": {"capacity": 1024}})
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use super::{Client, Error, Fault, ManagedObjectReference};
+use chrono::prelude::*;
+
+// Status of an alarm on an entity, gray means unknown
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AlarmStatus {
+    Gray,
+    Green,
+    Yellow,
+    Red,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+
+// Alarm definition, the entity is the object it is defined on and applies
+// to the objects below it
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmInfo {
+    pub key: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub enabled: bool,
+    pub entity: ManagedObjectReference,
+    pub alarm: ManagedObjectReference,
+    pub last_modified_time: Option<DateTime<Utc>>,
+    pub last_modified_user: Option<String>,
+}
+
+// Alarm triggered on an entity
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmState {
+    pub key: String,
+    pub entity: ManagedObjectReference,
+    pub alarm: ManagedObjectReference,
+    pub overall_status: AlarmStatus,
+    pub time: DateTime<Utc>,
+    #[serde(default)]
+    pub acknowledged: bool,
+    pub acknowledged_by_user: Option<String>,
+    pub acknowledged_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfAlarmState {
+    #[serde(default, rename = "AlarmState")]
+    states: Vec<AlarmState>,
+}
+
+#[derive(Deserialize)]
+struct Alarms {
+    #[serde(default, rename = "returnval")]
+    alarms: Vec<ManagedObjectReference>,
+}
+
+#[derive(Serialize)]
+struct GetAlarmRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity: Option<&'a ManagedObjectReference>,
+}
+
+#[derive(Serialize)]
+struct AlarmRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    alarm: &'a ManagedObjectReference,
+    entity: &'a ManagedObjectReference,
+}
+
+#[derive(Serialize)]
+struct SetAlarmStatusRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    alarm: &'a ManagedObjectReference,
+    entity: &'a ManagedObjectReference,
+    status: AlarmStatus,
+}
+
+impl Client<'_> {
+    // Alarm definitions on the entity, without entity the ones on the root folder
+    pub async fn alarms(
+        &self,
+        entity: Option<&ManagedObjectReference>,
+    ) -> Result<Vec<ManagedObjectReference>, Error> {
+        let alarm_manager = self.alarm_manager().await?;
+        let request = GetAlarmRequest {
+            this: &alarm_manager,
+            entity,
+        };
+        let body = self.invoke("GetAlarm", &request).await?;
+        let alarms: Alarms = super::parse_body(&body)?;
+        Ok(alarms.alarms)
+    }
+
+    pub async fn alarm_info(&self, alarm: &ManagedObjectReference) -> Result<AlarmInfo, Error> {
+        self.retrieve_property(alarm, "info")
+            .await?
+            .ok_or_else(|| Error::Fault(Fault::not_supported(&alarm.value)))
+    }
+
+    // Alarms currently triggered on the entity, e.g. the root folder for all
+    // triggered alarms of vCenter
+    pub async fn triggered_alarms(
+        &self,
+        entity: &ManagedObjectReference,
+    ) -> Result<Vec<AlarmState>, Error> {
+        let states: Option<ArrayOfAlarmState> = self
+            .retrieve_property(entity, "triggeredAlarmState")
+            .await?;
+        Ok(states.unwrap_or_default().states)
+    }
+
+    // Acknowledge a triggered alarm, it stays triggered until its status changes
+    pub async fn acknowledge_alarm(
+        &self,
+        alarm: &ManagedObjectReference,
+        entity: &ManagedObjectReference,
+    ) -> Result<(), Error> {
+        let alarm_manager = self.alarm_manager().await?;
+        let request = AlarmRequest {
+            this: &alarm_manager,
+            alarm,
+            entity,
+        };
+        self.call_empty("AcknowledgeAlarm", &request).await
+    }
+
+    // Reset a triggered alarm to green (vSphere 6.7+)
+    pub async fn reset_alarm(
+        &self,
+        alarm: &ManagedObjectReference,
+        entity: &ManagedObjectReference,
+    ) -> Result<(), Error> {
+        let alarm_manager = self.alarm_manager().await?;
+        let request = SetAlarmStatusRequest {
+            this: &alarm_manager,
+            alarm,
+            entity,
+            status: AlarmStatus::Green,
+        };
+        self.call_empty("SetAlarmStatus", &request).await
+    }
+
+    async fn alarm_manager(&self) -> Result<ManagedObjectReference, Error> {
+        let content = self.cached_content().await?;
+        content
+            .alarm_manager
+            .ok_or_else(|| Error::Fault(Fault::not_supported("AlarmManager")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlarmStatus, ArrayOfAlarmState, SetAlarmStatusRequest};
+    use crate::vim25::{envelope, ManagedObjectReference};
+
+    #[test]
+    fn deserialize_triggered_alarms() {
+        let xml = r#"<val xsi:type="ArrayOfAlarmState"><AlarmState xsi:type="AlarmState">
+            <key>alarm-7.host-10</key><entity type="HostSystem">host-10</entity>
+            <alarm type="Alarm">alarm-7</alarm><overallStatus>red</overallStatus>
+            <time>2021-03-04T10:16:00.512Z</time><acknowledged>true</acknowledged>
+            <acknowledgedByUser>VSPHERE.LOCAL\ops</acknowledgedByUser>
+            <acknowledgedTime>2021-03-04T10:20:00Z</acknowledgedTime></AlarmState></val>"#;
+        let array: ArrayOfAlarmState = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(array.states.len(), 1);
+        let state = &array.states[0];
+        assert_eq!(state.overall_status, AlarmStatus::Red);
+        assert_eq!(state.alarm, ManagedObjectReference::new("Alarm", "alarm-7"));
+        assert!(state.acknowledged);
+    }
+
+    #[test]
+    fn reset_envelope() {
+        let request = SetAlarmStatusRequest {
+            this: &ManagedObjectReference::new("AlarmManager", "AlarmManager"),
+            alarm: &ManagedObjectReference::new("Alarm", "alarm-7"),
+            entity: &ManagedObjectReference::new("HostSystem", "host-10"),
+            status: AlarmStatus::Green,
+        };
+        let envelope = envelope("SetAlarmStatus", &request).unwrap();
+        assert!(envelope.contains(
+            "<alarm type=\"Alarm\">alarm-7</alarm>\
+             <entity type=\"HostSystem\">host-10</entity><status>green</status>"
+        ));
+    }
+}
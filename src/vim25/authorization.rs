@@ -0,0 +1,277 @@
+use super::{Client, Error, Fault, ManagedObjectReference};
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Description {
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub summary: String,
+}
+
+// Role with its privileges like "VirtualMachine.Interact.PowerOn", system
+// roles like Admin (-1) or ReadOnly (-2) can not be changed
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Role {
+    #[serde(default)]
+    pub role_id: i32,
+    #[serde(default)]
+    pub system: bool,
+    pub name: String,
+    #[serde(default)]
+    pub info: Description,
+    #[serde(default, rename = "privilege")]
+    pub privileges: Vec<String>,
+}
+
+// Role of a user or group on an entity, with propagate it applies to the
+// objects below the entity too. The principal includes the domain, e.g.
+// "VSPHERE.LOCAL\devops".
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<ManagedObjectReference>,
+    #[serde(default)]
+    pub principal: String,
+    #[serde(default)]
+    pub group: bool,
+    #[serde(default)]
+    pub role_id: i32,
+    #[serde(default)]
+    pub propagate: bool,
+}
+
+impl Permission {
+    pub fn user(principal: impl Into<String>, role_id: i32) -> Self {
+        Permission {
+            entity: None,
+            principal: principal.into(),
+            group: false,
+            role_id,
+            propagate: true,
+        }
+    }
+
+    pub fn group(principal: impl Into<String>, role_id: i32) -> Self {
+        Permission {
+            group: true,
+            ..Permission::user(principal, role_id)
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct ArrayOfAuthorizationRole {
+    #[serde(default, rename = "AuthorizationRole")]
+    roles: Vec<Role>,
+}
+
+#[derive(Deserialize)]
+struct Permissions {
+    #[serde(default, rename = "returnval")]
+    permissions: Vec<Permission>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddRoleRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    name: &'a str,
+    priv_ids: &'a [String],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateRoleRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    role_id: i32,
+    new_name: &'a str,
+    priv_ids: &'a [String],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoveRoleRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    role_id: i32,
+    fail_if_used: bool,
+}
+
+#[derive(Serialize)]
+struct EntityPermissionsRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    entity: &'a ManagedObjectReference,
+    inherited: bool,
+}
+
+#[derive(Serialize)]
+struct SetPermissionsRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    entity: &'a ManagedObjectReference,
+    permission: &'a [Permission],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemovePermissionRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    entity: &'a ManagedObjectReference,
+    user: &'a str,
+    is_group: bool,
+}
+
+impl Client<'_> {
+    pub async fn roles(&self) -> Result<Vec<Role>, Error> {
+        let authorization_manager = self.authorization_manager().await?;
+        let roles: Option<ArrayOfAuthorizationRole> = self
+            .retrieve_property(&authorization_manager, "roleList")
+            .await?;
+        Ok(roles.unwrap_or_default().roles)
+    }
+
+    // Create a custom role, returns its id
+    pub async fn add_role(&self, name: &str, privileges: &[String]) -> Result<i32, Error> {
+        let authorization_manager = self.authorization_manager().await?;
+        let request = AddRoleRequest {
+            this: &authorization_manager,
+            name,
+            priv_ids: privileges,
+        };
+        self.call("AddAuthorizationRole", &request).await
+    }
+
+    // Rename a custom role and replace its privileges
+    pub async fn update_role(
+        &self,
+        role_id: i32,
+        name: &str,
+        privileges: &[String],
+    ) -> Result<(), Error> {
+        let authorization_manager = self.authorization_manager().await?;
+        let request = UpdateRoleRequest {
+            this: &authorization_manager,
+            role_id,
+            new_name: name,
+            priv_ids: privileges,
+        };
+        self.call_empty("UpdateAuthorizationRole", &request).await
+    }
+
+    // Remove a custom role, with fail_if_used it is kept while permissions
+    // use it, otherwise those permissions are removed too
+    pub async fn remove_role(&self, role_id: i32, fail_if_used: bool) -> Result<(), Error> {
+        let authorization_manager = self.authorization_manager().await?;
+        let request = RemoveRoleRequest {
+            this: &authorization_manager,
+            role_id,
+            fail_if_used,
+        };
+        self.call_empty("RemoveAuthorizationRole", &request).await
+    }
+
+    // Permissions defined on the entity, with inherited also the propagated
+    // ones of its parents
+    pub async fn permissions(
+        &self,
+        entity: &ManagedObjectReference,
+        inherited: bool,
+    ) -> Result<Vec<Permission>, Error> {
+        let authorization_manager = self.authorization_manager().await?;
+        let request = EntityPermissionsRequest {
+            this: &authorization_manager,
+            entity,
+            inherited,
+        };
+        let body = self.invoke("RetrieveEntityPermissions", &request).await?;
+        let permissions: Permissions = super::parse_body(&body)?;
+        Ok(permissions.permissions)
+    }
+
+    // Add permissions to the entity, existing permissions of the same
+    // principals are replaced
+    pub async fn set_permissions(
+        &self,
+        entity: &ManagedObjectReference,
+        permissions: &[Permission],
+    ) -> Result<(), Error> {
+        let authorization_manager = self.authorization_manager().await?;
+        let request = SetPermissionsRequest {
+            this: &authorization_manager,
+            entity,
+            permission: permissions,
+        };
+        self.call_empty("SetEntityPermissions", &request).await
+    }
+
+    pub async fn remove_permission(
+        &self,
+        entity: &ManagedObjectReference,
+        principal: &str,
+        group: bool,
+    ) -> Result<(), Error> {
+        let authorization_manager = self.authorization_manager().await?;
+        let request = RemovePermissionRequest {
+            this: &authorization_manager,
+            entity,
+            user: principal,
+            is_group: group,
+        };
+        self.call_empty("RemoveEntityPermission", &request).await
+    }
+
+    async fn authorization_manager(&self) -> Result<ManagedObjectReference, Error> {
+        let content = self.cached_content().await?;
+        content
+            .authorization_manager
+            .ok_or_else(|| Error::Fault(Fault::not_supported("AuthorizationManager")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayOfAuthorizationRole, Permission, SetPermissionsRequest};
+    use crate::vim25::{envelope, ManagedObjectReference};
+
+    #[test]
+    fn deserialize_roles() {
+        let xml = r#"<val xsi:type="ArrayOfAuthorizationRole">
+            <AuthorizationRole xsi:type="AuthorizationRole"><roleId>-1</roleId><system>true</system>
+            <name>Admin</name><info xsi:type="Description"><label>Administrator</label>
+            <summary>Full access rights</summary></info></AuthorizationRole>
+            <AuthorizationRole xsi:type="AuthorizationRole"><roleId>1101</roleId><system>false</system>
+            <name>vm-operator</name><info xsi:type="Description"><label>vm-operator</label>
+            <summary>vm-operator</summary></info><privilege>System.Anonymous</privilege>
+            <privilege>VirtualMachine.Interact.PowerOn</privilege></AuthorizationRole></val>"#;
+        let array: ArrayOfAuthorizationRole = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(array.roles.len(), 2);
+        assert!(array.roles[0].system);
+        assert_eq!(array.roles[0].info.label, "Administrator");
+        assert_eq!(array.roles[1].role_id, 1101);
+        assert_eq!(
+            array.roles[1].privileges,
+            vec!["System.Anonymous", "VirtualMachine.Interact.PowerOn"]
+        );
+    }
+
+    #[test]
+    fn set_permissions_envelope() {
+        let request = SetPermissionsRequest {
+            this: &ManagedObjectReference::new("AuthorizationManager", "AuthorizationManager"),
+            entity: &ManagedObjectReference::new("Folder", "group-v3"),
+            permission: &[Permission::group("VSPHERE.LOCAL\\devops", 1101)],
+        };
+        let xml = envelope("SetEntityPermissions", &request).unwrap();
+        assert!(xml.contains(
+            "<entity type=\"Folder\">group-v3</entity>\
+             <permission><principal>VSPHERE.LOCAL\\devops</principal><group>true</group>\
+             <roleId>1101</roleId><propagate>true</propagate></permission>"
+        ));
+    }
+}
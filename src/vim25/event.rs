@@ -0,0 +1,397 @@
+use super::{Client, Error, ManagedObjectReference, This};
+use chrono::prelude::*;
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+// Events read from a collector per call
+const PAGE_SIZE: u32 = 100;
+
+// Objects an event filter applies to relative to the entity
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Recursion {
+    #[serde(rename = "self")]
+    Itself,
+    Children,
+    All,
+}
+
+// Type of an event, event types without variant are kept as Other with the
+// name of the vim25 type, e.g. "VmReconfiguredEvent"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    VmCreated,
+    VmRemoved,
+    VmPoweredOn,
+    VmPoweredOff,
+    VmSuspended,
+    VmMigrated,
+    HostConnected,
+    HostDisconnected,
+    AlarmStatusChanged,
+    Other(String),
+}
+
+impl EventKind {
+    pub fn from_type(event_type: &str) -> Self {
+        match event_type {
+            "VmCreatedEvent" | "VmDeployedEvent" | "VmClonedEvent" | "VmRegisteredEvent" => {
+                EventKind::VmCreated
+            }
+            "VmRemovedEvent" => EventKind::VmRemoved,
+            "VmPoweredOnEvent" | "DrsVmPoweredOnEvent" => EventKind::VmPoweredOn,
+            "VmPoweredOffEvent" | "VmGuestShutdownEvent" => EventKind::VmPoweredOff,
+            "VmSuspendedEvent" => EventKind::VmSuspended,
+            "VmMigratedEvent" | "DrsVmMigratedEvent" | "VmRelocatedEvent" => EventKind::VmMigrated,
+            "HostConnectedEvent" => EventKind::HostConnected,
+            "HostDisconnectedEvent" | "HostConnectionLostEvent" | "HostReconnectionFailedEvent" => {
+                EventKind::HostDisconnected
+            }
+            "AlarmStatusChangedEvent" => EventKind::AlarmStatusChanged,
+            other => EventKind::Other(other.to_owned()),
+        }
+    }
+}
+
+// Object an event refers to, the element name of the reference depends on
+// the kind of the argument (vm, host, alarm, ...)
+#[derive(Deserialize, Debug, Clone)]
+pub struct EventArgument {
+    pub name: String,
+    #[serde(
+        alias = "vm",
+        alias = "host",
+        alias = "datacenter",
+        alias = "computeResource",
+        alias = "ds",
+        alias = "net",
+        alias = "alarm",
+        alias = "entity"
+    )]
+    pub reference: Option<ManagedObjectReference>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RawEvent {
+    #[serde(rename = "@type")]
+    event_type: String,
+    key: i64,
+    chain_id: i64,
+    created_time: DateTime<Utc>,
+    user_name: Option<String>,
+    datacenter: Option<EventArgument>,
+    compute_resource: Option<EventArgument>,
+    host: Option<EventArgument>,
+    vm: Option<EventArgument>,
+    full_formatted_message: Option<String>,
+    alarm: Option<EventArgument>,
+    entity: Option<EventArgument>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+// Event logged by vCenter, the alarm fields are only set for alarm events.
+// The status of alarms is one of "gray", "green", "yellow" and "red".
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub kind: EventKind,
+    pub event_type: String,
+    pub key: i64,
+    pub chain_id: i64,
+    pub created_time: DateTime<Utc>,
+    pub user_name: Option<String>,
+    pub datacenter: Option<EventArgument>,
+    pub compute_resource: Option<EventArgument>,
+    pub host: Option<EventArgument>,
+    pub vm: Option<EventArgument>,
+    pub message: Option<String>,
+    pub alarm: Option<EventArgument>,
+    pub entity: Option<EventArgument>,
+    pub alarm_from: Option<String>,
+    pub alarm_to: Option<String>,
+}
+
+impl From<RawEvent> for Event {
+    fn from(raw: RawEvent) -> Self {
+        Event {
+            kind: EventKind::from_type(&raw.event_type),
+            event_type: raw.event_type,
+            key: raw.key,
+            chain_id: raw.chain_id,
+            created_time: raw.created_time,
+            user_name: raw.user_name,
+            datacenter: raw.datacenter,
+            compute_resource: raw.compute_resource,
+            host: raw.host,
+            vm: raw.vm,
+            message: raw.full_formatted_message,
+            alarm: raw.alarm,
+            entity: raw.entity,
+            alarm_from: raw.from,
+            alarm_to: raw.to,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct EntityFilter {
+    entity: ManagedObjectReference,
+    recursion: Recursion,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TimeFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    begin_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<DateTime<Utc>>,
+}
+
+// Filter of the events to read, the SOAP API requires the fields in this order
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity: Option<EntityFilter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<TimeFilter>,
+    #[serde(rename = "eventTypeId", skip_serializing_if = "Vec::is_empty")]
+    event_types: Vec<String>,
+}
+
+impl EventFilter {
+    // Only events of the entity and depending on the recursion the objects below it
+    pub fn entity(mut self, entity: ManagedObjectReference, recursion: Recursion) -> Self {
+        self.entity = Some(EntityFilter { entity, recursion });
+        self
+    }
+
+    // Only events logged in the time range, either end may be open
+    pub fn time(mut self, begin: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Self {
+        self.time = Some(TimeFilter {
+            begin_time: begin,
+            end_time: end,
+        });
+        self
+    }
+
+    // Only events of the vim25 type, e.g. "VmPoweredOnEvent"
+    pub fn event_type(mut self, event_type: impl Into<String>) -> Self {
+        self.event_types.push(event_type.into());
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct CreateCollectorRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    filter: &'a EventFilter,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadEventsRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    max_count: u32,
+}
+
+#[derive(Deserialize)]
+struct Events {
+    #[serde(default, rename = "returnval")]
+    events: Vec<RawEvent>,
+}
+
+impl Client<'_> {
+    // Events matching the filter which were already logged, at most max_count
+    pub async fn query_events(
+        &self,
+        filter: &EventFilter,
+        max_count: usize,
+    ) -> Result<Vec<Event>, Error> {
+        let collector = self.create_event_collector(filter).await?;
+        let events = self.read_events(&collector, max_count).await;
+        // Destroy the collector when reading failed as well
+        let destroyed = self.destroy_event_collector(&collector).await;
+        let events = events?;
+        destroyed?;
+        Ok(events)
+    }
+
+    async fn read_events(
+        &self,
+        collector: &ManagedObjectReference,
+        max_count: usize,
+    ) -> Result<Vec<Event>, Error> {
+        let mut events = Vec::new();
+        while events.len() < max_count {
+            let page = self.read_next_events(collector).await?;
+            if page.is_empty() {
+                break;
+            }
+            events.extend(page);
+        }
+        events.truncate(max_count);
+        Ok(events)
+    }
+
+    // Stream the events matching the filter as they are logged, starting
+    // with the ones already logged in its time range. Without time range only
+    // new events are streamed. The collector is polled in the interval while
+    // no events arrive, vCenter removes it with the session.
+    pub fn event_stream<'a>(
+        &'a self,
+        filter: &EventFilter,
+        poll_interval: Duration,
+    ) -> EventStream<'a> {
+        let mut filter = filter.clone();
+        if filter.time.is_none() {
+            filter = filter.time(Some(Utc::now()), None);
+        }
+        let state = (None, VecDeque::new());
+        let events = stream::try_unfold(state, move |(collector, mut pending)| {
+            let filter = filter.clone();
+            async move {
+                let collector = match collector {
+                    Some(collector) => collector,
+                    None => self.create_event_collector(&filter).await?,
+                };
+                while pending.is_empty() {
+                    pending.extend(self.read_next_events(&collector).await?);
+                    if pending.is_empty() {
+                        tokio::time::delay_for(poll_interval).await;
+                    }
+                }
+                let event = pending.pop_front();
+                Ok(event.map(|event| (event, (Some(collector), pending))))
+            }
+        });
+        EventStream {
+            inner: events.boxed(),
+        }
+    }
+
+    async fn create_event_collector(
+        &self,
+        filter: &EventFilter,
+    ) -> Result<ManagedObjectReference, Error> {
+        let content = self.cached_content().await?;
+        let event_manager = content
+            .event_manager
+            .ok_or_else(|| Error::Fault(super::Fault::not_supported("EventManager")))?;
+        let request = CreateCollectorRequest {
+            this: &event_manager,
+            filter,
+        };
+        self.call("CreateCollectorForEvents", &request).await
+    }
+
+    async fn read_next_events(
+        &self,
+        collector: &ManagedObjectReference,
+    ) -> Result<Vec<Event>, Error> {
+        let request = ReadEventsRequest {
+            this: collector,
+            max_count: PAGE_SIZE,
+        };
+        let body = self.invoke("ReadNextEvents", &request).await?;
+        let events: Events = super::parse_body(&body)?;
+        Ok(events.events.into_iter().map(Event::from).collect())
+    }
+
+    async fn destroy_event_collector(
+        &self,
+        collector: &ManagedObjectReference,
+    ) -> Result<(), Error> {
+        self.call_empty("DestroyCollector", &This { this: collector })
+            .await
+    }
+}
+
+// Endless stream of events, see Client::event_stream
+pub struct EventStream<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<Event, Error>> + Send + 'a>>,
+}
+
+impl Stream for EventStream<'_> {
+    type Item = Result<Event, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventFilter, EventKind, Events, Recursion};
+    use crate::vim25::{envelope, parse_body, ManagedObjectReference};
+
+    #[test]
+    fn filter_envelope() {
+        let filter = EventFilter::default()
+            .entity(
+                ManagedObjectReference::new("Folder", "group-d1"),
+                Recursion::All,
+            )
+            .event_type("VmPoweredOnEvent")
+            .event_type("HostDisconnectedEvent");
+        #[derive(Serialize)]
+        struct Request<'a> {
+            filter: &'a EventFilter,
+        }
+        let envelope = envelope("CreateCollectorForEvents", &Request { filter: &filter }).unwrap();
+        assert!(envelope.contains(
+            "<filter><entity><entity type=\"Folder\">group-d1</entity><recursion>all</recursion>\
+             </entity><eventTypeId>VmPoweredOnEvent</eventTypeId>\
+             <eventTypeId>HostDisconnectedEvent</eventTypeId></filter>"
+        ));
+    }
+
+    #[test]
+    fn parse_events() {
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><ReadNextEventsResponse xmlns="urn:vim25">
+            <returnval xsi:type="VmPoweredOnEvent"><key>5021</key><chainId>5020</chainId>
+            <createdTime>2021-03-04T10:15:00.123Z</createdTime><userName>VSPHERE.LOCAL\ops</userName>
+            <datacenter><name>DC1</name><datacenter type="Datacenter">datacenter-1</datacenter></datacenter>
+            <host><name>esx01</name><host type="HostSystem">host-10</host></host>
+            <vm><name>web01</name><vm type="VirtualMachine">vm-42</vm></vm>
+            <fullFormattedMessage>web01 on esx01 in DC1 is powered on</fullFormattedMessage></returnval>
+            <returnval xsi:type="AlarmStatusChangedEvent"><key>5022</key><chainId>5022</chainId>
+            <createdTime>2021-03-04T10:16:00Z</createdTime><userName></userName>
+            <alarm><name>Host connection state</name><alarm type="Alarm">alarm-1</alarm></alarm>
+            <entity><name>esx01</name><entity type="HostSystem">host-10</entity></entity>
+            <from>green</from><to>red</to></returnval>
+            <returnval xsi:type="HostReconnectionFailedEvent"><key>5023</key><chainId>5023</chainId>
+            <createdTime>2021-03-04T10:17:00Z</createdTime><userName></userName>
+            <host><name>esx01</name><host type="HostSystem">host-10</host></host></returnval>
+            </ReadNextEventsResponse></soapenv:Body></soapenv:Envelope>"#;
+        let events: Events = parse_body(body).unwrap();
+        assert_eq!(events.events.len(), 3);
+        let powered_on = super::Event::from(events.events[0].clone());
+        assert_eq!(powered_on.kind, EventKind::VmPoweredOn);
+        let vm = powered_on.vm.unwrap();
+        assert_eq!(vm.name, "web01");
+        assert_eq!(
+            vm.reference,
+            Some(ManagedObjectReference::new("VirtualMachine", "vm-42"))
+        );
+        let alarm = super::Event::from(events.events[1].clone());
+        assert_eq!(alarm.kind, EventKind::AlarmStatusChanged);
+        assert_eq!(alarm.alarm_to.as_deref(), Some("red"));
+        assert_eq!(alarm.entity.unwrap().name, "esx01");
+        let reconnection_failed = super::Event::from(events.events[2].clone());
+        assert_eq!(reconnection_failed.kind, EventKind::HostDisconnected);
+
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><ReadNextEventsResponse xmlns="urn:vim25"/></soapenv:Body></soapenv:Envelope>"#;
+        let events: Events = parse_body(body).unwrap();
+        assert!(events.events.is_empty());
+    }
+}
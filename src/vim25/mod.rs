@@ -0,0 +1,620 @@
+// Minimal SOAP client for the vim25 API. It reuses the HTTP client of a
+// Session and can share its login with it, so calls which only exist in the
+// SOAP API can be mixed with REST calls.
+
+// Alarm definitions and triggered alarms
+pub mod alarms;
+// Roles and permissions on inventory objects
+pub mod authorization;
+// Events logged by vCenter
+pub mod event;
+// Retrieval of managed object properties
+pub mod property;
+// Waiting for tasks of the SOAP API
+pub mod task;
+
+use crate::cis::{self, DynamicId, Session};
+use chrono::prelude::*;
+use reqwest::header::{HeaderValue, CONTENT_TYPE, COOKIE, SET_COOKIE};
+use reqwest::{Request, Response};
+use serde::de::{self, DeserializeOwned, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::sync::Mutex;
+
+const SESSION_COOKIE: &str = "vmware_soap_session";
+const SDK_PATH: &str = "/sdk";
+const NAMESPACE: &str = "urn:vim25";
+
+// Vim25 module error type
+#[derive(Debug, Display, From)]
+pub enum Error {
+    #[display(fmt = "{}", _0)]
+    Cis(cis::Error),
+    #[display(fmt = "Reqwest error: {}", _0)]
+    Reqwest(reqwest::Error),
+    #[display(fmt = "XML error: {}", _0)]
+    Xml(quick_xml::DeError),
+    #[display(fmt = "SOAP fault: {}", _0)]
+    Fault(Fault),
+    #[display(fmt = "Unexpected status code: {}", _0)]
+    UnexpectedStatusCode(u16),
+    #[display(fmt = "Task failed: {}", _0)]
+    #[from(ignore)]
+    TaskFailed(String),
+    #[display(fmt = "Canceled")]
+    Canceled,
+}
+
+// Fault returned by the SOAP API instead of a response
+#[derive(Deserialize, Debug, Clone, Display)]
+#[display(fmt = "{} ({})", faultstring, faultcode)]
+pub struct Fault {
+    #[serde(default)]
+    pub faultcode: String,
+    #[serde(default)]
+    pub faultstring: String,
+    #[serde(default)]
+    detail: Option<FaultDetail>,
+}
+
+// The detail element holds the fault object, its type is e.g. NotAuthenticated
+#[derive(Deserialize, Debug, Clone)]
+struct FaultDetail {
+    #[serde(rename = "$value")]
+    fault: TypedFault,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TypedFault {
+    #[serde(rename = "@type", default)]
+    fault_type: String,
+}
+
+impl Fault {
+    // Fault for managers missing in the service content, e.g. on ESXi
+    pub(crate) fn not_supported(manager: &str) -> Self {
+        Fault {
+            faultcode: "NotSupported".to_owned(),
+            faultstring: format!("{} is not available", manager),
+            detail: None,
+        }
+    }
+
+    // Type of the fault object in the detail, e.g. "NotAuthenticated"
+    pub fn fault_type(&self) -> Option<&str> {
+        self.detail
+            .as_ref()
+            .map(|detail| detail.fault.fault_type.as_str())
+    }
+}
+
+// Reference to a managed object like a VirtualMachine or the SessionManager
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManagedObjectReference {
+    #[serde(rename = "@type")]
+    pub mo_type: String,
+    #[serde(rename = "$text")]
+    pub value: String,
+}
+
+impl ManagedObjectReference {
+    pub fn new(mo_type: impl Into<String>, value: impl Into<String>) -> Self {
+        ManagedObjectReference {
+            mo_type: mo_type.into(),
+            value: value.into(),
+        }
+    }
+}
+
+// Typed identifiers convert via DynamicId, e.g. DynamicId::from(&host).into()
+impl From<DynamicId> for ManagedObjectReference {
+    fn from(id: DynamicId) -> Self {
+        ManagedObjectReference::new(id.object_type, id.id)
+    }
+}
+
+// Polymorphic values carry xsi:type="ManagedObjectReference" besides the
+// type attribute, the XML deserializer sees both as "@type"
+impl<'de> Deserialize<'de> for ManagedObjectReference {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ReferenceVisitor)
+    }
+}
+
+struct ReferenceVisitor;
+
+impl<'de> Visitor<'de> for ReferenceVisitor {
+    type Value = ManagedObjectReference;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a managed object reference")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let (mut mo_type, mut value) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            let entry: String = map.next_value()?;
+            match key.as_str() {
+                "@type" if entry != "ManagedObjectReference" => mo_type = Some(entry),
+                "$text" | "$value" => value = Some(entry),
+                _ => {}
+            }
+        }
+        Ok(ManagedObjectReference {
+            mo_type: mo_type.ok_or_else(|| de::Error::missing_field("type"))?,
+            value: value.ok_or_else(|| de::Error::missing_field("value"))?,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AboutInfo {
+    pub name: String,
+    #[serde(default)]
+    pub full_name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub build: String,
+    #[serde(default)]
+    pub api_type: String,
+    #[serde(default)]
+    pub api_version: String,
+    pub instance_uuid: Option<String>,
+}
+
+// Entry points of the SOAP API, managers missing on ESXi are unset
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceContent {
+    pub root_folder: ManagedObjectReference,
+    pub property_collector: ManagedObjectReference,
+    pub about: AboutInfo,
+    pub session_manager: Option<ManagedObjectReference>,
+    pub search_index: Option<ManagedObjectReference>,
+    pub view_manager: Option<ManagedObjectReference>,
+    pub user_directory: Option<ManagedObjectReference>,
+    pub authorization_manager: Option<ManagedObjectReference>,
+    pub license_manager: Option<ManagedObjectReference>,
+    pub custom_fields_manager: Option<ManagedObjectReference>,
+    pub event_manager: Option<ManagedObjectReference>,
+    pub alarm_manager: Option<ManagedObjectReference>,
+    pub perf_manager: Option<ManagedObjectReference>,
+    pub virtual_disk_manager: Option<ManagedObjectReference>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSession {
+    pub key: String,
+    #[serde(default)]
+    pub user_name: String,
+    #[serde(default)]
+    pub full_name: String,
+    pub login_time: DateTime<Utc>,
+}
+
+// Request of a method without arguments
+#[derive(Serialize)]
+pub(crate) struct This<'a> {
+    #[serde(rename = "_this")]
+    pub(crate) this: &'a ManagedObjectReference,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    user_name: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Envelope<T> {
+    #[serde(rename = "Body")]
+    body: Body<T>,
+}
+
+// The element name of the body content depends on the method
+#[derive(Deserialize)]
+struct Body<T> {
+    #[serde(rename = "$value")]
+    content: T,
+}
+
+// Methods returning nothing or an empty list have no returnval
+#[derive(Deserialize)]
+struct ReturnValue<T> {
+    returnval: Option<T>,
+}
+
+// Client for the SOAP API of the host of a Session
+pub struct Client<'a> {
+    session: &'a Session,
+    version: String,
+    cookie: Mutex<Option<String>>,
+    content: Mutex<Option<ServiceContent>>,
+    // Username and password, only retained with auto relogin of the session
+    credentials: Mutex<Option<(String, String)>>,
+}
+
+impl<'a> Client<'a> {
+    pub fn new(session: &'a Session) -> Self {
+        Client {
+            session,
+            version: "7.0".to_owned(),
+            cookie: Mutex::new(None),
+            content: Mutex::new(None),
+            credentials: Mutex::new(None),
+        }
+    }
+
+    // API version sent as SOAPAction, older hosts reject newer versions
+    pub fn set_version(&mut self, version: impl Into<String>) {
+        self.version = version.into();
+    }
+
+    pub async fn service_content(&self) -> Result<ServiceContent, Error> {
+        let this = ManagedObjectReference::new("ServiceInstance", "ServiceInstance");
+        self.call("RetrieveServiceContent", &This { this: &this })
+            .await
+    }
+
+    // Service content fetched once per client
+    pub(crate) async fn cached_content(&self) -> Result<ServiceContent, Error> {
+        if let Some(content) = self.content.lock().expect("content lock").clone() {
+            return Ok(content);
+        }
+        let content = self.service_content().await?;
+        *self.content.lock().expect("content lock") = Some(content.clone());
+        Ok(content)
+    }
+
+    // Log in to the SOAP API and log the Session in with the same SOAP
+    // session. With auto relogin of the Session the credentials are retained
+    // to log in again when a call fails because the SOAP session expired.
+    pub async fn login(&self, username: &str, password: &str) -> Result<UserSession, Error> {
+        let content = self.cached_content().await?;
+        let session_manager = content.session_manager.ok_or(cis::Error::Unauthorized)?;
+        let body = self
+            .login_once(&session_manager, username, password)
+            .await?;
+        if self.session.auto_relogin() {
+            *self.credentials.lock().expect("credentials lock") =
+                Some((username.to_owned(), password.to_owned()));
+        }
+        match parse_returnval(&body)? {
+            Some(user_session) => Ok(user_session),
+            None => Err(quick_xml::DeError::Custom("missing returnval".to_owned()).into()),
+        }
+    }
+
+    // Send the login request without relogin and share the SOAP session
+    async fn login_once(
+        &self,
+        session_manager: &ManagedObjectReference,
+        username: &str,
+        password: &str,
+    ) -> Result<String, Error> {
+        let request = LoginRequest {
+            this: session_manager,
+            user_name: username,
+            password,
+        };
+        let body = self
+            .invoke_once(SDK_PATH, NAMESPACE, "Login", &request)
+            .await?;
+        let cookie = self.cookie.lock().expect("cookie lock").clone();
+        let shared = match cookie {
+            Some(cookie) => self.session.login_with_soap_session(&cookie).await?,
+            None => false,
+        };
+        if !shared {
+            return Err(cis::Error::Unauthorized.into());
+        }
+        Ok(body)
+    }
+
+    // Log in again with the retained credentials, returns false if that is not possible
+    async fn relogin(&self) -> Result<bool, Error> {
+        let credentials = self.credentials.lock().expect("credentials lock").clone();
+        let session_manager = self
+            .content
+            .lock()
+            .expect("content lock")
+            .as_ref()
+            .and_then(|content| content.session_manager.clone());
+        match (credentials, session_manager) {
+            (Some((username, password)), Some(session_manager)) => {
+                self.login_once(&session_manager, &username, &password)
+                    .await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    pub async fn logout(&self) -> Result<(), Error> {
+        let content = self.service_content().await?;
+        let session_manager = content.session_manager.ok_or(cis::Error::Unauthorized)?;
+        let request = This {
+            this: &session_manager,
+        };
+        self.call_empty("Logout", &request).await?;
+        *self.cookie.lock().expect("cookie lock") = None;
+        *self.credentials.lock().expect("credentials lock") = None;
+        Ok(())
+    }
+
+    // Invoke a method and return its returnval, the request is serialized
+    // as the method element and has to carry the "_this" reference
+    pub async fn call<T: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        request: &T,
+    ) -> Result<R, Error> {
+        match self.call_optional(method, request).await? {
+            Some(value) => Ok(value),
+            None => Err(quick_xml::DeError::Custom("missing returnval".to_owned()).into()),
+        }
+    }
+
+    // Invoke a method whose returnval may be missing, e.g. for empty results
+    pub async fn call_optional<T: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        request: &T,
+    ) -> Result<Option<R>, Error> {
+        let body = self.invoke(method, request).await?;
+        parse_returnval(&body)
+    }
+
+    // Invoke a method without return value
+    pub async fn call_empty<T: Serialize>(&self, method: &str, request: &T) -> Result<(), Error> {
+        self.invoke(method, request).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn invoke<T: Serialize>(
+        &self,
+        method: &str,
+        request: &T,
+    ) -> Result<String, Error> {
+        self.invoke_at(SDK_PATH, NAMESPACE, method, request).await
+    }
+
+    // Invoke a method of another SOAP service of the host sharing the login,
+    // e.g. the vSAN management service at /vsanHealth with namespace urn:vsan.
+    // A call which fails because the SOAP session expired is repeated once
+    // after logging in again, if the credentials were retained.
+    pub(crate) async fn invoke_at<T: Serialize>(
+        &self,
+        path: &str,
+        namespace: &str,
+        method: &str,
+        request: &T,
+    ) -> Result<String, Error> {
+        match self.invoke_once(path, namespace, method, request).await {
+            Err(Error::Fault(fault)) if fault.fault_type() == Some("NotAuthenticated") => {
+                if !self.relogin().await? {
+                    return Err(Error::Fault(fault));
+                }
+                self.invoke_once(path, namespace, method, request).await
+            }
+            result => result,
+        }
+    }
+
+    // Invoke a method with the retry policy and rate limiter of the session
+    async fn invoke_once<T: Serialize>(
+        &self,
+        path: &str,
+        namespace: &str,
+        method: &str,
+        request: &T,
+    ) -> Result<String, Error> {
+        let builder = self
+            .session
+            .client()
+            .post(&format!("https://{}{}", self.session.hostname(), path))
+            .header(CONTENT_TYPE, "text/xml; charset=utf-8")
+            .header("SOAPAction", format!("{}/{}", namespace, self.version))
+            .body(namespaced_envelope(namespace, method, request)?);
+        let resp: Response = self
+            .session
+            .execute_with(builder, |request| self.dispatch(request))
+            .await?;
+        if let Some(cookie) = resp
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .find_map(session_cookie)
+        {
+            *self.cookie.lock().expect("cookie lock") = Some(cookie);
+        }
+        let status = resp.status();
+        let body = resp.text().await?;
+        if status.is_success() {
+            return Ok(body);
+        }
+        match quick_xml::de::from_str::<Envelope<Fault>>(&body) {
+            Ok(envelope) => Err(Error::Fault(envelope.body.content)),
+            Err(_) => Err(Error::UnexpectedStatusCode(status.as_u16())),
+        }
+    }
+
+    // Send a request with the current SOAP session cookie
+    async fn dispatch(&self, mut request: Request) -> Result<Response, cis::Error> {
+        if let Some(cookie) = self.cookie.lock().expect("cookie lock").as_deref() {
+            let cookie = format!("{}=\"{}\"", SESSION_COOKIE, cookie);
+            if let Ok(value) = HeaderValue::from_str(&cookie) {
+                request.headers_mut().insert(COOKIE, value);
+            }
+        }
+        Ok(self.session.dispatch(request).await?)
+    }
+}
+
+// Envelope of a vim25 request, requests are sent with invoke_at
+#[cfg(test)]
+pub(crate) fn envelope<T: Serialize>(method: &str, request: &T) -> Result<String, Error> {
+    namespaced_envelope(NAMESPACE, method, request)
+}
+
+// Wrap a request in a SOAP envelope with the method as element name
+pub(crate) fn namespaced_envelope<T: Serialize>(
+    namespace: &str,
+    method: &str,
+    request: &T,
+) -> Result<String, Error> {
+    let mut content = String::new();
+    let serializer = quick_xml::se::Serializer::with_root(&mut content, Some(method))?;
+    request.serialize(serializer)?;
+    // The serializer has no namespace support, the method element gets it here
+    let content = format!(
+        "<{} xmlns=\"{}\"{}",
+        method,
+        namespace,
+        &content[method.len() + 1..]
+    );
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <soapenv:Envelope xmlns:soapenv=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\
+         <soapenv:Body>{}</soapenv:Body></soapenv:Envelope>",
+        content
+    ))
+}
+
+// The returnval of a response envelope
+pub(crate) fn parse_returnval<R: DeserializeOwned>(body: &str) -> Result<Option<R>, Error> {
+    let content: ReturnValue<R> = parse_body(body)?;
+    Ok(content.returnval)
+}
+
+// The content of a response envelope, e.g. for methods returning a list
+pub(crate) fn parse_body<R: DeserializeOwned>(body: &str) -> Result<R, Error> {
+    let envelope: Envelope<R> = quick_xml::de::from_str(body)?;
+    Ok(envelope.body.content)
+}
+
+// Value of the SOAP session cookie without quotes, if the header sets it
+fn session_cookie(header: &HeaderValue) -> Option<String> {
+    let cookie = header.to_str().ok()?.split(';').next()?;
+    let mut parts = cookie.splitn(2, '=');
+    match (parts.next()?.trim(), parts.next()) {
+        (SESSION_COOKIE, Some(value)) => Some(value.trim().trim_matches('"').to_owned()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cis::retry::RetryPolicy;
+    use crate::cis::transport::MockTransport;
+    use reqwest::{Method, StatusCode};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn login_envelope() {
+        let this = ManagedObjectReference::new("SessionManager", "SessionManager");
+        let request = LoginRequest {
+            this: &this,
+            user_name: "administrator@vsphere.local",
+            password: "a<b",
+        };
+        let envelope = envelope("Login", &request).unwrap();
+        assert!(envelope.contains(
+            "<soapenv:Body><Login xmlns=\"urn:vim25\">\
+             <_this type=\"SessionManager\">SessionManager</_this>\
+             <userName>administrator@vsphere.local</userName>\
+             <password>a&lt;b</password></Login></soapenv:Body>"
+        ));
+    }
+
+    #[test]
+    fn parse_response_and_fault() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><RetrieveServiceContentResponse xmlns="urn:vim25"><returnval>
+            <rootFolder type="Folder">group-d1</rootFolder>
+            <propertyCollector type="PropertyCollector">propertyCollector</propertyCollector>
+            <about><name>VMware vCenter Server</name><fullName>VMware vCenter Server 7.0.3</fullName>
+            <version>7.0.3</version><build>20395099</build><apiType>VirtualCenter</apiType>
+            <apiVersion>7.0.3.0</apiVersion></about>
+            <sessionManager type="SessionManager">SessionManager</sessionManager>
+            </returnval></RetrieveServiceContentResponse></soapenv:Body></soapenv:Envelope>"#;
+        let envelope: Envelope<ReturnValue<ServiceContent>> =
+            quick_xml::de::from_str(body).unwrap();
+        let content = envelope.body.content.returnval.unwrap();
+        assert_eq!(
+            content.root_folder,
+            ManagedObjectReference::new("Folder", "group-d1")
+        );
+        assert_eq!(content.about.api_type, "VirtualCenter");
+        assert!(content.perf_manager.is_none());
+
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><soapenv:Fault><faultcode>ServerFaultCode</faultcode>
+            <faultstring>Cannot complete login due to an incorrect user name or password.</faultstring>
+            </soapenv:Fault></soapenv:Body></soapenv:Envelope>"#;
+        let envelope: Envelope<Fault> = quick_xml::de::from_str(body).unwrap();
+        assert_eq!(envelope.body.content.faultcode, "ServerFaultCode");
+        assert_eq!(envelope.body.content.fault_type(), None);
+
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><soapenv:Fault><faultcode>ServerFaultCode</faultcode>
+            <faultstring>The session is not authenticated.</faultstring>
+            <detail><NotAuthenticatedFault xmlns="urn:vim25" xsi:type="NotAuthenticated">
+            <object type="Folder">group-d1</object><privilegeId>System.View</privilegeId>
+            </NotAuthenticatedFault></detail></soapenv:Fault></soapenv:Body></soapenv:Envelope>"#;
+        let envelope: Envelope<Fault> = quick_xml::de::from_str(body).unwrap();
+        assert_eq!(envelope.body.content.fault_type(), Some("NotAuthenticated"));
+    }
+
+    #[tokio::test]
+    async fn calls_are_retried() {
+        let mock = Arc::new(MockTransport::new());
+        mock.once(Method::POST, "/sdk", StatusCode::SERVICE_UNAVAILABLE, "");
+        mock.on(
+            Method::POST,
+            "/sdk",
+            StatusCode::OK,
+            r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><RetrieveServiceContentResponse xmlns="urn:vim25"><returnval>
+            <rootFolder type="Folder">group-d1</rootFolder>
+            <propertyCollector type="PropertyCollector">propertyCollector</propertyCollector>
+            <about><name>VMware vCenter Server</name><apiType>VirtualCenter</apiType></about>
+            </returnval></RetrieveServiceContentResponse></soapenv:Body></soapenv:Envelope>"#,
+        );
+        let mut session = Session::builder("vcenter.test")
+            .transport(mock.clone())
+            .build()
+            .expect("build");
+        session.set_retry_policy(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        });
+        let content = Client::new(&session)
+            .service_content()
+            .await
+            .expect("service content");
+        assert_eq!(content.about.api_type, "VirtualCenter");
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[test]
+    fn session_cookie_value() {
+        let header =
+            HeaderValue::from_static("vmware_soap_session=\"52a1b2c3\"; Path=/; HttpOnly; Secure;");
+        assert_eq!(session_cookie(&header).as_deref(), Some("52a1b2c3"));
+        let header = HeaderValue::from_static("other=1; Path=/");
+        assert_eq!(session_cookie(&header), None);
+    }
+}
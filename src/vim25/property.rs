@@ -0,0 +1,131 @@
+use super::{Client, Error, ManagedObjectReference};
+use serde::de::DeserializeOwned;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PropertySpec<'a> {
+    #[serde(rename = "type")]
+    object_type: &'a str,
+    path_set: &'a str,
+}
+
+#[derive(Serialize)]
+struct ObjectSpec<'a> {
+    obj: &'a ManagedObjectReference,
+    skip: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PropertyFilterSpec<'a> {
+    prop_set: PropertySpec<'a>,
+    object_set: ObjectSpec<'a>,
+}
+
+#[derive(Serialize)]
+struct RetrieveOptions {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RetrieveRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    spec_set: PropertyFilterSpec<'a>,
+    options: RetrieveOptions,
+}
+
+#[derive(Deserialize)]
+struct RetrieveResult<T> {
+    #[serde(default = "Vec::new")]
+    objects: Vec<ObjectContent<T>>,
+}
+
+#[derive(Deserialize)]
+struct ObjectContent<T> {
+    #[serde(default = "Vec::new", rename = "propSet")]
+    prop_set: Vec<DynamicProperty<T>>,
+}
+
+#[derive(Deserialize)]
+struct DynamicProperty<T> {
+    val: T,
+}
+
+impl Client<'_> {
+    // Value of a property of a managed object like "snapshot" of a
+    // VirtualMachine, unset properties are None
+    pub async fn retrieve_property<T: DeserializeOwned>(
+        &self,
+        obj: &ManagedObjectReference,
+        path: &str,
+    ) -> Result<Option<T>, Error> {
+        let content = self.cached_content().await?;
+        let request = retrieve_request(&content.property_collector, obj, path);
+        let result: Option<RetrieveResult<T>> =
+            self.call_optional("RetrievePropertiesEx", &request).await?;
+        Ok(result.and_then(first_value))
+    }
+}
+
+fn retrieve_request<'a>(
+    collector: &'a ManagedObjectReference,
+    obj: &'a ManagedObjectReference,
+    path: &'a str,
+) -> RetrieveRequest<'a> {
+    RetrieveRequest {
+        this: collector,
+        spec_set: PropertyFilterSpec {
+            prop_set: PropertySpec {
+                object_type: &obj.mo_type,
+                path_set: path,
+            },
+            object_set: ObjectSpec { obj, skip: false },
+        },
+        options: RetrieveOptions {},
+    }
+}
+
+fn first_value<T>(result: RetrieveResult<T>) -> Option<T> {
+    let object = result.objects.into_iter().next()?;
+    object
+        .prop_set
+        .into_iter()
+        .next()
+        .map(|property| property.val)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vim25::{envelope, parse_returnval};
+
+    #[test]
+    fn retrieve_envelope() {
+        let collector = ManagedObjectReference::new("PropertyCollector", "propertyCollector");
+        let vm = ManagedObjectReference::new("VirtualMachine", "vm-42");
+        let request = retrieve_request(&collector, &vm, "runtime.powerState");
+        let envelope = envelope("RetrievePropertiesEx", &request).unwrap();
+        assert!(envelope.contains(
+            "<specSet><propSet><type>VirtualMachine</type><pathSet>runtime.powerState</pathSet>\
+             </propSet><objectSet><obj type=\"VirtualMachine\">vm-42</obj><skip>false</skip>\
+             </objectSet></specSet><options/>"
+        ));
+    }
+
+    #[test]
+    fn parse_property() {
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><RetrievePropertiesExResponse xmlns="urn:vim25"><returnval><objects>
+            <obj type="VirtualMachine">vm-42</obj><propSet><name>runtime.powerState</name>
+            <val xsi:type="VirtualMachinePowerState">poweredOn</val></propSet>
+            </objects></returnval></RetrievePropertiesExResponse></soapenv:Body></soapenv:Envelope>"#;
+        let result: Option<RetrieveResult<String>> = parse_returnval(body).unwrap();
+        assert_eq!(result.and_then(first_value).as_deref(), Some("poweredOn"));
+
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><RetrievePropertiesExResponse xmlns="urn:vim25"/>
+            </soapenv:Body></soapenv:Envelope>"#;
+        let result: Option<RetrieveResult<String>> = parse_returnval(body).unwrap();
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use super::{Client, Error, ManagedObjectReference, This};
+use crate::cancel::CancelHandle;
+use futures::future::{self, Either};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskState {
+    Queued,
+    Running,
+    Success,
+    Error,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedMethodFault {
+    pub localized_message: Option<String>,
+}
+
+// State of a task, the result type depends on the method which started it
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo<R> {
+    pub key: String,
+    pub state: TaskState,
+    pub progress: Option<u32>,
+    pub result: Option<R>,
+    pub error: Option<LocalizedMethodFault>,
+}
+
+impl Client<'_> {
+    pub async fn task_info<R: DeserializeOwned>(
+        &self,
+        task: &ManagedObjectReference,
+    ) -> Result<TaskInfo<R>, Error> {
+        self.retrieve_property(task, "info")
+            .await?
+            .ok_or_else(|| Error::TaskFailed(format!("{} has no info", task.value)))
+    }
+
+    // Poll a task until it completes and return its result, failed tasks
+    // return Error::TaskFailed with the localized message. Dropping the
+    // future stops the polling but not the task, see wait_for_task_with_cancel.
+    pub async fn wait_for_task<R: DeserializeOwned>(
+        &self,
+        task: &ManagedObjectReference,
+        poll_interval: Duration,
+    ) -> Result<Option<R>, Error> {
+        self.wait_for_task_with_cancel(task, poll_interval, &CancelHandle::new())
+            .await
+    }
+
+    // Like wait_for_task, when the handle is canceled the task is canceled
+    // too and the wait fails with Error::Canceled
+    pub async fn wait_for_task_with_cancel<R: DeserializeOwned>(
+        &self,
+        task: &ManagedObjectReference,
+        poll_interval: Duration,
+        cancel_handle: &CancelHandle,
+    ) -> Result<Option<R>, Error> {
+        loop {
+            let info: TaskInfo<R> = self.task_info(task).await?;
+            match info.state {
+                TaskState::Success => return Ok(info.result),
+                TaskState::Error => {
+                    let message = info
+                        .error
+                        .and_then(|error| error.localized_message)
+                        .unwrap_or_else(|| format!("{} failed", task.value));
+                    return Err(Error::TaskFailed(message));
+                }
+                // Unknown states are polled until they turn into a known one
+                TaskState::Queued | TaskState::Running | TaskState::Unknown => {}
+            }
+            let delay = tokio::time::delay_for(poll_interval);
+            let canceled = cancel_handle.canceled();
+            futures::pin_mut!(canceled);
+            if let Either::Right(_) = future::select(delay, canceled).await {
+                // Best effort, fails for tasks which aren't cancelable or
+                // finished in the meantime
+                let _ = self.cancel_task(task).await;
+                return Err(Error::Canceled);
+            }
+        }
+    }
+
+    pub async fn cancel_task(&self, task: &ManagedObjectReference) -> Result<(), Error> {
+        self.call_empty("CancelTask", &This { this: task }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TaskInfo, TaskState};
+    use crate::vim25::ManagedObjectReference;
+
+    #[test]
+    fn deserialize_task_info() {
+        let xml = r#"<val xsi:type="TaskInfo"><key>task-101</key>
+            <task type="Task">task-101</task><descriptionId>VirtualMachine.createSnapshot</descriptionId>
+            <state>success</state><cancelled>false</cancelled>
+            <result type="VirtualMachineSnapshot" xsi:type="ManagedObjectReference">snapshot-7</result>
+            </val>"#;
+        let info: TaskInfo<ManagedObjectReference> = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(info.state, TaskState::Success);
+        assert_eq!(
+            info.result,
+            Some(ManagedObjectReference::new(
+                "VirtualMachineSnapshot",
+                "snapshot-7"
+            ))
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// Cluster health, capacity and resync status of vSAN. The vSAN management
+// API is not part of the REST API, vCenter serves it as SOAP service at
+// /vsanHealth which shares the login of a vim25 Client.
+use crate::id::ClusterId;
+use crate::vim25::{parse_returnval, Client, Error, ManagedObjectReference};
+use chrono::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const PATH: &str = "/vsanHealth";
+const NAMESPACE: &str = "urn:vsan";
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    Green,
+    Yellow,
+    Red,
+    Info,
+    Skipped,
+    #[cfg_attr(not(feature = "strict"), serde(other))]
+    Unknown,
+}
+
+// Result of a group of health checks like "network" or "data"
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthGroup {
+    #[serde(default)]
+    pub group_id: String,
+    #[serde(default)]
+    pub group_name: String,
+    pub group_health: HealthStatus,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthSummary {
+    pub overall_health: HealthStatus,
+    pub overall_health_description: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub groups: Vec<HealthGroup>,
+}
+
+// Capacity of the vSAN datastore of a cluster in bytes
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceUsage {
+    #[serde(default, rename = "totalCapacityB")]
+    pub total_capacity: i64,
+    #[serde(rename = "freeCapacityB")]
+    pub free_capacity: Option<i64>,
+}
+
+impl SpaceUsage {
+    pub fn used_capacity(&self) -> Option<i64> {
+        self.free_capacity.map(|free| self.total_capacity - free)
+    }
+}
+
+// Objects which are resynchronized, e.g. after a host left maintenance
+// mode, the ETA is in seconds
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResyncSummary {
+    #[serde(default)]
+    pub total_objects_to_sync: i64,
+    #[serde(default)]
+    pub total_bytes_to_sync: i64,
+    #[serde(rename = "totalRecoveryETA", default)]
+    pub total_recovery_eta: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthSummaryRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    cluster: &'a ManagedObjectReference,
+    include_obj_uuids: bool,
+    fetch_from_cache: bool,
+}
+
+#[derive(Serialize)]
+struct ClusterRequest<'a> {
+    #[serde(rename = "_this")]
+    this: &'a ManagedObjectReference,
+    cluster: &'a ManagedObjectReference,
+}
+
+// Health summary of the cluster, with fetch_from_cache the result of the
+// last run of the health checks is returned instead of running them
+pub async fn health_summary(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+    fetch_from_cache: bool,
+) -> Result<HealthSummary, Error> {
+    let this =
+        ManagedObjectReference::new("VsanVcClusterHealthSystem", "vsan-cluster-health-system");
+    let request = HealthSummaryRequest {
+        this: &this,
+        cluster: &cluster_reference(cluster),
+        include_obj_uuids: false,
+        fetch_from_cache,
+    };
+    call(client, "VsanQueryVcClusterHealthSummary", &request).await
+}
+
+pub async fn space_usage(client: &Client<'_>, cluster: &ClusterId) -> Result<SpaceUsage, Error> {
+    let this =
+        ManagedObjectReference::new("VsanSpaceReportSystem", "vsan-cluster-space-report-system");
+    let request = ClusterRequest {
+        this: &this,
+        cluster: &cluster_reference(cluster),
+    };
+    call(client, "VsanQuerySpaceUsage", &request).await
+}
+
+pub async fn resync_summary(
+    client: &Client<'_>,
+    cluster: &ClusterId,
+) -> Result<ResyncSummary, Error> {
+    let this = ManagedObjectReference::new("VsanObjectSystem", "vsan-cluster-object-system");
+    let request = ClusterRequest {
+        this: &this,
+        cluster: &cluster_reference(cluster),
+    };
+    let body = client
+        .invoke_at(
+            PATH,
+            NAMESPACE,
+            "VsanQuerySyncingVsanObjectsSummary",
+            &request,
+        )
+        .await?;
+    // Clusters without resync return an empty result
+    Ok(parse_returnval(&body)?.unwrap_or_default())
+}
+
+async fn call<T: Serialize, R: DeserializeOwned>(
+    client: &Client<'_>,
+    method: &str,
+    request: &T,
+) -> Result<R, Error> {
+    let body = client.invoke_at(PATH, NAMESPACE, method, request).await?;
+    match parse_returnval(&body)? {
+        Some(content) => Ok(content),
+        None => Err(quick_xml::DeError::Custom("missing returnval".to_owned()).into()),
+    }
+}
+
+fn cluster_reference(cluster: &ClusterId) -> ManagedObjectReference {
+    ManagedObjectReference::new(cluster.object_type(), cluster.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cluster_reference, HealthStatus, HealthSummary, HealthSummaryRequest, ResyncSummary,
+        NAMESPACE,
+    };
+    use crate::id::ClusterId;
+    use crate::vim25::{namespaced_envelope, parse_returnval, ManagedObjectReference};
+
+    #[test]
+    fn health_summary_envelope() {
+        let this =
+            ManagedObjectReference::new("VsanVcClusterHealthSystem", "vsan-cluster-health-system");
+        let request = HealthSummaryRequest {
+            this: &this,
+            cluster: &cluster_reference(&ClusterId::new("domain-c8")),
+            include_obj_uuids: false,
+            fetch_from_cache: true,
+        };
+        let envelope =
+            namespaced_envelope(NAMESPACE, "VsanQueryVcClusterHealthSummary", &request).unwrap();
+        assert!(envelope.contains(
+            "<VsanQueryVcClusterHealthSummary xmlns=\"urn:vsan\">\
+             <_this type=\"VsanVcClusterHealthSystem\">vsan-cluster-health-system</_this>\
+             <cluster type=\"ClusterComputeResource\">domain-c8</cluster>\
+             <includeObjUuids>false</includeObjUuids><fetchFromCache>true</fetchFromCache>"
+        ));
+    }
+
+    #[test]
+    fn parse_health_and_resync() {
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><VsanQueryVcClusterHealthSummaryResponse xmlns="urn:vsan"><returnval>
+            <timestamp>2021-03-04T10:00:00Z</timestamp><overallHealth>yellow</overallHealth>
+            <overallHealthDescription>Warnings in network</overallHealthDescription>
+            <groups><groupId>com.vmware.vsan.health.test.network</groupId><groupName>Network</groupName>
+            <groupHealth>yellow</groupHealth></groups>
+            <groups><groupId>com.vmware.vsan.health.test.data</groupId><groupName>Data</groupName>
+            <groupHealth>green</groupHealth></groups>
+            </returnval></VsanQueryVcClusterHealthSummaryResponse></soapenv:Body></soapenv:Envelope>"#;
+        let summary: HealthSummary = parse_returnval(body).unwrap().unwrap();
+        assert_eq!(summary.overall_health, HealthStatus::Yellow);
+        assert_eq!(summary.groups.len(), 2);
+        assert_eq!(summary.groups[1].group_health, HealthStatus::Green);
+
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+            <soapenv:Body><VsanQuerySyncingVsanObjectsSummaryResponse xmlns="urn:vsan"><returnval>
+            <totalObjectsToSync>12</totalObjectsToSync><totalBytesToSync>53687091200</totalBytesToSync>
+            <totalRecoveryETA>840</totalRecoveryETA></returnval>
+            </VsanQuerySyncingVsanObjectsSummaryResponse></soapenv:Body></soapenv:Envelope>"#;
+        let resync: ResyncSummary = parse_returnval(body).unwrap().unwrap();
+        assert_eq!(resync.total_objects_to_sync, 12);
+        assert_eq!(resync.total_recovery_eta, 840);
+    }
+}